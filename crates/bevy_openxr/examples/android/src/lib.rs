@@ -1,6 +1,7 @@
 //! A simple 3D scene with light shining over a cube sitting on a plane.
 
 use bevy::prelude::*;
+use bevy::render::render_resource::TextureUsages;
 use bevy_mod_openxr::{add_xr_plugins, init::OxrInitPlugin, types::OxrExtensions};
 
 #[bevy_main]
@@ -14,11 +15,19 @@ fn main() {
                 exts.enable_hand_tracking();
                 exts
             },
+            required_exts: default(),
             blend_modes: default(),
+            view_configuration_types: default(),
             backends: default(),
             formats: default(),
             resolutions: default(),
+            msaa: default(),
+            swapchain_usage: TextureUsages::RENDER_ATTACHMENT
+                | TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_SRC,
+            device_descriptor: default(),
             synchronous_pipeline_compilation: default(),
+            overlay: default(),
         }))
         .add_plugins(bevy_xr_utils::hand_gizmos::HandGizmosPlugin)
         .add_systems(Startup, setup)
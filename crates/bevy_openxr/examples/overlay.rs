@@ -2,7 +2,9 @@
 
 use bevy::prelude::*;
 use bevy_mod_openxr::{
-    add_xr_plugins, features::overlay::OxrOverlaySessionEvent, init::OxrInitPlugin,
+    add_xr_plugins,
+    features::overlay::{OxrOverlaySessionEvent, OxrOverlaySettings},
+    init::OxrInitPlugin,
     types::OxrExtensions,
 };
 use openxr::EnvironmentBlendMode;
@@ -13,9 +15,9 @@ fn main() {
             exts: {
                 let mut exts = OxrExtensions::default();
                 exts.enable_hand_tracking();
-                exts.extx_overlay = true;
                 exts
             },
+            overlay: Some(OxrOverlaySettings::default()),
             blend_modes: Some({
                 vec![
                     EnvironmentBlendMode::ALPHA_BLEND,
@@ -8,16 +8,32 @@ use bevy::utils::HashMap;
 use bevy_mod_xr::session::XrSessionCreatedEvent;
 use openxr::sys::ActionSuggestedBinding;
 
+use crate::action_set_syncing::OxrActionSetSyncSet;
+use crate::error::OxrBindingError;
+use crate::init::OxrInteractionProfileChanged;
+use crate::openxr_session_running;
 use crate::resources::OxrInstance;
+use crate::session::OxrSession;
+use crate::spaces::get_arr_init;
 
 impl Plugin for OxrActionBindingPlugin {
     fn build(&self, app: &mut App) {
         app.add_schedule(Schedule::new(OxrSendActionBindings));
         app.add_event::<OxrSuggestActionBinding>();
+        app.add_event::<OxrBoundSourcesChanged>();
+        app.init_resource::<OxrActionBoundSources>();
+        app.init_resource::<OxrSimpleControllerFallback>();
         app.add_systems(
             Update,
             run_action_binding_sugestion.run_if(on_event::<XrSessionCreatedEvent>),
         );
+        app.add_systems(
+            PreUpdate,
+            update_bound_sources
+                .after(OxrActionSetSyncSet)
+                .run_if(on_event::<OxrInteractionProfileChanged>)
+                .run_if(openxr_session_running),
+        );
     }
 }
 
@@ -28,9 +44,20 @@ pub(crate) fn run_action_binding_sugestion(world: &mut World) {
     _ = world.run_system_once(bind_actions);
 }
 
-fn bind_actions(instance: Res<OxrInstance>, mut actions: EventReader<OxrSuggestActionBinding>) {
+fn bind_actions(
+    instance: Res<OxrInstance>,
+    mut actions: EventReader<OxrSuggestActionBinding>,
+    mut bound_sources: ResMut<OxrActionBoundSources>,
+    fallback: Res<OxrSimpleControllerFallback>,
+) {
+    let mut suggestions = actions.read().cloned().collect::<Vec<_>>();
+    if fallback.0 {
+        let fallback = simple_controller_fallback(&suggestions);
+        suggestions.extend(fallback);
+    }
     let mut bindings: HashMap<&str, Vec<ActionSuggestedBinding>> = HashMap::new();
-    for e in actions.read() {
+    for e in suggestions.iter() {
+        bound_sources.0.entry(e.action).or_default();
         bindings.entry(&e.interaction_profile).or_default().extend(
             e.bindings
                 .clone()
@@ -88,6 +115,439 @@ fn bind_actions(instance: Res<OxrInstance>, mut actions: EventReader<OxrSuggestA
     }
 }
 
+const SIMPLE_CONTROLLER: &str = "/interaction_profiles/khr/simple_controller";
+
+/// Whether bindings for the `/interaction_profiles/khr/simple_controller` are derived from the bindings
+/// suggested for other interaction profiles, so every runtime has at least select, menu and the poses bound,
+/// even for controllers the app doesn't know about.
+///
+/// Triggers map to `select/click`, and the `menu/click`, `grip/pose`, `aim/pose` and haptic bindings are kept.
+/// Actions with bindings suggested for the simple controller are left alone. Enabled by default,
+/// insert `OxrSimpleControllerFallback(false)` before the session is created to opt out.
+#[derive(Resource, Clone, Copy, Debug, Deref, DerefMut)]
+pub struct OxrSimpleControllerFallback(pub bool);
+
+impl Default for OxrSimpleControllerFallback {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Derives the simple controller bindings of every action without any for it.
+fn simple_controller_fallback(
+    suggestions: &[OxrSuggestActionBinding],
+) -> Vec<OxrSuggestActionBinding> {
+    let mut fallback: Vec<OxrSuggestActionBinding> = Vec::new();
+    for e in suggestions {
+        if e.interaction_profile == SIMPLE_CONTROLLER
+            || suggestions
+                .iter()
+                .any(|s| s.action == e.action && s.interaction_profile == SIMPLE_CONTROLLER)
+        {
+            continue;
+        }
+        for binding in e.bindings.iter() {
+            let Some(path) = simple_controller_path(binding) else {
+                continue;
+            };
+            match fallback.iter_mut().find(|f| f.action == e.action) {
+                Some(f) if f.bindings.contains(&path) => {}
+                Some(f) => f.bindings.push(path),
+                None => fallback.push(OxrSuggestActionBinding {
+                    action: e.action,
+                    interaction_profile: SIMPLE_CONTROLLER.into(),
+                    bindings: vec![path],
+                }),
+            }
+        }
+    }
+    fallback
+}
+
+/// Maps a binding path of another controller to the simple controller.
+fn simple_controller_path(path: &str) -> Option<Cow<'static, str>> {
+    let (hand, component) = HAND_PATHS
+        .iter()
+        .find_map(|hand| Some((*hand, path.strip_prefix(hand)?)))?;
+    let component = match component {
+        "/input/trigger"
+        | "/input/trigger/value"
+        | "/input/trigger/click"
+        | "/input/select"
+        | "/input/select/click" => "/input/select/click",
+        "/input/menu" | "/input/menu/click" => "/input/menu/click",
+        "/input/grip/pose" | "/input/aim/pose" | "/output/haptic" => component,
+        _ => return None,
+    };
+    Some(format!("{hand}{component}").into())
+}
+
+impl OxrInstance {
+    /// Starts building the suggested bindings for an interaction profile,
+    /// e.g. `/interaction_profiles/oculus/touch_controller`.
+    pub fn bindings_builder(
+        &self,
+        interaction_profile: impl Into<String>,
+    ) -> OxrBindingsBuilder<'_> {
+        OxrBindingsBuilder {
+            instance: self,
+            interaction_profile: interaction_profile.into(),
+            bindings: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+}
+
+/// Collects the suggested bindings for one interaction profile and validates them before they are suggested.
+///
+/// The binding paths of the interaction profiles defined by the core specification are checked
+/// against the inputs and outputs of the profile, which the runtime would otherwise reject with `ERROR_PATH_UNSUPPORTED`.
+/// Paths of other interaction profiles are only checked for being valid paths.
+///
+/// ```ignore
+/// instance
+///     .bindings_builder("/interaction_profiles/khr/simple_controller")
+///     .binding(&select_action, "/user/hand/left/input/select/click")
+///     .binding(&select_action, "/user/hand/right/input/select/click")
+///     .suggest()?;
+/// ```
+pub struct OxrBindingsBuilder<'a> {
+    instance: &'a OxrInstance,
+    interaction_profile: String,
+    bindings: Vec<ActionSuggestedBinding>,
+    errors: Vec<OxrBindingError>,
+}
+
+impl OxrBindingsBuilder<'_> {
+    /// Binds `action` to `path`, e.g. `/user/hand/left/input/trigger/value`.
+    pub fn binding<T: openxr::ActionTy>(self, action: &openxr::Action<T>, path: &str) -> Self {
+        self.raw_binding(action.as_raw(), path)
+    }
+
+    /// Like [`binding`](Self::binding), but for a raw action handle.
+    pub fn raw_binding(mut self, action: openxr::sys::Action, path: &str) -> Self {
+        if known_profile_paths(&self.interaction_profile)
+            .is_some_and(|known| !is_known_path(known, path))
+        {
+            self.errors.push(OxrBindingError::UnsupportedPath {
+                profile: self.interaction_profile.clone(),
+                path: path.to_string(),
+            });
+            return self;
+        }
+        match self.instance.string_to_path(path) {
+            Ok(binding) => self
+                .bindings
+                .push(ActionSuggestedBinding { action, binding }),
+            Err(error) => self.errors.push(OxrBindingError::InvalidPath {
+                path: path.to_string(),
+                error,
+            }),
+        }
+        self
+    }
+
+    /// Suggests all valid bindings in a single `xrSuggestInteractionProfileBindings` call.
+    ///
+    /// Returns every invalid binding that was skipped, and the error of the call itself if it failed.
+    pub fn suggest(mut self) -> Result<(), Vec<OxrBindingError>> {
+        let interaction_profile = match self.instance.string_to_path(&self.interaction_profile) {
+            Ok(path) => path,
+            Err(error) => {
+                self.errors
+                    .push(OxrBindingError::InvalidInteractionProfile {
+                        path: self.interaction_profile,
+                        error,
+                    });
+                return Err(self.errors);
+            }
+        };
+        if !self.bindings.is_empty() {
+            let info = openxr::sys::InteractionProfileSuggestedBinding {
+                ty: openxr::sys::InteractionProfileSuggestedBinding::TYPE,
+                next: ptr::null(),
+                interaction_profile,
+                count_suggested_bindings: self.bindings.len() as u32,
+                suggested_bindings: self.bindings.as_ptr(),
+            };
+            let result = unsafe {
+                (self.instance.fp().suggest_interaction_profile_bindings)(
+                    self.instance.as_raw(),
+                    &info,
+                )
+            };
+            if result.into_raw() < 0 {
+                self.errors.push(OxrBindingError::SuggestFailed {
+                    profile: self.interaction_profile,
+                    error: result,
+                });
+            }
+        }
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+const HAND_PATHS: [&str; 2] = ["/user/hand/left", "/user/hand/right"];
+
+/// Top level user paths with the input and output paths they have.
+type ProfilePaths = &'static [(&'static [&'static str], &'static [&'static str])];
+
+/// Returns the paths of the interaction profiles of the core specification.
+fn known_profile_paths(profile: &str) -> Option<ProfilePaths> {
+    Some(match profile {
+        "/interaction_profiles/khr/simple_controller" => &[(
+            &HAND_PATHS,
+            &[
+                "/input/select/click",
+                "/input/menu/click",
+                "/input/grip/pose",
+                "/input/aim/pose",
+                "/output/haptic",
+            ],
+        )],
+        "/interaction_profiles/oculus/touch_controller" => &[
+            (
+                &HAND_PATHS,
+                &[
+                    "/input/squeeze/value",
+                    "/input/trigger/value",
+                    "/input/trigger/touch",
+                    "/input/thumbstick/x",
+                    "/input/thumbstick/y",
+                    "/input/thumbstick/click",
+                    "/input/thumbstick/touch",
+                    "/input/thumbrest/touch",
+                    "/input/grip/pose",
+                    "/input/aim/pose",
+                    "/output/haptic",
+                ],
+            ),
+            (
+                &["/user/hand/left"],
+                &[
+                    "/input/x/click",
+                    "/input/x/touch",
+                    "/input/y/click",
+                    "/input/y/touch",
+                    "/input/menu/click",
+                ],
+            ),
+            (
+                &["/user/hand/right"],
+                &[
+                    "/input/a/click",
+                    "/input/a/touch",
+                    "/input/b/click",
+                    "/input/b/touch",
+                    "/input/system/click",
+                ],
+            ),
+        ],
+        "/interaction_profiles/valve/index_controller" => &[(
+            &HAND_PATHS,
+            &[
+                "/input/system/click",
+                "/input/system/touch",
+                "/input/a/click",
+                "/input/a/touch",
+                "/input/b/click",
+                "/input/b/touch",
+                "/input/squeeze/value",
+                "/input/squeeze/force",
+                "/input/trigger/click",
+                "/input/trigger/value",
+                "/input/trigger/touch",
+                "/input/thumbstick/x",
+                "/input/thumbstick/y",
+                "/input/thumbstick/click",
+                "/input/thumbstick/touch",
+                "/input/trackpad/x",
+                "/input/trackpad/y",
+                "/input/trackpad/force",
+                "/input/trackpad/touch",
+                "/input/grip/pose",
+                "/input/aim/pose",
+                "/output/haptic",
+            ],
+        )],
+        "/interaction_profiles/htc/vive_controller" => &[(
+            &HAND_PATHS,
+            &[
+                "/input/system/click",
+                "/input/squeeze/click",
+                "/input/menu/click",
+                "/input/trigger/click",
+                "/input/trigger/value",
+                "/input/trackpad/x",
+                "/input/trackpad/y",
+                "/input/trackpad/click",
+                "/input/trackpad/touch",
+                "/input/grip/pose",
+                "/input/aim/pose",
+                "/output/haptic",
+            ],
+        )],
+        "/interaction_profiles/microsoft/motion_controller" => &[(
+            &HAND_PATHS,
+            &[
+                "/input/menu/click",
+                "/input/squeeze/click",
+                "/input/trigger/value",
+                "/input/thumbstick/x",
+                "/input/thumbstick/y",
+                "/input/thumbstick/click",
+                "/input/trackpad/x",
+                "/input/trackpad/y",
+                "/input/trackpad/click",
+                "/input/trackpad/touch",
+                "/input/grip/pose",
+                "/input/aim/pose",
+                "/output/haptic",
+            ],
+        )],
+        _ => return None,
+    })
+}
+
+/// Paths can also name an input without its component, e.g. `/input/thumbstick` for a 2d vector,
+/// which the runtime maps to the components.
+fn is_known_path(known: ProfilePaths, path: &str) -> bool {
+    known.iter().any(|(user_paths, components)| {
+        user_paths.iter().any(|user_path| {
+            path.strip_prefix(user_path).is_some_and(|component| {
+                !component.is_empty()
+                    && components.iter().any(|known| {
+                        known
+                            .strip_prefix(component)
+                            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+                    })
+            })
+        })
+    })
+}
+
+fn update_bound_sources(
+    session: Res<OxrSession>,
+    instance: Res<OxrInstance>,
+    mut bound_sources: ResMut<OxrActionBoundSources>,
+    mut changed: EventWriter<OxrBoundSourcesChanged>,
+) {
+    for (action, sources) in bound_sources.0.iter_mut() {
+        let paths = match session.enumerate_bound_sources(*action) {
+            Ok(paths) => paths,
+            Err(err) => {
+                warn!("Unable to enumerate bound sources for action: {}", err);
+                continue;
+            }
+        };
+        *sources = paths
+            .into_iter()
+            .map(|path| OxrBoundSource {
+                path: instance.path_to_string(path).unwrap_or_default(),
+                localized_name: session
+                    .input_source_localized_name(
+                        path,
+                        openxr::sys::InputSourceLocalizedNameFlags::USER_PATH
+                            | openxr::sys::InputSourceLocalizedNameFlags::COMPONENT,
+                    )
+                    .unwrap_or_default(),
+            })
+            .collect();
+    }
+    changed.send_default();
+}
+
+impl OxrSession {
+    /// Returns the input source paths the given action is currently bound to.
+    ///
+    /// These can change whenever the interaction profile changes.
+    pub fn enumerate_bound_sources(
+        &self,
+        action: openxr::sys::Action,
+    ) -> openxr::Result<Vec<openxr::Path>> {
+        let info = openxr::sys::BoundSourcesForActionEnumerateInfo {
+            ty: openxr::sys::BoundSourcesForActionEnumerateInfo::TYPE,
+            next: ptr::null(),
+            action,
+        };
+        get_arr_init(openxr::Path::NULL, |capacity, count, buf| unsafe {
+            (self.instance().fp().enumerate_bound_sources_for_action)(
+                self.as_raw(),
+                &info,
+                capacity,
+                count,
+                buf,
+            )
+        })
+    }
+
+    /// Returns a name for an input source path that can be shown to the user, e.g. "Left Hand Trigger".
+    pub fn input_source_localized_name(
+        &self,
+        source_path: openxr::Path,
+        which_components: openxr::sys::InputSourceLocalizedNameFlags,
+    ) -> openxr::Result<String> {
+        let info = openxr::sys::InputSourceLocalizedNameGetInfo {
+            ty: openxr::sys::InputSourceLocalizedNameGetInfo::TYPE,
+            next: ptr::null(),
+            source_path,
+            which_components,
+        };
+        let name = get_arr_init(0 as std::ffi::c_char, |capacity, count, buf| unsafe {
+            (self.instance().fp().get_input_source_localized_name)(
+                self.as_raw(),
+                &info,
+                capacity,
+                count,
+                buf,
+            )
+        })?;
+        let bytes = name
+            .iter()
+            .take_while(|c| **c != 0)
+            .map(|c| *c as u8)
+            .collect::<Vec<_>>();
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+/// An input source an action is currently bound to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OxrBoundSource {
+    /// The full input source path, e.g. `/user/hand/left/input/trigger/value`.
+    pub path: String,
+    /// A name of the input source for display, provided by the runtime.
+    pub localized_name: String,
+}
+
+/// The input sources every action with suggested bindings is currently bound to.
+///
+/// This is updated every time the interaction profile changes, e.g. after the user switched controllers,
+/// so controller diagrams and button glyphs can be kept up to date.
+#[derive(Resource, Default)]
+pub struct OxrActionBoundSources(HashMap<openxr::sys::Action, Vec<OxrBoundSource>>);
+
+impl OxrActionBoundSources {
+    /// Returns the input sources the action is bound to. Empty if it isn't bound to anything.
+    pub fn get(&self, action: openxr::sys::Action) -> &[OxrBoundSource] {
+        self.0.get(&action).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (openxr::sys::Action, &[OxrBoundSource])> {
+        self.0
+            .iter()
+            .map(|(action, sources)| (*action, sources.as_slice()))
+    }
+}
+
+/// Sent after [`OxrActionBoundSources`] was updated.
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct OxrBoundSourcesChanged;
+
 #[derive(Event, Clone)]
 /// Only Send this for Actions that were not attached yet!
 pub struct OxrSuggestActionBinding {
@@ -0,0 +1,293 @@
+use std::borrow::Cow;
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_mod_xr::session::{session_available, XrSessionCreated};
+use serde::Deserialize;
+
+use crate::action_binding::{OxrSendActionBindings, OxrSuggestActionBinding};
+use crate::action_set_attaching::OxrAttachActionSet;
+use crate::action_set_syncing::OxrActionSets;
+use crate::resources::OxrInstance;
+
+/// Creates the action sets and actions described by an [`OxrActionManifest`], suggests their bindings
+/// and attaches the sets once the session is created.
+///
+/// The created actions can be accessed through the [`OxrManifestActions`] resource.
+/// All action sets are added to [`OxrActionSets`], so they are synced without sending [`OxrSyncActionSet`](crate::action_set_syncing::OxrSyncActionSet) events.
+pub struct OxrActionManifestPlugin {
+    pub manifest: OxrActionManifest,
+}
+
+impl Plugin for OxrActionManifestPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.manifest.clone())
+            .add_systems(Startup, create_actions.run_if(session_available))
+            .add_systems(
+                OxrSendActionBindings,
+                suggest_bindings.run_if(resource_exists::<OxrManifestActions>),
+            )
+            .add_systems(
+                XrSessionCreated,
+                attach_sets.run_if(resource_exists::<OxrManifestActions>),
+            );
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum OxrActionManifestError {
+    #[error("Failed to read action manifest: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse action manifest: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+/// Declarative description of action sets, actions and their suggested bindings.
+///
+/// # Example
+///
+/// ```ron
+/// (
+///     action_sets: [
+///         (
+///             name: "gameplay",
+///             localized_name: "Gameplay",
+///             actions: [
+///                 (
+///                     name: "grab",
+///                     localized_name: "Grab",
+///                     kind: Bool,
+///                     subaction_paths: ["/user/hand/left", "/user/hand/right"],
+///                     bindings: {
+///                         "/interaction_profiles/oculus/touch_controller": [
+///                             "/user/hand/left/input/squeeze/value",
+///                             "/user/hand/right/input/squeeze/value",
+///                         ],
+///                     },
+///                 ),
+///             ],
+///         ),
+///     ],
+/// )
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Resource)]
+pub struct OxrActionManifest {
+    pub action_sets: Vec<OxrActionSetManifest>,
+}
+
+impl OxrActionManifest {
+    /// Parses a manifest in the RON format.
+    pub fn from_ron(ron: &str) -> Result<Self, OxrActionManifestError> {
+        Ok(ron::from_str(ron)?)
+    }
+
+    /// Reads and parses a manifest file in the RON format.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, OxrActionManifestError> {
+        Self::from_ron(&std::fs::read_to_string(path)?)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct OxrActionSetManifest {
+    pub name: String,
+    pub localized_name: String,
+    #[serde(default)]
+    pub priority: u32,
+    pub actions: Vec<OxrActionDescription>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct OxrActionDescription {
+    /// Name of the action, has to be unique across the whole manifest.
+    pub name: String,
+    pub localized_name: String,
+    pub kind: OxrActionKind,
+    /// Top level user paths, e.g. `/user/hand/left`, that the action can be filtered by.
+    #[serde(default)]
+    pub subaction_paths: Vec<String>,
+    /// Suggested bindings, from interaction profile path to input source paths.
+    #[serde(default)]
+    pub bindings: HashMap<String, Vec<String>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum OxrActionKind {
+    Bool,
+    Float,
+    Vector2,
+    Pose,
+    Vibration,
+}
+
+/// An action created from an [`OxrActionManifest`].
+#[derive(Clone)]
+pub enum OxrManifestAction {
+    Bool(openxr::Action<bool>),
+    Float(openxr::Action<f32>),
+    Vector2(openxr::Action<openxr::Vector2f>),
+    Pose(openxr::Action<openxr::Posef>),
+    Vibration(openxr::Action<openxr::Haptic>),
+}
+
+impl OxrManifestAction {
+    pub fn as_raw(&self) -> openxr::sys::Action {
+        match self {
+            Self::Bool(action) => action.as_raw(),
+            Self::Float(action) => action.as_raw(),
+            Self::Vector2(action) => action.as_raw(),
+            Self::Pose(action) => action.as_raw(),
+            Self::Vibration(action) => action.as_raw(),
+        }
+    }
+}
+
+/// The action sets and actions created from the [`OxrActionManifest`], by name.
+#[derive(Resource, Default)]
+pub struct OxrManifestActions {
+    sets: HashMap<String, openxr::ActionSet>,
+    actions: HashMap<String, OxrManifestAction>,
+}
+
+impl OxrManifestActions {
+    pub fn action_set(&self, name: &str) -> Option<&openxr::ActionSet> {
+        self.sets.get(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&OxrManifestAction> {
+        self.actions.get(name)
+    }
+
+    pub fn bool_action(&self, name: &str) -> Option<&openxr::Action<bool>> {
+        match self.get(name)? {
+            OxrManifestAction::Bool(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    pub fn float_action(&self, name: &str) -> Option<&openxr::Action<f32>> {
+        match self.get(name)? {
+            OxrManifestAction::Float(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    pub fn vector2_action(&self, name: &str) -> Option<&openxr::Action<openxr::Vector2f>> {
+        match self.get(name)? {
+            OxrManifestAction::Vector2(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    pub fn pose_action(&self, name: &str) -> Option<&openxr::Action<openxr::Posef>> {
+        match self.get(name)? {
+            OxrManifestAction::Pose(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    pub fn haptic_action(&self, name: &str) -> Option<&openxr::Action<openxr::Haptic>> {
+        match self.get(name)? {
+            OxrManifestAction::Vibration(action) => Some(action),
+            _ => None,
+        }
+    }
+}
+
+fn create_actions(
+    instance: Res<OxrInstance>,
+    manifest: Res<OxrActionManifest>,
+    mut action_sets: ResMut<OxrActionSets>,
+    mut cmds: Commands,
+) {
+    let mut created = OxrManifestActions::default();
+    for set_manifest in &manifest.action_sets {
+        let set = match instance.create_action_set(
+            &set_manifest.name,
+            &set_manifest.localized_name,
+            set_manifest.priority,
+        ) {
+            Ok(set) => set,
+            Err(err) => {
+                error!(
+                    "Unable to create action set \"{}\": {}",
+                    set_manifest.name, err
+                );
+                continue;
+            }
+        };
+        for description in &set_manifest.actions {
+            if created.actions.contains_key(&description.name) {
+                warn!(
+                    "Action \"{}\" is defined multiple times in the action manifest, skipping",
+                    description.name
+                );
+                continue;
+            }
+            let subaction_paths = description
+                .subaction_paths
+                .iter()
+                .filter_map(|path| match instance.string_to_path(path) {
+                    Ok(path) => Some(path),
+                    Err(err) => {
+                        error!("Unable to convert path: \"{}\"; error: {}", path, err);
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            let name = &description.name;
+            let localized_name = &description.localized_name;
+            let action = match description.kind {
+                OxrActionKind::Bool => set
+                    .create_action(name, localized_name, &subaction_paths)
+                    .map(OxrManifestAction::Bool),
+                OxrActionKind::Float => set
+                    .create_action(name, localized_name, &subaction_paths)
+                    .map(OxrManifestAction::Float),
+                OxrActionKind::Vector2 => set
+                    .create_action(name, localized_name, &subaction_paths)
+                    .map(OxrManifestAction::Vector2),
+                OxrActionKind::Pose => set
+                    .create_action(name, localized_name, &subaction_paths)
+                    .map(OxrManifestAction::Pose),
+                OxrActionKind::Vibration => set
+                    .create_action(name, localized_name, &subaction_paths)
+                    .map(OxrManifestAction::Vibration),
+            };
+            match action {
+                Ok(action) => {
+                    created.actions.insert(name.clone(), action);
+                }
+                Err(err) => error!("Unable to create action \"{}\": {}", name, err),
+            }
+        }
+        action_sets.insert(set_manifest.name.clone(), set.clone());
+        created.sets.insert(set_manifest.name.clone(), set);
+    }
+    cmds.insert_resource(created);
+}
+
+fn suggest_bindings(
+    manifest: Res<OxrActionManifest>,
+    actions: Res<OxrManifestActions>,
+    mut bindings: EventWriter<OxrSuggestActionBinding>,
+) {
+    for description in manifest.action_sets.iter().flat_map(|set| &set.actions) {
+        let Some(action) = actions.get(&description.name) else {
+            continue;
+        };
+        for (profile, paths) in &description.bindings {
+            bindings.send(OxrSuggestActionBinding {
+                action: action.as_raw(),
+                interaction_profile: Cow::Owned(profile.clone()),
+                bindings: paths.iter().cloned().map(Cow::Owned).collect(),
+            });
+        }
+    }
+}
+
+fn attach_sets(actions: Res<OxrManifestActions>, mut attach: EventWriter<OxrAttachActionSet>) {
+    for set in actions.sets.values() {
+        attach.send(OxrAttachActionSet(set.clone()));
+    }
+}
@@ -1,28 +1,47 @@
-use crate::{action_binding::run_action_binding_sugestion, session::OxrSession};
-use bevy::prelude::*;
-use bevy_mod_xr::session::XrSessionCreatedEvent;
+use crate::{
+    action_binding::run_action_binding_sugestion,
+    action_set_syncing::{dedup_sets, OxrActionSets},
+    session::OxrSession,
+};
+use bevy::{prelude::*, utils::HashSet};
+use bevy_mod_xr::session::{XrPreDestroySession, XrSessionCreatedEvent};
 
 impl Plugin for OxrActionAttachingPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<OxrAttachActionSet>();
+        app.init_resource::<OxrAttachedActionSets>();
         app.add_systems(
             PostUpdate,
             attach_sets
                 .run_if(on_event::<XrSessionCreatedEvent>)
                 .after(run_action_binding_sugestion),
         );
+        app.add_systems(XrPreDestroySession, clear_attached_sets);
     }
 }
 
-fn attach_sets(session: Res<OxrSession>, mut events: EventReader<OxrAttachActionSet>) {
-    let sets = events.read().map(|v| &v.0).collect::<Vec<_>>();
+fn attach_sets(
+    session: Res<OxrSession>,
+    action_sets: Option<Res<OxrActionSets>>,
+    mut attached: ResMut<OxrAttachedActionSets>,
+    mut events: EventReader<OxrAttachActionSet>,
+) {
+    // all sets have to be attached in a single call
+    let sets = dedup_sets(
+        events
+            .read()
+            .map(|v| &v.0)
+            .chain(action_sets.iter().flat_map(|sets| sets.iter())),
+        |set| set.as_raw(),
+    );
     if sets.is_empty() {
         return;
     }
     info!("attaching {} sessions", sets.len());
     match session.attach_action_sets(&sets) {
         Ok(_) => {
-            info!("attached sessions!")
+            info!("attached sessions!");
+            attached.0 = sets.iter().map(|set| set.as_raw().into_raw()).collect();
         }
         Err(openxr::sys::Result::ERROR_ACTIONSETS_ALREADY_ATTACHED) => {
             error!("Action Sets Already attached!");
@@ -41,4 +60,22 @@ fn attach_sets(session: Res<OxrSession>, mut events: EventReader<OxrAttachAction
 /// be applied in [`PostUpdate`]
 pub struct OxrAttachActionSet(pub openxr::ActionSet);
 
+/// The action sets attached to the current session.
+///
+/// Action sets can only be attached once per session, so sets created after the session was created are missing here.
+/// Only attached sets are synced, `xrSyncActions` fails for all sets if any of them isn't attached.
+#[derive(Resource, Default, Debug)]
+pub struct OxrAttachedActionSets(HashSet<u64>);
+
+impl OxrAttachedActionSets {
+    /// Returns `true` if `set` is attached to the current session.
+    pub fn contains(&self, set: &openxr::ActionSet) -> bool {
+        self.0.contains(&set.as_raw().into_raw())
+    }
+}
+
+fn clear_attached_sets(mut attached: ResMut<OxrAttachedActionSets>) {
+    attached.0.clear();
+}
+
 pub struct OxrActionAttachingPlugin;
@@ -1,5 +1,11 @@
-use crate::{openxr_session_running, session::OxrSession};
+use std::borrow::Cow;
+
+use crate::{
+    action_set_attaching::OxrAttachedActionSets, openxr_session_running, resources::OxrInstance,
+    session::OxrSession, OxrPreUpdateSet,
+};
 use bevy::prelude::*;
+use bevy::utils::HashMap;
 
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
 pub struct OxrActionSetSyncSet;
@@ -7,6 +13,11 @@ pub struct OxrActionSetSyncSet;
 impl Plugin for OxrActionSyncingPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<OxrSyncActionSet>();
+        app.init_resource::<OxrActionSets>();
+        app.configure_sets(
+            PreUpdate,
+            OxrActionSetSyncSet.in_set(OxrPreUpdateSet::SyncActions),
+        );
         app.add_systems(
             PreUpdate,
             sync_sets
@@ -16,10 +27,32 @@ impl Plugin for OxrActionSyncingPlugin {
     }
 }
 
-fn sync_sets(session: Res<OxrSession>, mut events: EventReader<OxrSyncActionSet>) {
-    let sets = events
-        .read()
-        .map(|v| &v.0)
+fn sync_sets(
+    session: Res<OxrSession>,
+    action_sets: Res<OxrActionSets>,
+    attached: Option<Res<OxrAttachedActionSets>>,
+    mut events: EventReader<OxrSyncActionSet>,
+) {
+    let sets = dedup_sets(
+        events
+            .read()
+            .map(|v| &v.0)
+            .filter(|set| !action_sets.is_disabled(set))
+            .chain(action_sets.active_sets()),
+        |set| set.as_raw(),
+    );
+    let sets = sets
+        .into_iter()
+        // a single set that isn't attached makes the whole sync fail
+        .filter(|set| {
+            let is_attached = attached
+                .as_ref()
+                .is_none_or(|attached| attached.contains(set));
+            if !is_attached {
+                warn_once!("Not syncing action sets that weren't attached to the session, add action sets before the session is created");
+            }
+            is_attached
+        })
         .map(openxr::ActiveActionSet::new)
         .collect::<Vec<_>>();
     if sets.is_empty() {
@@ -31,8 +64,124 @@ fn sync_sets(session: Res<OxrSession>, mut events: EventReader<OxrSyncActionSet>
     }
 }
 
+/// Removes duplicate sets, keeping the first one. The runtime rejects duplicate sets in a single call.
+pub(crate) fn dedup_sets<T: Copy, R: PartialEq>(
+    sets: impl IntoIterator<Item = T>,
+    raw: impl Fn(T) -> R,
+) -> Vec<T> {
+    let mut deduped: Vec<T> = Vec::new();
+    for set in sets {
+        if !deduped.iter().any(|s| raw(*s) == raw(set)) {
+            deduped.push(set);
+        }
+    }
+    deduped
+}
+
 #[derive(Event, Clone)]
 /// Send this event for every ActionSet you want to attach to the [`OxrSession`] once the Session Status changed to Ready. all requests will
 pub struct OxrSyncActionSet(pub openxr::ActionSet);
 
+/// Named action sets that are synced every frame while they are active.
+///
+/// Sets in here don't need an [`OxrSyncActionSet`] or an [`OxrAttachActionSet`](crate::action_set_attaching::OxrAttachActionSet)
+/// to be sent for them, they are all attached in one call when the session is created. Sets added after that can't be attached anymore
+/// and are skipped when syncing, see [`OxrAttachedActionSets`].
+/// Disabling a set excludes it from `xrSyncActions`, even if an [`OxrSyncActionSet`] is sent for it,
+/// which makes the runtime report all of its actions as inactive.
+/// This can be used to e.g. stop gameplay actions from firing while a menu is open.
+#[derive(Resource, Default)]
+pub struct OxrActionSets {
+    sets: HashMap<Cow<'static, str>, (openxr::ActionSet, bool)>,
+}
+
+impl OxrActionSets {
+    /// Adds an active action set with the given name, replacing any set with the same name.
+    pub fn insert(&mut self, name: impl Into<Cow<'static, str>>, set: openxr::ActionSet) {
+        self.sets.insert(name.into(), (set, true));
+    }
+
+    /// Removes the action set with the given name. It will no longer be synced automatically.
+    pub fn remove(&mut self, name: &str) -> Option<openxr::ActionSet> {
+        self.sets.remove(name).map(|(set, _)| set)
+    }
+
+    /// Creates an action set and adds it as active under its `name`.
+    ///
+    /// When actions of several sets are bound to the same input, only the actions of the sets with the highest `priority` receive it.
+    pub fn create(
+        &mut self,
+        instance: &OxrInstance,
+        name: impl Into<Cow<'static, str>>,
+        localized_name: &str,
+        priority: u32,
+    ) -> openxr::Result<openxr::ActionSet> {
+        let name = name.into();
+        let set = instance.create_action_set(&name, localized_name, priority)?;
+        self.insert(name, set.clone());
+        Ok(set)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&openxr::ActionSet> {
+        self.sets.get(name).map(|(set, _)| set)
+    }
+
+    /// Enables or disables syncing of the action set with the given name.
+    ///
+    /// Returns `false` if there is no action set with that name.
+    pub fn set_active(&mut self, name: &str, active: bool) -> bool {
+        match self.sets.get_mut(name) {
+            Some((_, is_active)) => {
+                *is_active = active;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the action set with the given name exists and is active.
+    pub fn is_active(&self, name: &str) -> bool {
+        self.sets.get(name).is_some_and(|(_, active)| *active)
+    }
+
+    /// Returns all action sets, active or not.
+    pub fn iter(&self) -> impl Iterator<Item = &openxr::ActionSet> {
+        self.sets.values().map(|(set, _)| set)
+    }
+
+    /// Returns all action sets that are currently active.
+    pub fn active_sets(&self) -> impl Iterator<Item = &openxr::ActionSet> {
+        self.sets
+            .values()
+            .filter(|(_, active)| *active)
+            .map(|(set, _)| set)
+    }
+
+    fn is_disabled(&self, set: &openxr::ActionSet) -> bool {
+        self.sets
+            .values()
+            .any(|(s, active)| !*active && s.as_raw() == set.as_raw())
+    }
+}
+
 pub struct OxrActionSyncingPlugin;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedup_sets_keeps_the_first_of_each_set() {
+        let sets = [(1, "a"), (2, "b"), (1, "c"), (3, "d"), (2, "e")];
+        assert_eq!(
+            dedup_sets(sets, |(raw, _)| raw),
+            [(1, "a"), (2, "b"), (3, "d")]
+        );
+    }
+
+    #[test]
+    fn dedup_sets_keeps_the_order() {
+        assert_eq!(dedup_sets([3, 1, 2], |raw| raw), [3, 1, 2]);
+        assert!(dedup_sets(std::iter::empty::<u64>(), |raw| raw).is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+//! Polls the battery levels of the XR devices into [`XrBatteryStatus`].
+//!
+//! OpenXR has no extension reporting battery levels, so they come from the platform where possible:
+//! on Android, i.e. standalone headsets, the level of the headset is read from the system battery.
+//! Controller levels, and the headset level on other platforms, stay [`None`].
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_mod_xr::battery::XrBatteryStatus;
+
+use crate::openxr_session_running;
+
+/// Updates [`XrBatteryStatus`] every `poll_interval` while the session is running.
+pub struct OxrBatteryStatusPlugin {
+    pub poll_interval: Duration,
+}
+
+impl Default for OxrBatteryStatusPlugin {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Plugin for OxrBatteryStatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<XrBatteryStatus>()
+            .insert_resource(OxrBatteryPollTimer(Timer::new(
+                self.poll_interval,
+                TimerMode::Repeating,
+            )))
+            .add_systems(Update, poll_battery_status.run_if(openxr_session_running));
+    }
+}
+
+#[derive(Resource)]
+struct OxrBatteryPollTimer(Timer);
+
+fn poll_battery_status(
+    time: Res<Time<Real>>,
+    mut timer: ResMut<OxrBatteryPollTimer>,
+    mut status: ResMut<XrBatteryStatus>,
+    mut polled: Local<bool>,
+) {
+    // poll right away, then on every tick of the timer
+    if !timer.0.tick(time.delta()).just_finished() && *polled {
+        return;
+    }
+    *polled = true;
+    status.set_if_neq(XrBatteryStatus {
+        hmd: hmd_battery_level(),
+        left_controller: None,
+        right_controller: None,
+    });
+}
+
+/// Returns the battery level of the headset from 0.0 to 1.0, or [`None`] where it isn't available.
+pub fn hmd_battery_level() -> Option<f32> {
+    #[cfg(target_os = "android")]
+    {
+        match android_battery_level() {
+            Ok(level) => level,
+            Err(err) => {
+                debug!("Failed to read the battery level: {err}");
+                None
+            }
+        }
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        None
+    }
+}
+
+/// Reads the sticky `ACTION_BATTERY_CHANGED` intent, which doesn't need any permissions.
+#[cfg(target_os = "android")]
+fn android_battery_level() -> jni::errors::Result<Option<f32>> {
+    use jni::objects::{JObject, JValue};
+
+    let ctx = ndk_context::android_context();
+    let vm = unsafe { jni::JavaVM::from_raw(ctx.vm().cast()) }?;
+    let env = vm.attach_current_thread()?;
+    let context = unsafe { JObject::from_raw(ctx.context().cast()) };
+
+    let action = env.new_string("android.intent.action.BATTERY_CHANGED")?;
+    let filter = env.new_object(
+        "android/content/IntentFilter",
+        "(Ljava/lang/String;)V",
+        &[JValue::Object(action.into())],
+    )?;
+    let intent = env
+        .call_method(
+            context,
+            "registerReceiver",
+            "(Landroid/content/BroadcastReceiver;Landroid/content/IntentFilter;)Landroid/content/Intent;",
+            &[JValue::Object(JObject::null()), JValue::Object(filter)],
+        )?
+        .l()?;
+    if intent.is_null() {
+        return Ok(None);
+    }
+    let get_int_extra = |name: &str| -> jni::errors::Result<i32> {
+        let name = env.new_string(name)?;
+        env.call_method(
+            intent,
+            "getIntExtra",
+            "(Ljava/lang/String;I)I",
+            &[JValue::Object(name.into()), JValue::Int(-1)],
+        )?
+        .i()
+    };
+    let level = get_int_extra("level")?;
+    let scale = get_int_extra("scale")?;
+    if level < 0 || scale <= 0 {
+        return Ok(None);
+    }
+    Ok(Some(level as f32 / scale as f32))
+}
@@ -0,0 +1,255 @@
+//! Copies the rendered eye buffers to the CPU, e.g. for recording, streaming or screenshots.
+//!
+//! This is opt-in: add [`OxrFrameCapturePlugin`] and read every frame from [`OxrCapturedFrames`],
+//! or use [`OxrCaptureMode::OnDemand`] to capture single frames as [`Image`]s with [`XrCaptureFrameEvent`].
+//! The frame is still submitted to the headset as usual, the copy happens right before the
+//! swapchain image is released, since the image belongs to the runtime afterwards.
+//!
+//! The swapchain textures need [`COPY_SRC`](wgpu::TextureUsages::COPY_SRC) usage, which is part of the default
+//! [`OxrInitPlugin::swapchain_usage`](crate::init::OxrInitPlugin::swapchain_usage).
+use std::sync::{
+    mpsc::{channel, Receiver, Sender},
+    Mutex,
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
+        render_asset::RenderAssetUsages,
+        render_resource::{BufferDescriptor, BufferUsages, Extent3d, TextureDimension},
+        renderer::{RenderDevice, RenderQueue},
+        Render, RenderApp,
+    },
+};
+use bevy_mod_xr::session::XrRenderSet;
+
+use crate::{
+    init::should_run_frame_loop,
+    render::OxrRenderStep,
+    resources::{OxrAcquiredSwapchainImage, OxrFrameState, OxrGraphicsInfo},
+};
+
+/// Captures rendered XR frames, see [`OxrCaptureMode`] for how they are delivered.
+#[derive(Default)]
+pub struct OxrFrameCapturePlugin {
+    pub mode: OxrCaptureMode,
+}
+
+/// Which frames [`OxrFrameCapturePlugin`] captures.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OxrCaptureMode {
+    /// Capture every rendered frame and deliver it through [`OxrCapturedFrames`].
+    #[default]
+    EveryFrame,
+    /// Capture a single frame for every [`XrCaptureFrameEvent`] and deliver it as an
+    /// [`OxrFrameCaptured`] event. [`OxrCapturedFrames`] is drained by the plugin in this mode.
+    OnDemand,
+}
+
+impl Plugin for OxrFrameCapturePlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+        let every_frame = self.mode == OxrCaptureMode::EveryFrame;
+        app.add_event::<XrCaptureFrameEvent>()
+            .add_event::<OxrFrameCaptured>()
+            .add_plugins(ExtractResourcePlugin::<OxrCaptureRequested>::default())
+            .insert_resource(OxrCaptureRequested(every_frame))
+            .insert_resource(OxrCapturedFrames(Mutex::new(receiver)));
+        if !every_frame {
+            app.add_systems(PostUpdate, request_capture)
+                .add_systems(First, send_captured_images);
+        }
+        app.sub_app_mut(RenderApp)
+            .insert_resource(OxrCaptureSender(sender))
+            .add_systems(
+                Render,
+                capture_frame
+                    .in_set(XrRenderSet::PostRender)
+                    .before(OxrRenderStep::ReleaseImage)
+                    .run_if(should_run_frame_loop)
+                    .run_if(resource_exists::<OxrAcquiredSwapchainImage>)
+                    .run_if(|requested: Option<Res<OxrCaptureRequested>>| {
+                        requested.is_some_and(|requested| requested.0)
+                    }),
+            );
+    }
+}
+
+/// Send this to capture the next rendered XR frame with [`OxrCaptureMode::OnDemand`].
+///
+/// Several events sent in the same frame only capture a single frame.
+/// With [`OxrCaptureMode::EveryFrame`] every frame is captured anyway and this is ignored.
+#[derive(Clone, Copy, Debug, Default, Event)]
+pub struct XrCaptureFrameEvent;
+
+/// A frame captured after an [`XrCaptureFrameEvent`].
+#[derive(Clone, Debug, Event)]
+pub struct OxrFrameCaptured {
+    /// A 2d array image with one layer per view.
+    pub image: Image,
+    /// The predicted display time of the captured frame.
+    pub display_time: openxr::Time,
+}
+
+/// Whether the frame rendered next should be captured.
+///
+/// Always set with [`OxrCaptureMode::EveryFrame`], otherwise set from [`XrCaptureFrameEvent`]s.
+#[derive(Clone, Copy, Debug, Default, Resource, ExtractResource)]
+struct OxrCaptureRequested(bool);
+
+fn request_capture(
+    mut events: EventReader<XrCaptureFrameEvent>,
+    mut requested: ResMut<OxrCaptureRequested>,
+) {
+    let request = !events.is_empty();
+    events.clear();
+    if requested.0 != request {
+        requested.0 = request;
+    }
+}
+
+fn send_captured_images(frames: Res<OxrCapturedFrames>, mut events: EventWriter<OxrFrameCaptured>) {
+    for frame in frames.drain() {
+        let image = Image::new(
+            Extent3d {
+                width: frame.size.x,
+                height: frame.size.y,
+                depth_or_array_layers: frame.layers,
+            },
+            TextureDimension::D2,
+            frame.data,
+            frame.format,
+            RenderAssetUsages::default(),
+        );
+        events.send(OxrFrameCaptured {
+            image,
+            display_time: frame.display_time,
+        });
+    }
+}
+
+/// A copy of the eye buffers of a single frame.
+pub struct OxrCapturedFrame {
+    /// Tightly packed pixel data of all layers, one layer (eye) after another.
+    pub data: Vec<u8>,
+    /// Size of a single layer in pixels.
+    pub size: UVec2,
+    /// Number of layers in `data`, one per view.
+    pub layers: u32,
+    pub format: wgpu::TextureFormat,
+    /// The predicted display time of the captured frame.
+    pub display_time: openxr::Time,
+}
+
+/// Receives the frames captured by [`OxrFrameCapturePlugin`].
+///
+/// Frames arrive a frame or two after they were rendered, once the copy finished on the GPU.
+/// Frames that aren't received pile up, so make sure to drain this regularly.
+#[derive(Resource)]
+pub struct OxrCapturedFrames(Mutex<Receiver<OxrCapturedFrame>>);
+
+impl OxrCapturedFrames {
+    /// Returns the oldest captured frame that wasn't received yet.
+    pub fn try_recv(&self) -> Option<OxrCapturedFrame> {
+        self.0.lock().unwrap().try_recv().ok()
+    }
+
+    /// Returns all captured frames that weren't received yet, oldest first.
+    pub fn drain(&self) -> Vec<OxrCapturedFrame> {
+        self.0.lock().unwrap().try_iter().collect()
+    }
+}
+
+#[derive(Resource)]
+struct OxrCaptureSender(Sender<OxrCapturedFrame>);
+
+fn capture_frame(
+    image: Res<OxrAcquiredSwapchainImage>,
+    frame_state: Res<OxrFrameState>,
+    graphics_info: Res<OxrGraphicsInfo>,
+    sender: Res<OxrCaptureSender>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    if !frame_state.should_render {
+        return;
+    }
+    if !graphics_info
+        .swapchain_usage
+        .contains(wgpu::TextureUsages::COPY_SRC)
+    {
+        warn_once!("Can't capture XR frames, the swapchain textures don't have COPY_SRC usage");
+        return;
+    }
+    let Some(pixel_size) = graphics_info.format.block_copy_size(None) else {
+        warn_once!(
+            "Can't capture XR frames with format {:?}",
+            graphics_info.format
+        );
+        return;
+    };
+    let size = graphics_info.resolution;
+    let layers = image.texture.depth_or_array_layers();
+    let row_bytes = size.x * pixel_size;
+    let padded_row_bytes =
+        row_bytes.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+    let buffer = render_device.create_buffer(&BufferDescriptor {
+        label: Some("xr_capture_buffer"),
+        size: (padded_row_bytes * size.y * layers) as u64,
+        usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = render_device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("xr_capture_encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        image.texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row_bytes),
+                rows_per_image: Some(size.y),
+            },
+        },
+        wgpu::Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: layers,
+        },
+    );
+    render_queue.submit([encoder.finish()]);
+
+    let sender = sender.0.clone();
+    let display_time = frame_state.predicted_display_time;
+    let format = graphics_info.format;
+    let mapped_buffer = buffer.clone();
+    // The callback runs once the device is polled after the copy finished, which happens every frame.
+    buffer
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, move |result| {
+            if let Err(err) = result {
+                error!("Failed to map XR capture buffer: {err}");
+                return;
+            }
+            let mut data = Vec::with_capacity((row_bytes * size.y * layers) as usize);
+            {
+                let mapped = mapped_buffer.slice(..).get_mapped_range();
+                // Strip the padding required by the row alignment of texture copies.
+                for row in mapped.chunks_exact(padded_row_bytes as usize) {
+                    data.extend_from_slice(&row[..row_bytes as usize]);
+                }
+            }
+            mapped_buffer.unmap();
+            // The receiving side might have been dropped, there is nothing to do about that.
+            let _ = sender.send(OxrCapturedFrame {
+                data,
+                size,
+                layers,
+                format,
+                display_time,
+            });
+        });
+}
@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+use bevy_mod_xr::hands::{AimPose, GripPose, LeftHand, RightHand};
+use bevy_mod_xr::session::{XrPreDestroySession, XrSessionCreated};
+use openxr::Posef;
+
+use crate::action_binding::{OxrSendActionBindings, OxrSuggestActionBinding};
+use crate::action_set_attaching::OxrAttachActionSet;
+use crate::action_set_syncing::{OxrActionSetSyncSet, OxrSyncActionSet};
+use crate::resources::OxrInstance;
+use crate::session::OxrSession;
+use crate::{openxr_session_available, openxr_session_running};
+
+/// Interaction profiles [`OxrControllerPoses::suggest_bindings`] suggests the aim and grip poses for.
+pub(crate) const INTERACTION_PROFILES: &[&str] = &[
+    "/interaction_profiles/khr/simple_controller",
+    "/interaction_profiles/oculus/touch_controller",
+    "/interaction_profiles/valve/index_controller",
+    "/interaction_profiles/htc/vive_controller",
+    "/interaction_profiles/microsoft/motion_controller",
+];
+
+/// Tracks the aim and grip poses of both controllers in its own action set.
+///
+/// Spawns an entity with an [`XrSpace`](bevy_mod_xr::spaces::XrSpace), [`LeftHand`] or [`RightHand`]
+/// and [`AimPose`] or [`GripPose`] for each pose when the session is created,
+/// which are located every frame like any other space.
+/// Use [`OxrControllerPoses`] directly to add the poses to an existing action set instead.
+pub struct OxrControllerPosesPlugin;
+
+impl Plugin for OxrControllerPosesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Startup,
+            create_controller_poses.run_if(openxr_session_available),
+        )
+        .add_systems(OxrSendActionBindings, suggest_bindings)
+        .add_systems(XrSessionCreated, (attach_set, spawn_pose_spaces))
+        .add_systems(XrPreDestroySession, despawn_pose_spaces)
+        .add_systems(
+            PreUpdate,
+            sync_set
+                .before(OxrActionSetSyncSet)
+                .run_if(openxr_session_running),
+        );
+    }
+}
+
+/// Aim and grip pose actions for both hands, used to spawn entities tracking the controllers.
+#[derive(Resource)]
+pub struct OxrControllerPoses {
+    pub set: openxr::ActionSet,
+    pub aim: openxr::Action<Posef>,
+    pub grip: openxr::Action<Posef>,
+    pub left: openxr::Path,
+    pub right: openxr::Path,
+}
+
+/// Marks the entities spawned by [`OxrControllerPosesPlugin`].
+#[derive(Clone, Copy, Component, Debug)]
+pub struct OxrControllerPose;
+
+impl OxrControllerPoses {
+    /// Creates `aim_pose` and `grip_pose` actions with the `/user/hand/left` and `/user/hand/right`
+    /// subaction paths in the given action set.
+    pub fn new(instance: &OxrInstance, set: &openxr::ActionSet) -> openxr::Result<Self> {
+        let left = instance.string_to_path("/user/hand/left")?;
+        let right = instance.string_to_path("/user/hand/right")?;
+        Ok(Self {
+            set: set.clone(),
+            aim: set.create_action("aim_pose", "Aim Pose", &[left, right])?,
+            grip: set.create_action("grip_pose", "Grip Pose", &[left, right])?,
+            left,
+            right,
+        })
+    }
+
+    /// Suggests the aim and grip poses of the common controller interaction profiles.
+    ///
+    /// Has to run in [`OxrSendActionBindings`].
+    pub fn suggest_bindings(&self, bindings: &mut EventWriter<OxrSuggestActionBinding>) {
+        for profile in INTERACTION_PROFILES {
+            for (action, pose) in [(&self.aim, "aim"), (&self.grip, "grip")] {
+                bindings.send(OxrSuggestActionBinding {
+                    action: action.as_raw(),
+                    interaction_profile: (*profile).into(),
+                    bindings: vec![
+                        format!("/user/hand/left/input/{pose}/pose").into(),
+                        format!("/user/hand/right/input/{pose}/pose").into(),
+                    ],
+                });
+            }
+        }
+    }
+
+    /// Creates the action spaces of all poses and spawns an entity for each of them, returning the entities.
+    ///
+    /// The entities are tagged with [`LeftHand`] or [`RightHand`] and [`AimPose`] or [`GripPose`],
+    /// `bundle` is added to all of them.
+    pub fn spawn_spaces(
+        &self,
+        session: &OxrSession,
+        cmds: &mut Commands,
+        bundle: impl Bundle + Clone,
+    ) -> openxr::Result<[Entity; 4]> {
+        let left_aim = session.create_action_space(&self.aim, self.left, Isometry3d::IDENTITY)?;
+        let right_aim = session.create_action_space(&self.aim, self.right, Isometry3d::IDENTITY)?;
+        let left_grip = session.create_action_space(&self.grip, self.left, Isometry3d::IDENTITY)?;
+        let right_grip =
+            session.create_action_space(&self.grip, self.right, Isometry3d::IDENTITY)?;
+        Ok([
+            cmds.spawn((left_aim, LeftHand, AimPose, bundle.clone()))
+                .id(),
+            cmds.spawn((right_aim, RightHand, AimPose, bundle.clone()))
+                .id(),
+            cmds.spawn((left_grip, LeftHand, GripPose, bundle.clone()))
+                .id(),
+            cmds.spawn((right_grip, RightHand, GripPose, bundle)).id(),
+        ])
+    }
+}
+
+fn create_controller_poses(instance: Res<OxrInstance>, mut cmds: Commands) {
+    let poses = instance
+        .create_action_set("controller_poses", "Controller Poses", 0)
+        .and_then(|set| OxrControllerPoses::new(&instance, &set));
+    match poses {
+        Ok(poses) => cmds.insert_resource(poses),
+        Err(err) => error!("Failed to create controller pose actions: {err}"),
+    }
+}
+
+fn suggest_bindings(
+    poses: Option<Res<OxrControllerPoses>>,
+    mut bindings: EventWriter<OxrSuggestActionBinding>,
+) {
+    if let Some(poses) = poses {
+        poses.suggest_bindings(&mut bindings);
+    }
+}
+
+fn attach_set(poses: Option<Res<OxrControllerPoses>>, mut attach: EventWriter<OxrAttachActionSet>) {
+    if let Some(poses) = poses {
+        attach.send(OxrAttachActionSet(poses.set.clone()));
+    }
+}
+
+fn sync_set(poses: Option<Res<OxrControllerPoses>>, mut sync: EventWriter<OxrSyncActionSet>) {
+    if let Some(poses) = poses {
+        sync.send(OxrSyncActionSet(poses.set.clone()));
+    }
+}
+
+fn spawn_pose_spaces(
+    poses: Option<Res<OxrControllerPoses>>,
+    session: Res<OxrSession>,
+    mut cmds: Commands,
+) {
+    let Some(poses) = poses else {
+        return;
+    };
+    if let Err(err) = poses.spawn_spaces(&session, &mut cmds, OxrControllerPose) {
+        error!("Failed to create controller pose spaces: {err}");
+    }
+}
+
+fn despawn_pose_spaces(query: Query<Entity, With<OxrControllerPose>>, mut cmds: Commands) {
+    for e in &query {
+        cmds.entity(e).despawn_recursive();
+    }
+}
@@ -2,6 +2,7 @@ use std::borrow::Cow;
 use std::fmt;
 
 use super::graphics::GraphicsBackend;
+use super::layer_builder::OxrLayerType;
 
 use thiserror::Error;
 
@@ -27,8 +28,8 @@ pub enum OxrError {
     NoAvailableViewConfiguration,
     #[error("No compatible blend mode available")]
     NoAvailableBlendMode,
-    #[error("No compatible format available")]
-    NoAvailableFormat,
+    #[error("The runtime created a swapchain without images")]
+    NoSwapchainImages,
     #[error("OpenXR runtime does not support these extensions: {0}")]
     UnavailableExtensions(UnavailableExts),
     #[error("Could not meet graphics requirements for platform. See console for details")]
@@ -45,6 +46,91 @@ pub enum OxrError {
     NulError(#[from] std::ffi::NulError),
     #[error("Graphics init error: {0}")]
     InitError(InitError),
+    #[error("Composition layer type {0:?} is not supported by the runtime or its extension is not enabled")]
+    UnsupportedLayerType(OxrLayerType),
+}
+
+/// Why [`OxrInitPlugin`](crate::init::OxrInitPlugin) couldn't initialize XR.
+///
+/// The app keeps running without XR in [`XrState::Unavailable`](bevy_mod_xr::session::XrState::Unavailable) either way,
+/// so e.g. a desktop camera can be spawned instead.
+#[derive(Error, Debug)]
+pub enum OxrInitError {
+    /// `XR_ERROR_FORM_FACTOR_UNAVAILABLE`, usually because no headset is connected.
+    #[error(
+        "The OpenXR runtime supports head mounted displays, but no headset is currently available"
+    )]
+    FormFactorUnavailable,
+    /// None of the requested swapchain formats is supported by the runtime, or the runtime supports no format wgpu can use.
+    #[error("None of the swapchain formats {requested:?} is supported, the runtime supports {available:?}")]
+    NoSupportedFormat {
+        /// The formats from [`OxrInitPlugin::formats`](crate::init::OxrInitPlugin::formats), empty if any format was allowed.
+        requested: Vec<wgpu::TextureFormat>,
+        /// The formats the runtime supports that can be imported into wgpu, in the runtime's order of preference.
+        available: Vec<wgpu::TextureFormat>,
+    },
+    #[error(transparent)]
+    Oxr(#[from] OxrError),
+}
+
+/// A failed step of the frame loop, logged by [`handle_render_error`](crate::render::handle_render_error).
+#[derive(Error, Debug)]
+pub enum OxrRenderError {
+    #[error("Failed to wait frame: {0}")]
+    WaitFrame(openxr::sys::Result),
+    #[error("Failed to begin frame: {0}")]
+    BeginFrame(openxr::sys::Result),
+    #[error("Failed to acquire image: {0}")]
+    AcquireImage(OxrError),
+    #[error("Failed to wait image: {0}")]
+    WaitImage(OxrError),
+    #[error("Failed to release image: {0}")]
+    ReleaseImage(OxrError),
+    #[error("Failed to end frame: {0}")]
+    EndFrame(OxrError),
+}
+
+impl OxrRenderError {
+    /// Returns the OpenXR result code of the error, if it came from the runtime.
+    pub fn raw(&self) -> Option<openxr::sys::Result> {
+        match self {
+            Self::WaitFrame(e) | Self::BeginFrame(e) => Some(*e),
+            Self::AcquireImage(e)
+            | Self::WaitImage(e)
+            | Self::ReleaseImage(e)
+            | Self::EndFrame(e) => match e {
+                OxrError::OpenXrError(e) => Some(*e),
+                _ => None,
+            },
+        }
+    }
+
+    /// Returns true if the error is expected while the session is starting or stopping.
+    pub fn is_session_not_running(&self) -> bool {
+        self.raw() == Some(openxr::sys::Result::ERROR_SESSION_NOT_RUNNING)
+    }
+}
+
+/// An invalid binding found by [`OxrBindingsBuilder`](crate::action_binding::OxrBindingsBuilder).
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum OxrBindingError {
+    #[error("Invalid interaction profile path \"{path}\": {error}")]
+    InvalidInteractionProfile {
+        path: String,
+        error: openxr::sys::Result,
+    },
+    #[error("Invalid binding path \"{path}\": {error}")]
+    InvalidPath {
+        path: String,
+        error: openxr::sys::Result,
+    },
+    #[error("Interaction profile \"{profile}\" has no input or output \"{path}\"")]
+    UnsupportedPath { profile: String, path: String },
+    #[error("Failed to suggest bindings for \"{profile}\": {error}")]
+    SuggestFailed {
+        profile: String,
+        error: openxr::sys::Result,
+    },
 }
 
 pub use init_error::InitError;
@@ -98,6 +184,12 @@ mod init_error {
     }
 }
 
+impl From<openxr::sys::Result> for OxrInitError {
+    fn from(value: openxr::sys::Result) -> Self {
+        Self::Oxr(value.into())
+    }
+}
+
 impl From<Vec<Cow<'static, str>>> for OxrError {
     fn from(value: Vec<Cow<'static, str>>) -> Self {
         Self::UnavailableExtensions(UnavailableExts(value))
@@ -30,16 +30,163 @@ impl OxrExtensions {
     }
     pub fn disable_hand_tracking(&mut self) -> &mut Self {
         self.0.ext_hand_tracking = false;
+        self.0.ext_hand_tracking_data_source = false;
+        self
+    }
+    /// Enables hand tracking and lets hand trackers report whether the joints are synthesized from a controller,
+    /// see [`OxrHandTrackingDataSources`](crate::features::handtracking::OxrHandTrackingDataSources).
+    pub fn enable_hand_tracking_data_source(&mut self) -> &mut Self {
+        self.0.ext_hand_tracking = true;
+        self.0.ext_hand_tracking_data_source = true;
+        self
+    }
+    pub fn disable_hand_tracking_data_source(&mut self) -> &mut Self {
+        self.0.ext_hand_tracking_data_source = false;
+        self
+    }
+    pub fn enable_fb_body_tracking(&mut self) -> &mut Self {
+        self.0.fb_body_tracking = true;
+        self
+    }
+    pub fn disable_fb_body_tracking(&mut self) -> &mut Self {
+        self.0.fb_body_tracking = false;
+        self
+    }
+    pub fn enable_fb_face_tracking2(&mut self) -> &mut Self {
+        self.0.fb_face_tracking2 = true;
+        self
+    }
+    pub fn disable_fb_face_tracking2(&mut self) -> &mut Self {
+        self.0.fb_face_tracking2 = false;
         self
     }
     pub fn enable_extx_overlay(&mut self) -> &mut Self {
         self.0.extx_overlay = true;
         self
     }
+    /// Enables fixed foveated rendering, see [`OxrFoveationProfile`](crate::features::foveation::OxrFoveationProfile).
+    pub fn enable_fb_foveation(&mut self) -> &mut Self {
+        self.0.fb_foveation = true;
+        self.0.fb_foveation_configuration = true;
+        self.0.fb_swapchain_update_state = true;
+        self
+    }
+    pub fn disable_fb_foveation(&mut self) -> &mut Self {
+        self.0.fb_foveation = false;
+        self.0.fb_foveation_configuration = false;
+        self.0.fb_swapchain_update_state = false;
+        self
+    }
+    /// Allows loading controller models, see [`load_controller_model`](crate::session::OxrSession::load_controller_model).
+    pub fn enable_msft_controller_model(&mut self) -> &mut Self {
+        self.0.msft_controller_model = true;
+        self
+    }
+    pub fn disable_msft_controller_model(&mut self) -> &mut Self {
+        self.0.msft_controller_model = false;
+        self
+    }
+    /// Allows creating, saving and loading spatial anchors, see [`create_spatial_anchor`](crate::session::OxrSession::create_spatial_anchor).
+    pub fn enable_fb_spatial_anchors(&mut self) -> &mut Self {
+        self.0.fb_spatial_entity = true;
+        self.0.fb_spatial_entity_query = true;
+        self.0.fb_spatial_entity_storage = true;
+        self
+    }
+    pub fn disable_fb_spatial_anchors(&mut self) -> &mut Self {
+        self.0.fb_spatial_entity = false;
+        self.0.fb_spatial_entity_query = false;
+        self.0.fb_spatial_entity_storage = false;
+        self
+    }
+    /// Allows picking the refresh rate of the display, see [`request_display_refresh_rate`](crate::session::OxrSession::request_display_refresh_rate).
+    pub fn enable_fb_display_refresh_rate(&mut self) -> &mut Self {
+        self.0.fb_display_refresh_rate = true;
+        self
+    }
+    pub fn disable_fb_display_refresh_rate(&mut self) -> &mut Self {
+        self.0.fb_display_refresh_rate = false;
+        self
+    }
+    /// Allows querying the hidden area of the views, see [`OxrVisibilityMask`](crate::features::visibility_mask::OxrVisibilityMask).
+    pub fn enable_khr_visibility_mask(&mut self) -> &mut Self {
+        self.0.khr_visibility_mask = true;
+        self
+    }
+    pub fn disable_khr_visibility_mask(&mut self) -> &mut Self {
+        self.0.khr_visibility_mask = false;
+        self
+    }
+    /// Allows submitting [`OxrCylinderLayer`](crate::features::quad_layer::OxrCylinderLayer)s.
+    pub fn enable_composition_layer_cylinder(&mut self) -> &mut Self {
+        self.0.khr_composition_layer_cylinder = true;
+        self
+    }
+    pub fn disable_composition_layer_cylinder(&mut self) -> &mut Self {
+        self.0.khr_composition_layer_cylinder = false;
+        self
+    }
+    /// Allows rendering the first person observer view used for mixed reality capture,
+    /// see [`OxrSecondaryViewPlugin`](crate::features::secondary_view::OxrSecondaryViewPlugin).
+    pub fn enable_msft_first_person_observer(&mut self) -> &mut Self {
+        self.0.msft_secondary_view_configuration = true;
+        self.0.msft_first_person_observer = true;
+        self
+    }
+    pub fn disable_msft_first_person_observer(&mut self) -> &mut Self {
+        self.0.msft_first_person_observer = false;
+        self
+    }
+    /// Submits the scene depth with the projection layer, see [`OxrDepthSwapchain`](crate::resources::OxrDepthSwapchain).
+    pub fn enable_composition_layer_depth(&mut self) -> &mut Self {
+        self.0.khr_composition_layer_depth = true;
+        self
+    }
+    pub fn disable_composition_layer_depth(&mut self) -> &mut Self {
+        self.0.khr_composition_layer_depth = false;
+        self
+    }
+    /// Allows submitting motion vectors and depth for application space warp,
+    /// see [`OxrSpaceWarpPlugin`](crate::features::space_warp::OxrSpaceWarpPlugin).
+    pub fn enable_fb_space_warp(&mut self) -> &mut Self {
+        self.0.fb_space_warp = true;
+        self
+    }
+    pub fn disable_fb_space_warp(&mut self) -> &mut Self {
+        self.0.fb_space_warp = false;
+        self
+    }
+    /// Allows converting between [`openxr::Time`] and the clocks of the operating system,
+    /// see [`convert_time_to_timespec`](crate::session::OxrSession::convert_time_to_timespec).
+    pub fn enable_time_conversion(&mut self) -> &mut Self {
+        #[cfg(windows)]
+        {
+            self.0.khr_win32_convert_performance_counter_time = true;
+        }
+        #[cfg(not(windows))]
+        {
+            self.0.khr_convert_timespec_time = true;
+        }
+        self
+    }
+    pub fn disable_time_conversion(&mut self) -> &mut Self {
+        #[cfg(windows)]
+        {
+            self.0.khr_win32_convert_performance_counter_time = false;
+        }
+        self.0.khr_convert_timespec_time = false;
+        self
+    }
     /// returns true if all of the extensions enabled are also available in `available_exts`
     pub fn is_available(&self, available_exts: &OxrExtensions) -> bool {
         self.clone() & available_exts.clone() == *self
     }
+    /// Returns the extensions enabled in both `self` and `other`.
+    ///
+    /// Use this with the [available extensions](crate::init::available_extensions) to only enable what the runtime supports.
+    pub fn intersect(&self, other: &OxrExtensions) -> OxrExtensions {
+        self.clone() & other.clone()
+    }
 }
 impl From<ExtensionSet> for OxrExtensions {
     fn from(value: ExtensionSet) -> Self {
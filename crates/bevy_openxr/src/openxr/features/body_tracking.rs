@@ -0,0 +1,226 @@
+use std::ptr;
+
+use bevy::prelude::*;
+use bevy_mod_xr::body::{XrBodyJoint, XrBodyJoints};
+use bevy_mod_xr::session::{XrPreDestroySession, XrSessionCreated, XrTrackingRoot};
+use bevy_mod_xr::spaces::{XrPrimaryReferenceSpace, XrReferenceSpace, XrSpace};
+use openxr::{sys, AnyGraphics};
+
+use crate::helper_traits::{ToQuat, ToVec3};
+use crate::resources::{OxrFrameState, Pipelined};
+use crate::session::OxrSession;
+use crate::spaces::OxrSpaceLocationFlags;
+use crate::{openxr_session_running, OxrPreUpdateSet};
+
+/// Number of joints in the default joint set of `XR_FB_body_tracking`.
+pub const BODY_JOINT_COUNT_FB: usize = 70;
+
+/// Spawns an entity with an [`OxrBodyTracker`] and [`XrBodyJoints`] when a session is created
+/// and updates the joints every frame.
+///
+/// Does nothing unless [`enable_fb_body_tracking`](crate::exts::OxrExtensions::enable_fb_body_tracking) was used.
+pub struct OxrBodyTrackingPlugin;
+
+impl Plugin for OxrBodyTrackingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(XrSessionCreated, spawn_body_tracker)
+            .add_systems(XrPreDestroySession, clean_up_body_trackers)
+            .add_systems(
+                PreUpdate,
+                update_body_joints
+                    .in_set(OxrPreUpdateSet::UpdateNonCriticalComponents)
+                    .run_if(openxr_session_running),
+            );
+    }
+}
+
+/// Wrapper around an `XrBodyTrackerFB`, destroyed when dropped.
+#[derive(Component)]
+pub struct OxrBodyTracker {
+    session: openxr::Session<AnyGraphics>,
+    handle: sys::BodyTrackerFB,
+}
+
+impl OxrBodyTracker {
+    pub fn as_raw(&self) -> sys::BodyTrackerFB {
+        self.handle
+    }
+}
+
+impl Drop for OxrBodyTracker {
+    fn drop(&mut self) {
+        if let Some(fp) = self.session.instance().exts().fb_body_tracking.as_ref() {
+            unsafe {
+                (fp.destroy_body_tracker)(self.handle);
+            }
+        }
+    }
+}
+
+/// The joint locations returned by [`OxrSession::locate_body_joints`].
+pub struct OxrBodyJointLocations {
+    pub confidence: f32,
+    pub joints: Vec<sys::BodyJointLocationFB>,
+}
+
+impl OxrSession {
+    /// Creates a body tracker using the default joint set.
+    ///
+    /// Requires [`XR_FB_body_tracking`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_FB_body_tracking).
+    pub fn create_body_tracker(&self) -> openxr::Result<OxrBodyTracker> {
+        let fp = self
+            .instance()
+            .exts()
+            .fb_body_tracking
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)?;
+        let info = sys::BodyTrackerCreateInfoFB {
+            ty: sys::BodyTrackerCreateInfoFB::TYPE,
+            next: ptr::null(),
+            body_joint_set: sys::BodyJointSetFB::DEFAULT,
+        };
+        let mut handle = sys::BodyTrackerFB::NULL;
+        cvt(unsafe { (fp.create_body_tracker)(self.as_raw(), &info, &mut handle) })?;
+        Ok(OxrBodyTracker {
+            session: self.0.clone(),
+            handle,
+        })
+    }
+
+    /// Locates the joints of a body tracker relative to `base`.
+    ///
+    /// Returns [`None`] if the body isn't currently tracked.
+    pub fn locate_body_joints(
+        &self,
+        tracker: &OxrBodyTracker,
+        base: &XrSpace,
+        time: openxr::Time,
+    ) -> openxr::Result<Option<OxrBodyJointLocations>> {
+        let fp = self
+            .instance()
+            .exts()
+            .fb_body_tracking
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)?;
+        let info = sys::BodyJointsLocateInfoFB {
+            ty: sys::BodyJointsLocateInfoFB::TYPE,
+            next: ptr::null(),
+            base_space: base.as_raw_openxr_space(),
+            time,
+        };
+        let mut joints = vec![
+            sys::BodyJointLocationFB {
+                location_flags: openxr::SpaceLocationFlags::EMPTY,
+                pose: openxr::Posef::IDENTITY,
+            };
+            BODY_JOINT_COUNT_FB
+        ];
+        let mut locations = sys::BodyJointLocationsFB {
+            ty: sys::BodyJointLocationsFB::TYPE,
+            next: ptr::null_mut(),
+            is_active: false.into(),
+            confidence: 0.0,
+            joint_count: joints.len() as u32,
+            joint_locations: joints.as_mut_ptr(),
+            skeleton_changed_count: 0,
+            time: openxr::Time::from_nanos(0),
+        };
+        cvt(unsafe { (fp.locate_body_joints)(tracker.handle, &info, &mut locations) })?;
+        if !bool::from(locations.is_active) {
+            return Ok(None);
+        }
+        Ok(Some(OxrBodyJointLocations {
+            confidence: locations.confidence,
+            joints,
+        }))
+    }
+}
+
+fn spawn_body_tracker(session: Res<OxrSession>, mut cmds: Commands) {
+    if session.instance().exts().fb_body_tracking.is_none() {
+        return;
+    }
+    match session.create_body_tracker() {
+        Ok(tracker) => {
+            cmds.spawn((
+                tracker,
+                XrBodyJoints {
+                    joints: vec![XrBodyJoint::default(); BODY_JOINT_COUNT_FB],
+                    ..default()
+                },
+            ));
+        }
+        Err(err) => warn!("Error while creating body tracker: {err}"),
+    }
+}
+
+fn clean_up_body_trackers(mut cmds: Commands, query: Query<Entity, With<OxrBodyTracker>>) {
+    for e in &query {
+        cmds.entity(e).despawn_recursive();
+    }
+}
+
+fn update_body_joints(
+    default_ref_space: Res<XrPrimaryReferenceSpace>,
+    frame_state: Res<OxrFrameState>,
+    session: Res<OxrSession>,
+    root_query: Query<&GlobalTransform, With<XrTrackingRoot>>,
+    mut tracker_query: Query<(
+        &OxrBodyTracker,
+        Option<&XrReferenceSpace>,
+        &mut XrBodyJoints,
+    )>,
+    pipelined: Option<Res<Pipelined>>,
+) {
+    let time = if pipelined.is_some() {
+        openxr::Time::from_nanos(
+            frame_state.predicted_display_time.as_nanos()
+                + frame_state.predicted_display_period.as_nanos(),
+        )
+    } else {
+        frame_state.predicted_display_time
+    };
+    let root = root_query.get_single().copied().unwrap_or_default();
+    for (tracker, ref_space, mut joints) in &mut tracker_query {
+        let ref_space = ref_space.map(|v| &v.0).unwrap_or(&default_ref_space.0);
+        let locations = match session.locate_body_joints(tracker, ref_space, time) {
+            Ok(Some(v)) => v,
+            Ok(None) => {
+                joints.tracked = false;
+                continue;
+            }
+            Err(err) => {
+                warn!("Error while locating body joints: {err}");
+                joints.tracked = false;
+                continue;
+            }
+        };
+        joints.tracked = true;
+        joints.confidence = locations.confidence;
+        for (joint, location) in joints.iter_mut().zip(locations.joints.iter()) {
+            let flags = OxrSpaceLocationFlags(location.location_flags);
+            let (_, mut rotation, mut translation) =
+                joint.transform.to_scale_rotation_translation();
+            if flags.pos_valid() {
+                translation = root.transform_point(location.pose.position.to_vec3());
+            }
+            if flags.rot_valid() {
+                rotation = root.rotation() * location.pose.orientation.to_quat();
+            }
+            joint.transform = GlobalTransform::from(Transform {
+                translation,
+                rotation,
+                ..default()
+            });
+            joint.location_flags = flags.into();
+        }
+    }
+}
+
+fn cvt(x: sys::Result) -> openxr::Result<sys::Result> {
+    if x.into_raw() >= 0 {
+        Ok(x)
+    } else {
+        Err(x)
+    }
+}
@@ -0,0 +1,156 @@
+use std::ffi::c_char;
+use std::{mem, ptr};
+
+use bevy::prelude::*;
+use openxr::sys;
+
+use crate::helper_traits::ToTransform;
+use crate::session::OxrSession;
+use crate::spaces::get_arr_init;
+
+/// A node of a controller model that the runtime animates, e.g. a button or a thumbstick.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OxrControllerModelNode {
+    /// Name of the node in the glTF model.
+    pub name: String,
+    /// Name of the parent node in the glTF model, empty if the node is the root.
+    pub parent_name: String,
+    /// Transform of the node relative to its parent node.
+    pub transform: Transform,
+}
+
+impl OxrSession {
+    /// Returns the key of the controller model for a top level user path, e.g. `/user/hand/left`.
+    ///
+    /// Returns [`None`] if there is no model available yet, which is the case until the controller was detected.
+    ///
+    /// Requires [`XR_MSFT_controller_model`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_MSFT_controller_model).
+    pub fn controller_model_key(
+        &self,
+        subaction_path: openxr::Path,
+    ) -> openxr::Result<Option<sys::ControllerModelKeyMSFT>> {
+        let fp = self.controller_model_fp()?;
+        let mut key_state = sys::ControllerModelKeyStateMSFT {
+            ty: sys::ControllerModelKeyStateMSFT::TYPE,
+            next: ptr::null_mut(),
+            model_key: sys::ControllerModelKeyMSFT::from_raw(0),
+        };
+        cvt(unsafe {
+            (fp.get_controller_model_key)(self.as_raw(), subaction_path, &mut key_state)
+        })?;
+        if key_state.model_key.into_raw() == 0 {
+            return Ok(None);
+        }
+        Ok(Some(key_state.model_key))
+    }
+
+    /// Loads the controller model for a top level user path as a binary glTF (`.glb`).
+    ///
+    /// Returns [`None`] if there is no model available yet, see [`controller_model_key`](Self::controller_model_key).
+    ///
+    /// Requires [`XR_MSFT_controller_model`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_MSFT_controller_model).
+    pub fn load_controller_model(
+        &self,
+        subaction_path: openxr::Path,
+    ) -> openxr::Result<Option<Vec<u8>>> {
+        let fp = self.controller_model_fp()?;
+        let Some(key) = self.controller_model_key(subaction_path)? else {
+            return Ok(None);
+        };
+        match get_arr_init(0u8, |capacity, count, buf| unsafe {
+            (fp.load_controller_model)(self.as_raw(), key, capacity, count, buf)
+        }) {
+            Ok(data) => Ok(Some(data)),
+            // the key can become invalid if the controller was switched in the meantime
+            Err(sys::Result::ERROR_CONTROLLER_MODEL_KEY_INVALID_MSFT) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the current transforms of the animatable nodes of a controller model,
+    /// which should be applied to the nodes with the same names in the glTF model.
+    ///
+    /// Requires [`XR_MSFT_controller_model`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_MSFT_controller_model).
+    pub fn controller_model_nodes(
+        &self,
+        model_key: sys::ControllerModelKeyMSFT,
+    ) -> openxr::Result<Vec<OxrControllerModelNode>> {
+        let fp = self.controller_model_fp()?;
+
+        let mut properties = sys::ControllerModelPropertiesMSFT {
+            ty: sys::ControllerModelPropertiesMSFT::TYPE,
+            next: ptr::null_mut(),
+            node_capacity_input: 0,
+            node_count_output: 0,
+            node_properties: ptr::null_mut(),
+        };
+        cvt(unsafe {
+            (fp.get_controller_model_properties)(self.as_raw(), model_key, &mut properties)
+        })?;
+        let mut node_properties = vec![
+            {
+                let mut node: sys::ControllerModelNodePropertiesMSFT = unsafe { mem::zeroed() };
+                node.ty = sys::ControllerModelNodePropertiesMSFT::TYPE;
+                node
+            };
+            properties.node_count_output as usize
+        ];
+        properties.node_capacity_input = node_properties.len() as u32;
+        properties.node_properties = node_properties.as_mut_ptr();
+        cvt(unsafe {
+            (fp.get_controller_model_properties)(self.as_raw(), model_key, &mut properties)
+        })?;
+
+        let mut node_states = vec![
+            sys::ControllerModelNodeStateMSFT {
+                ty: sys::ControllerModelNodeStateMSFT::TYPE,
+                next: ptr::null_mut(),
+                node_pose: openxr::Posef::IDENTITY,
+            };
+            node_properties.len()
+        ];
+        let mut state = sys::ControllerModelStateMSFT {
+            ty: sys::ControllerModelStateMSFT::TYPE,
+            next: ptr::null_mut(),
+            node_capacity_input: node_states.len() as u32,
+            node_count_output: 0,
+            node_states: node_states.as_mut_ptr(),
+        };
+        cvt(unsafe { (fp.get_controller_model_state)(self.as_raw(), model_key, &mut state) })?;
+
+        Ok(node_properties
+            .iter()
+            .zip(node_states.iter())
+            .map(|(properties, state)| OxrControllerModelNode {
+                name: c_str_to_string(&properties.node_name),
+                parent_name: c_str_to_string(&properties.parent_node_name),
+                transform: state.node_pose.to_transform(),
+            })
+            .collect())
+    }
+
+    fn controller_model_fp(&self) -> openxr::Result<&openxr::raw::ControllerModelMSFT> {
+        self.instance()
+            .exts()
+            .msft_controller_model
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)
+    }
+}
+
+fn c_str_to_string(chars: &[c_char]) -> String {
+    let bytes = chars
+        .iter()
+        .take_while(|c| **c != 0)
+        .map(|c| *c as u8)
+        .collect::<Vec<_>>();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn cvt(x: sys::Result) -> openxr::Result<sys::Result> {
+    if x.into_raw() >= 0 {
+        Ok(x)
+    } else {
+        Err(x)
+    }
+}
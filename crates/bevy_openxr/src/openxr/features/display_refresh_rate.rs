@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use openxr::{sys, Event};
+
+use crate::exts::OxrEnabledExtensions;
+use crate::poll_events::{OxrEvent, OxrEventHandlerExt};
+use crate::session::OxrSession;
+use crate::spaces::get_arr_init;
+
+/// Sends an [`OxrDisplayRefreshRateChanged`] whenever the runtime changes the refresh rate of the display.
+///
+/// Requires [`enable_fb_display_refresh_rate`](crate::exts::OxrExtensions::enable_fb_display_refresh_rate).
+pub struct OxrDisplayRefreshRatePlugin;
+
+impl Plugin for OxrDisplayRefreshRatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OxrDisplayRefreshRateChanged>();
+        if app
+            .world()
+            .get_resource::<OxrEnabledExtensions>()
+            .is_some_and(|exts| exts.fb_display_refresh_rate)
+        {
+            app.add_oxr_event_handler(handle_refresh_rate_event);
+        }
+    }
+}
+
+/// Sent when the refresh rate of the display changed, e.g. after [`OxrSession::request_display_refresh_rate`].
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub struct OxrDisplayRefreshRateChanged {
+    /// The previous refresh rate in Hz.
+    pub from: f32,
+    /// The new refresh rate in Hz.
+    pub to: f32,
+}
+
+fn handle_refresh_rate_event(
+    event: In<OxrEvent>,
+    mut writer: EventWriter<OxrDisplayRefreshRateChanged>,
+) {
+    // this unwrap will never panic since we are in a valid scope
+    if let Event::DisplayRefreshRateChangedFB(event) = unsafe { event.get() }.unwrap() {
+        writer.send(OxrDisplayRefreshRateChanged {
+            from: event.from_display_refresh_rate(),
+            to: event.to_display_refresh_rate(),
+        });
+    }
+}
+
+impl OxrSession {
+    /// Returns the refresh rates in Hz the display supports.
+    ///
+    /// Requires [`XR_FB_display_refresh_rate`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_FB_display_refresh_rate).
+    pub fn enumerate_display_refresh_rates(&self) -> openxr::Result<Vec<f32>> {
+        let fp = self.display_refresh_rate_fp()?;
+        get_arr_init(0.0, |capacity, count, buf| unsafe {
+            (fp.enumerate_display_refresh_rates)(self.as_raw(), capacity, count, buf)
+        })
+    }
+
+    /// Returns the current refresh rate of the display in Hz.
+    ///
+    /// Requires [`XR_FB_display_refresh_rate`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_FB_display_refresh_rate).
+    pub fn get_display_refresh_rate(&self) -> openxr::Result<f32> {
+        let fp = self.display_refresh_rate_fp()?;
+        let mut rate = 0.0;
+        cvt(unsafe { (fp.get_display_refresh_rate)(self.as_raw(), &mut rate) })?;
+        Ok(rate)
+    }
+
+    /// Requests a refresh rate of the display in Hz, which has to be one of the
+    /// [`enumerate_display_refresh_rates`](Self::enumerate_display_refresh_rates).
+    /// Pass `0.0` to let the runtime pick the refresh rate again.
+    ///
+    /// The change isn't immediate, an [`OxrDisplayRefreshRateChanged`] is sent once it is applied.
+    ///
+    /// Requires [`XR_FB_display_refresh_rate`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_FB_display_refresh_rate).
+    pub fn request_display_refresh_rate(&self, rate: f32) -> openxr::Result<()> {
+        let fp = self.display_refresh_rate_fp()?;
+        cvt(unsafe { (fp.request_display_refresh_rate)(self.as_raw(), rate) })?;
+        Ok(())
+    }
+
+    fn display_refresh_rate_fp(&self) -> openxr::Result<&openxr::raw::DisplayRefreshRateFB> {
+        self.instance()
+            .exts()
+            .fb_display_refresh_rate
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)
+    }
+}
+
+fn cvt(x: sys::Result) -> openxr::Result<sys::Result> {
+    if x.into_raw() >= 0 {
+        Ok(x)
+    } else {
+        Err(x)
+    }
+}
@@ -0,0 +1,206 @@
+use std::ptr;
+
+use bevy::prelude::*;
+use bevy_mod_xr::face::XrFaceWeights;
+use bevy_mod_xr::session::{XrPreDestroySession, XrSessionCreated};
+use openxr::{sys, AnyGraphics};
+
+use crate::resources::{OxrFrameState, Pipelined};
+use crate::session::OxrSession;
+use crate::{openxr_session_running, OxrPreUpdateSet};
+
+/// Number of expressions in the default expression set of `XR_FB_face_tracking2`.
+pub const FACE_EXPRESSION_COUNT_FB: usize = 70;
+/// Number of confidence regions of `XR_FB_face_tracking2`, the lower and upper face.
+pub const FACE_CONFIDENCE_COUNT_FB: usize = 2;
+
+/// Spawns an entity with an [`OxrFaceTracker`] and [`XrFaceWeights`] when a session is created
+/// and updates the weights every frame.
+///
+/// Does nothing unless [`enable_fb_face_tracking2`](crate::exts::OxrExtensions::enable_fb_face_tracking2) was used.
+pub struct OxrFaceTrackingPlugin;
+
+impl Plugin for OxrFaceTrackingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(XrSessionCreated, spawn_face_tracker)
+            .add_systems(XrPreDestroySession, clean_up_face_trackers)
+            .add_systems(
+                PreUpdate,
+                update_face_weights
+                    .in_set(OxrPreUpdateSet::UpdateNonCriticalComponents)
+                    .run_if(openxr_session_running),
+            );
+    }
+}
+
+/// Wrapper around an `XrFaceTracker2FB`, destroyed when dropped.
+#[derive(Component)]
+pub struct OxrFaceTracker {
+    session: openxr::Session<AnyGraphics>,
+    handle: sys::FaceTracker2FB,
+}
+
+impl OxrFaceTracker {
+    pub fn as_raw(&self) -> sys::FaceTracker2FB {
+        self.handle
+    }
+}
+
+impl Drop for OxrFaceTracker {
+    fn drop(&mut self) {
+        if let Some(fp) = self.session.instance().exts().fb_face_tracking2.as_ref() {
+            unsafe {
+                (fp.destroy_face_tracker2)(self.handle);
+            }
+        }
+    }
+}
+
+/// The weights returned by [`OxrSession::get_face_expression_weights`].
+pub struct OxrFaceExpressionWeights {
+    pub weights: Vec<f32>,
+    pub confidences: Vec<f32>,
+    /// `false` if the runtime couldn't track the face, the weights are meaningless in that case.
+    pub is_valid: bool,
+    pub is_eye_following_valid: bool,
+    pub data_source: sys::FaceTrackingDataSource2FB,
+}
+
+impl OxrSession {
+    /// Creates a face tracker using the default expression set, driven by the cameras of the headset.
+    ///
+    /// Requires [`XR_FB_face_tracking2`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_FB_face_tracking2).
+    pub fn create_face_tracker(&self) -> openxr::Result<OxrFaceTracker> {
+        let fp = self
+            .instance()
+            .exts()
+            .fb_face_tracking2
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)?;
+        let mut data_sources = [sys::FaceTrackingDataSource2FB::VISUAL];
+        let info = sys::FaceTrackerCreateInfo2FB {
+            ty: sys::FaceTrackerCreateInfo2FB::TYPE,
+            next: ptr::null(),
+            face_expression_set: sys::FaceExpressionSet2FB::DEFAULT,
+            requested_data_source_count: data_sources.len() as u32,
+            requested_data_sources: data_sources.as_mut_ptr(),
+        };
+        let mut handle = sys::FaceTracker2FB::NULL;
+        cvt(unsafe { (fp.create_face_tracker2)(self.as_raw(), &info, &mut handle) })?;
+        Ok(OxrFaceTracker {
+            session: self.0.clone(),
+            handle,
+        })
+    }
+
+    /// Returns the expression weights of a face tracker at `time`.
+    pub fn get_face_expression_weights(
+        &self,
+        tracker: &OxrFaceTracker,
+        time: openxr::Time,
+    ) -> openxr::Result<OxrFaceExpressionWeights> {
+        let fp = self
+            .instance()
+            .exts()
+            .fb_face_tracking2
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)?;
+        let info = sys::FaceExpressionInfo2FB {
+            ty: sys::FaceExpressionInfo2FB::TYPE,
+            next: ptr::null(),
+            time,
+        };
+        let mut weights = vec![0.0; FACE_EXPRESSION_COUNT_FB];
+        let mut confidences = vec![0.0; FACE_CONFIDENCE_COUNT_FB];
+        let mut expression_weights = sys::FaceExpressionWeights2FB {
+            ty: sys::FaceExpressionWeights2FB::TYPE,
+            next: ptr::null_mut(),
+            weight_count: weights.len() as u32,
+            weights: weights.as_mut_ptr(),
+            confidence_count: confidences.len() as u32,
+            confidences: confidences.as_mut_ptr(),
+            is_valid: false.into(),
+            is_eye_following_blendshapes_valid: false.into(),
+            data_source: sys::FaceTrackingDataSource2FB::VISUAL,
+            time: openxr::Time::from_nanos(0),
+        };
+        cvt(unsafe {
+            (fp.get_face_expression_weights2)(tracker.handle, &info, &mut expression_weights)
+        })?;
+        Ok(OxrFaceExpressionWeights {
+            weights,
+            confidences,
+            is_valid: expression_weights.is_valid.into(),
+            is_eye_following_valid: expression_weights.is_eye_following_blendshapes_valid.into(),
+            data_source: expression_weights.data_source,
+        })
+    }
+}
+
+fn spawn_face_tracker(session: Res<OxrSession>, mut cmds: Commands) {
+    if session.instance().exts().fb_face_tracking2.is_none() {
+        return;
+    }
+    match session.create_face_tracker() {
+        Ok(tracker) => {
+            cmds.spawn((
+                tracker,
+                XrFaceWeights {
+                    weights: vec![0.0; FACE_EXPRESSION_COUNT_FB],
+                    confidences: vec![0.0; FACE_CONFIDENCE_COUNT_FB],
+                    ..default()
+                },
+            ));
+        }
+        Err(err) => warn!("Error while creating face tracker: {err}"),
+    }
+}
+
+fn clean_up_face_trackers(mut cmds: Commands, query: Query<Entity, With<OxrFaceTracker>>) {
+    for e in &query {
+        cmds.entity(e).despawn_recursive();
+    }
+}
+
+fn update_face_weights(
+    frame_state: Res<OxrFrameState>,
+    session: Res<OxrSession>,
+    mut tracker_query: Query<(&OxrFaceTracker, &mut XrFaceWeights)>,
+    pipelined: Option<Res<Pipelined>>,
+) {
+    let time = if pipelined.is_some() {
+        openxr::Time::from_nanos(
+            frame_state.predicted_display_time.as_nanos()
+                + frame_state.predicted_display_period.as_nanos(),
+        )
+    } else {
+        frame_state.predicted_display_time
+    };
+    for (tracker, mut face) in &mut tracker_query {
+        let expression_weights = match session.get_face_expression_weights(tracker, time) {
+            Ok(v) => v,
+            Err(err) => {
+                warn!("Error while getting face expression weights: {err}");
+                face.valid = false;
+                continue;
+            }
+        };
+        // keep the last valid weights around instead of snapping the face to neutral
+        if !expression_weights.is_valid {
+            face.valid = false;
+            continue;
+        }
+        face.valid = true;
+        face.eye_following_valid = expression_weights.is_eye_following_valid;
+        face.weights = expression_weights.weights;
+        face.confidences = expression_weights.confidences;
+    }
+}
+
+fn cvt(x: sys::Result) -> openxr::Result<sys::Result> {
+    if x.into_raw() >= 0 {
+        Ok(x)
+    } else {
+        Err(x)
+    }
+}
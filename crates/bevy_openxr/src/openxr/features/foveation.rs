@@ -0,0 +1,146 @@
+use std::ptr;
+
+use bevy::prelude::*;
+use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
+use bevy::render::{Render, RenderApp, RenderSet};
+use openxr::sys;
+
+use crate::exts::OxrEnabledExtensions;
+use crate::resources::OxrSwapchain;
+use crate::session::OxrSession;
+
+/// Applies the [`OxrFoveationProfile`] to the swapchain whenever it changes.
+///
+/// Requires [`enable_fb_foveation`](crate::exts::OxrExtensions::enable_fb_foveation).
+pub struct OxrFoveationPlugin;
+
+impl Plugin for OxrFoveationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OxrFoveationProfile>();
+        let enabled = app
+            .world()
+            .get_resource::<OxrEnabledExtensions>()
+            .is_some_and(|exts| {
+                exts.fb_foveation
+                    && exts.fb_foveation_configuration
+                    && exts.fb_swapchain_update_state
+            });
+        if !enabled {
+            return;
+        }
+        app.add_plugins(ExtractResourcePlugin::<OxrFoveationProfile>::default());
+        app.sub_app_mut(RenderApp).add_systems(
+            Render,
+            apply_foveation_profile
+                .run_if(resource_exists::<OxrFoveationProfile>)
+                .run_if(resource_exists::<OxrSwapchain>)
+                .in_set(RenderSet::PrepareAssets),
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FoveationLevel {
+    #[default]
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+impl From<FoveationLevel> for sys::FoveationLevelFB {
+    fn from(level: FoveationLevel) -> Self {
+        match level {
+            FoveationLevel::Off => sys::FoveationLevelFB::NONE,
+            FoveationLevel::Low => sys::FoveationLevelFB::LOW,
+            FoveationLevel::Medium => sys::FoveationLevelFB::MEDIUM,
+            FoveationLevel::High => sys::FoveationLevelFB::HIGH,
+        }
+    }
+}
+
+/// Fixed foveated rendering settings, rendering the edges of the view at a lower resolution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Resource, ExtractResource)]
+pub struct OxrFoveationProfile {
+    pub level: FoveationLevel,
+    /// Moves the high resolution area up (positive) or down (negative), in degrees.
+    pub vertical_offset: f32,
+    /// Lets the runtime lower the foveation level when the GPU isn't fully utilized,
+    /// using `level` as the maximum.
+    pub dynamic: bool,
+}
+
+impl OxrSession {
+    /// Creates a foveation profile and applies it to the swapchain.
+    ///
+    /// Requires [`XR_FB_foveation`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_FB_foveation),
+    /// `XR_FB_foveation_configuration` and `XR_FB_swapchain_update_state`.
+    pub fn apply_foveation_profile(
+        &self,
+        swapchain: &OxrSwapchain,
+        profile: &OxrFoveationProfile,
+    ) -> openxr::Result<()> {
+        let exts = self.instance().exts();
+        let (Some(foveation), Some(update_state)) = (
+            exts.fb_foveation.as_ref(),
+            exts.fb_swapchain_update_state.as_ref(),
+        ) else {
+            return Err(sys::Result::ERROR_EXTENSION_NOT_PRESENT);
+        };
+        let mut level_info = sys::FoveationLevelProfileCreateInfoFB {
+            ty: sys::FoveationLevelProfileCreateInfoFB::TYPE,
+            next: ptr::null_mut(),
+            level: profile.level.into(),
+            vertical_offset: profile.vertical_offset,
+            dynamic: if profile.dynamic {
+                sys::FoveationDynamicFB::LEVEL_ENABLED
+            } else {
+                sys::FoveationDynamicFB::DISABLED
+            },
+        };
+        let create_info = sys::FoveationProfileCreateInfoFB {
+            ty: sys::FoveationProfileCreateInfoFB::TYPE,
+            next: &mut level_info as *mut _ as _,
+        };
+        let mut handle = sys::FoveationProfileFB::NULL;
+        cvt(unsafe {
+            (foveation.create_foveation_profile)(self.as_raw(), &create_info, &mut handle)
+        })?;
+        let state = sys::SwapchainStateFoveationFB {
+            ty: sys::SwapchainStateFoveationFB::TYPE,
+            next: ptr::null_mut(),
+            flags: sys::SwapchainStateFoveationFlagsFB::EMPTY,
+            profile: handle,
+        };
+        let result = cvt(unsafe {
+            (update_state.update_swapchain)(swapchain.as_raw(), &state as *const _ as _)
+        });
+        // the swapchain keeps using the profile after it is destroyed
+        unsafe { (foveation.destroy_foveation_profile)(handle) };
+        result.map(|_| ())
+    }
+}
+
+fn apply_foveation_profile(
+    session: Res<OxrSession>,
+    swapchain: Res<OxrSwapchain>,
+    profile: Res<OxrFoveationProfile>,
+    mut applied_to: Local<Option<sys::Swapchain>>,
+) {
+    // the swapchain is replaced when the session or the swapchain is recreated
+    if !profile.is_changed() && *applied_to == Some(swapchain.as_raw()) {
+        return;
+    }
+    *applied_to = Some(swapchain.as_raw());
+    if let Err(err) = session.apply_foveation_profile(&swapchain, &profile) {
+        error!("Failed to apply foveation profile: {err}");
+    }
+}
+
+fn cvt(x: sys::Result) -> openxr::Result<sys::Result> {
+    if x.into_raw() >= 0 {
+        Ok(x)
+    } else {
+        Err(x)
+    }
+}
@@ -1,22 +1,25 @@
+use std::f32::consts::TAU;
+use std::ptr;
+
 use bevy::prelude::*;
 use bevy_mod_xr::hands::{
     spawn_hand_bones, HandBone, HandSide, SpawnHandTracker, SpawnHandTrackerCommandExecutor,
-    XrHandBoneRadius,
+    XrFollowHandJoint, XrHandBoneRadius, XrHandDataSource, XrHandJoints, XrHandTrackingActive,
 };
 use bevy_mod_xr::hands::{LeftHand, RightHand, XrHandBoneEntities};
 use bevy_mod_xr::session::{XrPreDestroySession, XrSessionCreated};
 use bevy_mod_xr::spaces::{
-    XrPrimaryReferenceSpace, XrReferenceSpace, XrSpaceLocationFlags, XrSpaceVelocityFlags,
+    XrPrimaryReferenceSpace, XrReferenceSpace, XrSpace, XrSpaceLocationFlags, XrSpaceVelocityFlags,
     XrVelocity,
 };
-use openxr::{SpaceLocationFlags, SpaceVelocityFlags};
+use openxr::{sys, SpaceLocationFlags, SpaceVelocityFlags, HAND_JOINT_COUNT};
 
-use crate::helper_traits::ToVec3;
+use crate::helper_traits::{ToQuat, ToVec3};
 use crate::resources::OxrFrameState;
 use crate::resources::Pipelined;
 use crate::session::OxrSession;
-use crate::spaces::{OxrSpaceLocationFlags, OxrSpaceVelocityFlags};
-use crate::{openxr_session_available, openxr_session_running};
+use crate::spaces::{OxrSpaceExt as _, OxrSpaceLocationFlags, OxrSpaceVelocityFlags};
+use crate::{openxr_session_available, openxr_session_running, OxrPreUpdateSet};
 
 pub struct HandTrackingPlugin {
     default_hands: bool,
@@ -31,7 +34,24 @@ impl Default for HandTrackingPlugin {
 
 impl Plugin for HandTrackingPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PreUpdate, locate_hands.run_if(openxr_session_running));
+        app.init_resource::<XrHandTrackingActive>()
+            .init_resource::<OxrHandTrackingDataSources>()
+            .add_systems(
+                PreUpdate,
+                (
+                    locate_hands.run_if(hand_tracking_active),
+                    update_hand_joints.run_if(hand_tracking_active),
+                    clear_hand_flags.run_if(
+                        resource_changed::<XrHandTrackingActive>.and(not(hand_tracking_active)),
+                    ),
+                    update_hand_joint_followers
+                        .after(locate_hands)
+                        .after(update_hand_joints)
+                        .after(clear_hand_flags),
+                )
+                    .in_set(OxrPreUpdateSet::UpdateNonCriticalComponents)
+                    .run_if(openxr_session_running),
+            );
         if self.default_hands {
             app.add_systems(XrPreDestroySession, clean_up_default_hands)
                 .add_systems(XrSessionCreated, spawn_default_hands);
@@ -45,15 +65,29 @@ fn set_spawn_executor(mut cmds: Commands) {
 }
 
 fn handle_tracker_spawn(world: &mut World, tracker: Entity, side: HandSide) {
-    let Some(session) = world.get_resource_mut::<OxrSession>() else {
+    let Some(session) = world.get_resource::<OxrSession>() else {
         error!("unable to get session while creating hand tracker");
         return;
     };
     debug!("spawning hand");
-    let oxr_tracker = match session.create_hand_tracker(match side {
+    let hand = match side {
         HandSide::Left => openxr::HandEXT::LEFT,
         HandSide::Right => openxr::HandEXT::RIGHT,
-    }) {
+    };
+    let data_sources = world.get_resource::<OxrHandTrackingDataSources>();
+    let result = match data_sources {
+        Some(data_sources)
+            if session
+                .instance()
+                .exts()
+                .ext_hand_tracking_data_source
+                .is_some() =>
+        {
+            session.create_hand_tracker_with_data_sources(hand, &data_sources.0)
+        }
+        _ => session.create_hand_tracker(hand),
+    };
+    let oxr_tracker = match result {
         Ok(t) => t,
         Err(openxr::sys::Result::ERROR_EXTENSION_NOT_PRESENT) => {
             warn!("Handtracking Extension not loaded, Unable to create Handtracker!");
@@ -115,8 +149,424 @@ fn clean_up_default_hands(
 }
 
 #[derive(Deref, DerefMut, Component)]
+#[require(HandJointFilter)]
 pub struct OxrHandTracker(pub openxr::HandTracker);
 
+/// The data sources hand trackers are created with, in order of preference.
+///
+/// Remove [`CONTROLLER`](sys::HandTrackingDataSourceEXT::CONTROLLER) to ignore joints synthesized from controllers,
+/// the hand is reported as not tracked while it holds a controller then.
+/// Changes only apply to hand trackers created afterwards.
+///
+/// Requires [`enable_hand_tracking_data_source`](crate::exts::OxrExtensions::enable_hand_tracking_data_source),
+/// the runtime picks the data sources otherwise.
+#[derive(Clone, Debug, PartialEq, Eq, Resource)]
+pub struct OxrHandTrackingDataSources(pub Vec<sys::HandTrackingDataSourceEXT>);
+
+impl Default for OxrHandTrackingDataSources {
+    fn default() -> Self {
+        Self(vec![
+            sys::HandTrackingDataSourceEXT::UNOBSTRUCTED,
+            sys::HandTrackingDataSourceEXT::CONTROLLER,
+        ])
+    }
+}
+
+impl OxrSession {
+    /// Creates a hand tracker that only uses `data_sources`.
+    ///
+    /// Requires [`XR_EXT_hand_tracking_data_source`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_EXT_hand_tracking_data_source).
+    pub fn create_hand_tracker_with_data_sources(
+        &self,
+        hand: openxr::HandEXT,
+        data_sources: &[sys::HandTrackingDataSourceEXT],
+    ) -> openxr::Result<openxr::HandTracker> {
+        let fp = self
+            .instance()
+            .exts()
+            .ext_hand_tracking
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)?;
+        let mut data_sources = data_sources.to_vec();
+        let data_source_info = sys::HandTrackingDataSourceInfoEXT {
+            ty: sys::HandTrackingDataSourceInfoEXT::TYPE,
+            next: ptr::null(),
+            requested_data_source_count: data_sources.len() as u32,
+            requested_data_sources: data_sources.as_mut_ptr(),
+        };
+        let info = sys::HandTrackerCreateInfoEXT {
+            ty: sys::HandTrackerCreateInfoEXT::TYPE,
+            next: &data_source_info as *const _ as _,
+            hand,
+            hand_joint_set: openxr::HandJointSetEXT::DEFAULT,
+        };
+        let mut handle = sys::HandTrackerEXT::NULL;
+        cvt(unsafe { (fp.create_hand_tracker)(self.as_raw(), &info, &mut handle) })?;
+        Ok(unsafe { openxr::HandTracker::from_raw(&self.0, handle) })
+    }
+
+    /// Like [`OxrSession::locate_hand_joints_with_velocities`], but also returns the data source the joints
+    /// come from, read in the same `xrLocateHandJointsEXT` call.
+    ///
+    /// The data source is [`None`] if the runtime doesn't report one for this hand.
+    ///
+    /// Requires [`XR_EXT_hand_tracking_data_source`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_EXT_hand_tracking_data_source).
+    #[allow(clippy::type_complexity)]
+    pub fn locate_hand_joints_with_data_source(
+        &self,
+        tracker: &openxr::HandTracker,
+        base: &XrSpace,
+        time: openxr::Time,
+    ) -> openxr::Result<
+        Option<(
+            openxr::HandJointLocations,
+            openxr::HandJointVelocities,
+            Option<sys::HandTrackingDataSourceEXT>,
+        )>,
+    > {
+        let fp = self
+            .instance()
+            .exts()
+            .ext_hand_tracking
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)?;
+        if self
+            .instance()
+            .exts()
+            .ext_hand_tracking_data_source
+            .is_none()
+        {
+            return Err(sys::Result::ERROR_EXTENSION_NOT_PRESENT);
+        }
+        let locate_info = sys::HandJointsLocateInfoEXT {
+            ty: sys::HandJointsLocateInfoEXT::TYPE,
+            next: ptr::null(),
+            base_space: base.as_raw_openxr_space(),
+            time,
+        };
+        let mut state = sys::HandTrackingDataSourceStateEXT {
+            ty: sys::HandTrackingDataSourceStateEXT::TYPE,
+            next: ptr::null_mut(),
+            is_active: false.into(),
+            data_source: sys::HandTrackingDataSourceEXT::UNOBSTRUCTED,
+        };
+        let mut velocities = [sys::HandJointVelocityEXT {
+            velocity_flags: SpaceVelocityFlags::EMPTY,
+            linear_velocity: Default::default(),
+            angular_velocity: Default::default(),
+        }; HAND_JOINT_COUNT];
+        let mut velocity_info = sys::HandJointVelocitiesEXT {
+            ty: sys::HandJointVelocitiesEXT::TYPE,
+            next: &mut state as *mut _ as _,
+            joint_count: HAND_JOINT_COUNT as u32,
+            joint_velocities: velocities.as_mut_ptr(),
+        };
+        let mut joints = [sys::HandJointLocationEXT {
+            location_flags: SpaceLocationFlags::EMPTY,
+            pose: openxr::Posef::IDENTITY,
+            radius: 0.0,
+        }; HAND_JOINT_COUNT];
+        let mut locations = sys::HandJointLocationsEXT {
+            ty: sys::HandJointLocationsEXT::TYPE,
+            next: &mut velocity_info as *mut _ as _,
+            is_active: false.into(),
+            joint_count: HAND_JOINT_COUNT as u32,
+            joint_locations: joints.as_mut_ptr(),
+        };
+        cvt(unsafe { (fp.locate_hand_joints)(tracker.as_raw(), &locate_info, &mut locations) })?;
+        if !bool::from(locations.is_active) {
+            return Ok(None);
+        }
+        let data_source = bool::from(state.is_active).then_some(state.data_source);
+        Ok(Some((joints, velocities, data_source)))
+    }
+}
+
+/// Smooths the joints in [`XrHandJoints`] with a [one euro filter](https://gery.casiez.net/1euro/)
+/// to remove jitter, e.g. for gesture detection.
+///
+/// Not inserted by default, without this resource the joints are the raw poses reported by the runtime.
+/// The filter is reset when the hand loses tracking.
+#[derive(Clone, Copy, Debug, PartialEq, Resource)]
+pub struct OxrHandSmoothing {
+    /// Cutoff frequency in Hz while the joint is not moving, lower values remove more jitter but add lag.
+    pub min_cutoff: f32,
+    /// How much the cutoff frequency increases with the speed of the joint (in meters or radians per second),
+    /// higher values reduce lag during fast movements.
+    pub beta: f32,
+}
+
+impl Default for OxrHandSmoothing {
+    fn default() -> Self {
+        Self {
+            min_cutoff: 1.0,
+            beta: 10.0,
+        }
+    }
+}
+
+/// Cutoff frequency used to smooth the speed of a joint.
+const DERIVATIVE_CUTOFF: f32 = 1.0;
+
+/// One euro filter state of the joints of a hand tracker.
+#[derive(Component, Default)]
+struct HandJointFilter {
+    last_time: Option<openxr::Time>,
+    joints: [Option<JointFilter>; HAND_JOINT_COUNT],
+}
+
+impl HandJointFilter {
+    fn reset(&mut self) {
+        self.last_time = None;
+        self.joints = [None; HAND_JOINT_COUNT];
+    }
+}
+
+#[derive(Clone, Copy)]
+struct JointFilter {
+    translation: Vec3,
+    rotation: Quat,
+    linear_speed: f32,
+    angular_speed: f32,
+}
+
+impl JointFilter {
+    fn new(transform: &Transform) -> Self {
+        Self {
+            translation: transform.translation,
+            rotation: transform.rotation,
+            linear_speed: 0.0,
+            angular_speed: 0.0,
+        }
+    }
+
+    fn filter(&mut self, settings: &OxrHandSmoothing, dt: f32, transform: &mut Transform) {
+        // the same predicted display time can be located twice, e.g. when a frame is skipped
+        if dt > 0.0 {
+            self.update(settings, dt, transform);
+        }
+        transform.translation = self.translation;
+        transform.rotation = self.rotation;
+    }
+
+    fn update(&mut self, settings: &OxrHandSmoothing, dt: f32, transform: &Transform) {
+        let speed_alpha = smoothing_factor(dt, DERIVATIVE_CUTOFF);
+
+        let linear_speed = self.translation.distance(transform.translation) / dt;
+        self.linear_speed += (linear_speed - self.linear_speed) * speed_alpha;
+        let alpha = smoothing_factor(dt, settings.min_cutoff + settings.beta * self.linear_speed);
+        self.translation = self.translation.lerp(transform.translation, alpha);
+
+        let angular_speed = self.rotation.angle_between(transform.rotation) / dt;
+        self.angular_speed += (angular_speed - self.angular_speed) * speed_alpha;
+        let alpha = smoothing_factor(dt, settings.min_cutoff + settings.beta * self.angular_speed);
+        self.rotation = self.rotation.slerp(transform.rotation, alpha);
+    }
+}
+
+fn smoothing_factor(dt: f32, cutoff: f32) -> f32 {
+    let tau = 1.0 / (TAU * cutoff);
+    1.0 / (1.0 + tau / dt)
+}
+
+/// A [`Condition`](bevy::ecs::schedule::Condition) system that says if hand joints should be located.
+pub fn hand_tracking_active(active: Option<Res<XrHandTrackingActive>>) -> bool {
+    active.is_none_or(|active| active.0)
+}
+
+fn update_hand_joints(
+    default_ref_space: Res<XrPrimaryReferenceSpace>,
+    frame_state: Res<OxrFrameState>,
+    session: Res<OxrSession>,
+    mut tracker_query: Query<(
+        &OxrHandTracker,
+        Option<&XrReferenceSpace>,
+        &mut XrHandJoints,
+        &mut HandJointFilter,
+        Option<&mut XrHandDataSource>,
+    )>,
+    pipelined: Option<Res<Pipelined>>,
+    smoothing: Option<Res<OxrHandSmoothing>>,
+) {
+    let time = if pipelined.is_some() {
+        openxr::Time::from_nanos(
+            frame_state.predicted_display_time.as_nanos()
+                + frame_state.predicted_display_period.as_nanos(),
+        )
+    } else {
+        frame_state.predicted_display_time
+    };
+    let report_data_source = session
+        .instance()
+        .exts()
+        .ext_hand_tracking_data_source
+        .is_some();
+    for (tracker, ref_space, mut joints, mut filter, data_source) in &mut tracker_query {
+        let ref_space = ref_space.map(|v| &v.0).unwrap_or(&default_ref_space.0);
+        let mut data_source = data_source.filter(|_| report_data_source);
+        let located = if data_source.is_some() {
+            session.locate_hand_joints_with_data_source(tracker, ref_space, time)
+        } else {
+            session
+                .locate_hand_joints_with_velocities(tracker, ref_space, time)
+                .map(|v| v.map(|(locations, velocities)| (locations, velocities, None)))
+        };
+        let (locations, velocities, source) = match located {
+            Ok(Some(v)) => v,
+            Ok(None) => {
+                if let Some(data_source) = data_source.as_mut() {
+                    data_source.set_if_neq(XrHandDataSource::Unknown);
+                }
+                clear_joint_flags(&mut joints);
+                filter.reset();
+                continue;
+            }
+            Err(err) => {
+                warn!("Error while locating hand joints: {}", err.to_string());
+                if let Some(data_source) = data_source.as_mut() {
+                    data_source.set_if_neq(XrHandDataSource::Unknown);
+                }
+                clear_joint_flags(&mut joints);
+                filter.reset();
+                continue;
+            }
+        };
+        if let Some(data_source) = data_source.as_mut() {
+            data_source.set_if_neq(match source {
+                Some(sys::HandTrackingDataSourceEXT::UNOBSTRUCTED) => {
+                    XrHandDataSource::Unobstructed
+                }
+                Some(sys::HandTrackingDataSourceEXT::CONTROLLER) => XrHandDataSource::Controller,
+                _ => XrHandDataSource::Unknown,
+            });
+        }
+        // the filter runs at the rate of the predicted display times, not at the rate of the app
+        let dt = filter.last_time.map_or(0.0, |last| {
+            (time.as_nanos() - last.as_nanos()) as f32 / 1_000_000_000.0
+        });
+        filter.last_time = Some(time);
+        for (((joint, location), velocity), joint_filter) in joints
+            .iter_mut()
+            .zip(locations.iter())
+            .zip(velocities.iter())
+            .zip(filter.joints.iter_mut())
+        {
+            let location_flags = OxrSpaceLocationFlags(location.location_flags);
+            if location_flags.pos_valid() {
+                joint.transform.translation = location.pose.position.to_vec3();
+            }
+            if location_flags.rot_valid() {
+                joint.transform.rotation = location.pose.orientation.to_quat();
+            }
+            joint.radius = location.radius;
+            joint.location_flags = location_flags.into();
+            let valid = location_flags.pos_valid() && location_flags.rot_valid();
+            match (smoothing.as_deref(), joint_filter.as_mut()) {
+                (Some(settings), Some(state)) if valid => {
+                    state.filter(settings, dt, &mut joint.transform)
+                }
+                (Some(_), None) if valid => {
+                    *joint_filter = Some(JointFilter::new(&joint.transform))
+                }
+                _ => *joint_filter = None,
+            }
+
+            let velocity_flags = OxrSpaceVelocityFlags(velocity.velocity_flags);
+            if velocity_flags.linear_valid() {
+                joint.linear_velocity = velocity.linear_velocity.to_vec3();
+            }
+            if velocity_flags.angular_valid() {
+                joint.angular_velocity = velocity.angular_velocity.to_vec3();
+            }
+            joint.velocity_flags.linear_valid = velocity_flags.linear_valid();
+            joint.velocity_flags.angular_valid = velocity_flags.angular_valid();
+        }
+    }
+}
+
+/// Copies the pose of the followed joint from the first hand tracker of the same side,
+/// read from its [`XrHandJoints`] or else from its bone entities.
+fn update_hand_joint_followers(
+    trackers: Query<
+        (
+            &HandSide,
+            Option<&XrHandJoints>,
+            Option<&XrHandBoneEntities>,
+        ),
+        With<OxrHandTracker>,
+    >,
+    bones: Query<(&Transform, &XrSpaceLocationFlags), (With<HandBone>, Without<XrFollowHandJoint>)>,
+    mut followers: Query<
+        (
+            &XrFollowHandJoint,
+            &mut Transform,
+            &mut XrSpaceLocationFlags,
+        ),
+        Without<HandBone>,
+    >,
+) {
+    for (follow, mut transform, mut flags) in &mut followers {
+        let joint = trackers
+            .iter()
+            .find(|(side, ..)| **side == follow.hand)
+            .and_then(|(_, joints, bone_entities)| match (joints, bone_entities) {
+                (Some(joints), _) => {
+                    let joint = joints.get(follow.joint);
+                    Some((joint.transform, joint.location_flags))
+                }
+                (None, Some(bone_entities)) => bones
+                    .get(bone_entities[follow.joint as usize])
+                    .ok()
+                    .map(|(transform, flags)| (*transform, *flags)),
+                (None, None) => None,
+            });
+        // keep the last pose while the joint isn't tracked
+        let (joint_transform, joint_flags) = joint.unwrap_or_default();
+        if joint_flags.position_valid {
+            transform.translation = joint_transform.translation;
+        }
+        if joint_flags.orientation_valid {
+            transform.rotation = joint_transform.rotation;
+        }
+        flags.set_if_neq(joint_flags);
+    }
+}
+
+fn clear_joint_flags(joints: &mut XrHandJoints) {
+    for joint in joints.iter_mut() {
+        joint.location_flags = XrSpaceLocationFlags::default();
+        joint.velocity_flags = XrSpaceVelocityFlags::default();
+    }
+}
+
+fn clear_hand_flags(
+    mut bone_query: Query<
+        (
+            &mut OxrSpaceLocationFlags,
+            &mut XrSpaceLocationFlags,
+            Option<&mut OxrSpaceVelocityFlags>,
+            Option<&mut XrSpaceVelocityFlags>,
+        ),
+        With<HandBone>,
+    >,
+    mut joints_query: Query<&mut XrHandJoints>,
+) {
+    for mut joints in &mut joints_query {
+        clear_joint_flags(&mut joints);
+    }
+    for (mut flags, mut xr_flags, vel_flags, xr_vel_flags) in &mut bone_query {
+        flags.0 = SpaceLocationFlags::EMPTY;
+        *xr_flags = XrSpaceLocationFlags::default();
+        if let Some(mut flags) = vel_flags {
+            flags.0 = SpaceVelocityFlags::EMPTY;
+        }
+        if let Some(mut flags) = xr_vel_flags {
+            flags.linear_valid = false;
+            flags.angular_valid = false;
+        }
+    }
+}
+
 fn locate_hands(
     default_ref_space: Res<XrPrimaryReferenceSpace>,
     frame_state: Res<OxrFrameState>,
@@ -164,8 +614,7 @@ fn locate_hands(
                 if let Some(mut flags) = vel_flags {
                     flags.0 = SpaceVelocityFlags::EMPTY;
                 }
-                xr_flags.position_tracked = false;
-                xr_flags.rotation_tracked = false;
+                *xr_flags = XrSpaceLocationFlags::default();
                 if let Some(mut flags) = xr_vel_flags {
                     flags.linear_valid = false;
                     flags.angular_valid = false;
@@ -271,9 +720,80 @@ fn locate_hands(
                 transform.rotation.z = joint.pose.orientation.z;
                 transform.rotation.w = joint.pose.orientation.w;
             }
-            xr_location_flags.position_tracked = flags.pos_valid() && flags.pos_tracked();
-            xr_location_flags.rotation_tracked = flags.rot_valid() && flags.rot_tracked();
+            *xr_location_flags = flags.into();
             *location_flags = flags;
         }
     }
 }
+
+fn cvt(x: sys::Result) -> openxr::Result<sys::Result> {
+    if x.into_raw() >= 0 {
+        Ok(x)
+    } else {
+        Err(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1.0 / 90.0;
+
+    #[test]
+    fn smoothing_factor_grows_with_the_cutoff() {
+        let low = smoothing_factor(DT, 1.0);
+        let high = smoothing_factor(DT, 100.0);
+        assert!(0.0 < low && low < high && high < 1.0);
+    }
+
+    #[test]
+    fn still_joints_are_not_changed() {
+        let settings = OxrHandSmoothing::default();
+        let start = Transform::from_xyz(0.1, 1.2, -0.3).with_rotation(Quat::from_rotation_y(0.5));
+        let mut filter = JointFilter::new(&start);
+        for _ in 0..10 {
+            let mut transform = start;
+            filter.filter(&settings, DT, &mut transform);
+            assert!(transform.translation.abs_diff_eq(start.translation, 1e-6));
+            assert!(transform.rotation.abs_diff_eq(start.rotation, 1e-6));
+        }
+    }
+
+    #[test]
+    fn jumps_are_smoothed() {
+        let settings = OxrHandSmoothing::default();
+        let mut filter = JointFilter::new(&Transform::IDENTITY);
+        let mut transform = Transform::from_xyz(0.0, 0.0, -0.1);
+        filter.filter(&settings, DT, &mut transform);
+        assert!(transform.translation.z < 0.0 && transform.translation.z > -0.1);
+    }
+
+    #[test]
+    fn same_time_keeps_the_filtered_pose() {
+        let settings = OxrHandSmoothing::default();
+        let mut filter = JointFilter::new(&Transform::IDENTITY);
+        let mut transform = Transform::from_xyz(0.0, 0.0, -0.1);
+        filter.filter(&settings, 0.0, &mut transform);
+        assert_eq!(transform.translation, Vec3::ZERO);
+    }
+
+    #[test]
+    fn higher_beta_follows_fast_movement_closer() {
+        let follow = |beta: f32| {
+            let settings = OxrHandSmoothing {
+                min_cutoff: 1.0,
+                beta,
+            };
+            let mut filter = JointFilter::new(&Transform::IDENTITY);
+            let mut filtered = 0.0;
+            for frame in 1..=10 {
+                let mut transform = Transform::from_xyz(0.0, 0.0, -0.02 * frame as f32);
+                filter.filter(&settings, DT, &mut transform);
+                filtered = transform.translation.z;
+            }
+            filtered
+        };
+        assert!(follow(10.0) < follow(0.0));
+    }
+}
@@ -1,4 +1,15 @@
+pub mod body_tracking;
+pub mod controller_model;
+pub mod display_refresh_rate;
+pub mod face_tracking;
+pub mod foveation;
 pub mod handtracking;
 #[cfg(feature = "passthrough")]
 pub mod passthrough;
 pub mod overlay;
+pub mod quad_layer;
+pub mod secondary_view;
+pub mod space_warp;
+pub mod spatial_anchors;
+pub mod spectator;
+pub mod visibility_mask;
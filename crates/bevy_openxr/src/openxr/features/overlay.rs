@@ -11,11 +11,25 @@ use crate::{
     session::{OxrSessionCreateNextChain, OxrSessionCreateNextProvider},
 };
 
+/// Creates the session as an overlay session when
+/// [`XR_EXTX_overlay`](https://registry.khronos.org/OpenXR/specs/1.0/html/xrspec.html#XR_EXTX_overlay) is enabled,
+/// e.g. through [`OxrInitPlugin::overlay`](crate::init::OxrInitPlugin::overlay).
+///
+/// The composition layers of an overlay session are composited on top of the layers of the main session
+/// of another app. Whether that main session is visible is tracked in [`OxrMainSessionVisibility`].
 pub struct OxrOverlayPlugin;
 
 impl Plugin for OxrOverlayPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.add_event::<OxrOverlaySessionEvent>();
+        app.init_resource::<OxrMainSessionVisibility>();
+        if !app
+            .world()
+            .get_resource::<OxrEnabledExtensions>()
+            .is_some_and(|exts| exts.extx_overlay)
+        {
+            return;
+        }
         app.init_resource::<OxrOverlaySettings>();
         app.add_systems(
             First,
@@ -25,9 +39,17 @@ impl Plugin for OxrOverlayPlugin {
     }
 }
 
-fn handle_overlay_event(event: In<OxrEvent>, mut writer: EventWriter<OxrOverlaySessionEvent>) {
+fn handle_overlay_event(
+    event: In<OxrEvent>,
+    mut writer: EventWriter<OxrOverlaySessionEvent>,
+    mut visibility: ResMut<OxrMainSessionVisibility>,
+) {
     // this unwrap will never panic since we are in a valid scope
     if let Event::MainSessionVisibilityChangedEXTX(event) = unsafe { event.get() }.unwrap() {
+        *visibility = OxrMainSessionVisibility {
+            visible: event.visible(),
+            flags: event.flags(),
+        };
         writer.send(OxrOverlaySessionEvent::MainSessionVisibilityChanged {
             visible: event.visible(),
             flags: event.flags(),
@@ -35,8 +57,30 @@ fn handle_overlay_event(event: In<OxrEvent>, mut writer: EventWriter<OxrOverlayS
     }
 }
 
-#[derive(Resource)]
+/// Whether the main session the overlay session is composited over is currently visible,
+/// updated from `XrEventDataMainSessionVisibilityChangedEXTX`.
+///
+/// `visible` stays `false` for sessions that aren't overlay sessions.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct OxrMainSessionVisibility {
+    pub visible: bool,
+    /// Whether the main session is submitting depth, so overlay layers can be depth tested against it.
+    pub flags: openxr::OverlayMainSessionFlagsEXTX,
+}
+
+impl Default for OxrMainSessionVisibility {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            flags: openxr::OverlayMainSessionFlagsEXTX::EMPTY,
+        }
+    }
+}
+
+/// Settings the overlay session is created with, changes after the first session was created are ignored.
+#[derive(Resource, Clone, Copy, Debug)]
 pub struct OxrOverlaySettings {
+    /// Placement of this overlay relative to other overlay sessions, higher values are composited on top.
     pub session_layer_placement: u32,
     pub flags: openxr::OverlaySessionCreateFlagsEXTX,
 }
@@ -52,15 +96,21 @@ impl Default for OxrOverlaySettings {
 
 fn add_overlay_info_to_chain(
     mut chain: NonSendMut<OxrSessionCreateNextChain>,
-    exts: Res<OxrEnabledExtensions>,
     settings: Res<OxrOverlaySettings>,
+    mut pushed: Local<bool>,
 ) {
-    if exts.extx_overlay {
-        chain.push(OxrSessionCreateInfoOverlay::new(
-            settings.flags,
-            settings.session_layer_placement,
-        ));
+    // the chain is kept across sessions, so the info only has to be added once
+    if *pushed {
+        if settings.is_changed() {
+            warn!("OxrOverlaySettings changed after the overlay info was added, the change is ignored");
+        }
+        return;
     }
+    chain.push(OxrSessionCreateInfoOverlay::new(
+        settings.flags,
+        settings.session_layer_placement,
+    ));
+    *pushed = true;
 }
 
 #[derive(Event, Clone, Copy, Debug)]
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use bevy::render::extract_resource::ExtractResourcePlugin;
 use bevy::render::Render;
 use bevy::render::RenderApp;
 use bevy::render::RenderSet;
@@ -25,11 +26,29 @@ impl Plugin for OxrPassthroughPlugin {
         if resources.is_some_and(|(instance, system)| {
             supports_passthrough(instance, *system).is_ok_and(|s| s)
         }) {
+            app.init_resource::<OxrPassthroughEnabled>()
+                .init_resource::<OxrPassthroughStyle>()
+                .add_plugins((
+                    ExtractResourcePlugin::<OxrPassthroughEnabled>::default(),
+                    ExtractResourcePlugin::<OxrPassthroughStyle>::default(),
+                ));
             app.sub_app_mut(RenderApp).add_systems(
                 Render,
-                insert_passthrough
-                    .in_set(RenderSet::PrepareAssets)
-                    .run_if(resource_added::<OxrSession>),
+                (
+                    insert_passthrough.run_if(resource_added::<OxrSession>),
+                    toggle_passthrough
+                        .run_if(resource_exists::<OxrPassthrough>)
+                        .run_if(resource_changed::<OxrPassthroughEnabled>),
+                    apply_passthrough_style
+                        .run_if(resource_exists::<OxrPassthroughLayer>)
+                        .run_if(resource_exists::<OxrPassthroughStyle>)
+                        .run_if(
+                            resource_changed::<OxrPassthroughStyle>
+                                .or(resource_added::<OxrPassthroughLayer>),
+                        ),
+                )
+                    .chain()
+                    .in_set(RenderSet::PrepareAssets),
             );
         } else {
             error!("Passthrough is not supported with this runtime")
@@ -39,10 +58,18 @@ impl Plugin for OxrPassthroughPlugin {
 
 pub fn insert_passthrough(world: &mut World) {
     let session = world.resource::<OxrSession>();
+    let flags = if world
+        .get_resource::<OxrPassthroughEnabled>()
+        .is_none_or(|enabled| enabled.0)
+    {
+        openxr::PassthroughFlagsFB::IS_RUNNING_AT_CREATION
+    } else {
+        openxr::PassthroughFlagsFB::EMPTY
+    };
 
     if let Ok((passthrough, passthrough_layer)) = create_passthrough(
         session,
-        openxr::PassthroughFlagsFB::IS_RUNNING_AT_CREATION,
+        flags,
         openxr::PassthroughLayerPurposeFB::RECONSTRUCTION,
     ) {
         world
@@ -53,6 +80,33 @@ pub fn insert_passthrough(world: &mut World) {
     }
 }
 
+/// Starts or pauses passthrough when [`OxrPassthroughEnabled`] changes.
+pub fn toggle_passthrough(
+    enabled: Res<OxrPassthroughEnabled>,
+    passthrough: Res<OxrPassthrough>,
+    passthrough_layer: Res<OxrPassthroughLayer>,
+) {
+    let result = if enabled.0 {
+        passthrough.start().and_then(|_| passthrough_layer.resume())
+    } else {
+        passthrough_layer.pause().and_then(|_| passthrough.pause())
+    };
+    if let Err(e) = result {
+        error!("Failed to toggle passthrough: {e}");
+    }
+}
+
+/// Applies [`OxrPassthroughStyle`] to the passthrough layer when either changes.
+pub fn apply_passthrough_style(
+    session: Res<OxrSession>,
+    style: Res<OxrPassthroughStyle>,
+    passthrough_layer: Res<OxrPassthroughLayer>,
+) {
+    if let Err(e) = session.set_passthrough_style(&passthrough_layer, &style) {
+        error!("Failed to set passthrough style: {e}");
+    }
+}
+
 pub fn resume_passthrough(
     passthrough: Res<OxrPassthrough>,
     passthrough_layer: Res<OxrPassthroughLayer>,
@@ -0,0 +1,548 @@
+//! Quad and cylinder composition layers, which show an image on a rectangle or a curved panel in the world.
+//!
+//! The runtime samples the image directly instead of it going through the stereo projection,
+//! which keeps text and other UI much sharper.
+//! [`OxrUiLayer`] shows what a camera renders to an image, e.g. bevy UI, as a quad layer.
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_asset::RenderAssets,
+        renderer::{RenderDevice, RenderQueue},
+        texture::GpuImage,
+        Render, RenderApp,
+    },
+    transform::TransformSystem,
+    utils::HashMap,
+};
+use bevy_mod_xr::{
+    session::{XrPreDestroySession, XrRenderSet, XrTrackingRoot},
+    spaces::{XrPrimaryReferenceSpace, XrSpace},
+};
+use openxr::{CompositionLayerFlags, EyeVisibility};
+
+use crate::{
+    helper_traits::ToPosef,
+    init::should_run_frame_loop,
+    layer_builder::{
+        CompositionLayer, CompositionLayerCylinder, CompositionLayerQuad, LayerProvider,
+        OxrLayerType, SwapchainSubImage,
+    },
+    render::OxrRenderStep,
+    resources::{OxrLayerSupport, OxrRenderLayers, OxrSwapchain, OxrSwapchainImages},
+    session::OxrSession,
+};
+
+/// Submits a composition layer for every entity with an [`OxrQuadLayer`] or an [`OxrCylinderLayer`].
+pub struct OxrQuadLayerPlugin;
+
+impl Plugin for OxrQuadLayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ExtractComponentPlugin::<OxrQuadLayer>::default(),
+            ExtractComponentPlugin::<OxrCylinderLayer>::default(),
+        ))
+        .add_systems(
+            PostUpdate,
+            update_ui_layers.after(TransformSystem::TransformPropagate),
+        );
+
+        app.sub_app_mut(RenderApp)
+            .init_resource::<OxrQuadLayerSwapchains>()
+            .add_systems(XrPreDestroySession, clean_up_swapchains)
+            .add_systems(
+                Render,
+                update_quad_layers
+                    .in_set(XrRenderSet::PostRender)
+                    .before(OxrRenderStep::EndFrame)
+                    .run_if(should_run_frame_loop),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        // the render layers are added by the render plugin, which might be added after this plugin
+        if let Some(mut layers) = app
+            .sub_app_mut(RenderApp)
+            .world_mut()
+            .get_resource_mut::<OxrRenderLayers>()
+        {
+            layers.push(Box::new(QuadLayers));
+            layers.push(Box::new(CylinderLayers));
+        }
+    }
+}
+
+/// Shows `image` on a rectangle of `size` meters.
+///
+/// The image is copied into the layer's swapchain every frame, so it can be a render target, e.g. of a UI camera.
+/// It needs [`TextureUsages::COPY_SRC`](wgpu::TextureUsages::COPY_SRC), and a format the runtime supports for swapchains,
+/// or whose sRGB or linear counterpart it supports. Layers with other formats are skipped.
+#[derive(Component, Clone, Debug, ExtractComponent)]
+pub struct OxrQuadLayer {
+    pub image: Handle<Image>,
+    /// Size of the quad in meters.
+    pub size: Vec2,
+    /// Which eyes the quad is shown to.
+    pub eye_visibility: EyeVisibility,
+    /// The space `pose` is relative to. Uses the [`XrPrimaryReferenceSpace`] if [`None`].
+    pub space: Option<XrSpace>,
+    /// Pose of the center of the quad. The quad faces towards +Z.
+    pub pose: Isometry3d,
+}
+
+impl OxrQuadLayer {
+    pub fn new(image: Handle<Image>, size: Vec2) -> Self {
+        Self {
+            image,
+            size,
+            eye_visibility: EyeVisibility::BOTH,
+            space: None,
+            pose: Isometry3d::IDENTITY,
+        }
+    }
+
+    pub fn with_eye_visibility(mut self, eye_visibility: EyeVisibility) -> Self {
+        self.eye_visibility = eye_visibility;
+        self
+    }
+
+    pub fn with_space(mut self, space: XrSpace) -> Self {
+        self.space = Some(space);
+        self
+    }
+
+    pub fn with_pose(mut self, pose: impl Into<Isometry3d>) -> Self {
+        self.pose = pose.into();
+        self
+    }
+}
+
+/// Shows `image` on the inside of a section of a cylinder, e.g. for a curved menu around the user.
+///
+/// Unlike a large quad, the image keeps the same distance to a viewer at the center of the cylinder.
+/// Like [`OxrQuadLayer`], the image is copied every frame and needs [`TextureUsages::COPY_SRC`](wgpu::TextureUsages::COPY_SRC).
+///
+/// Requires [`enable_composition_layer_cylinder`](crate::exts::OxrExtensions::enable_composition_layer_cylinder),
+/// the layer isn't shown otherwise.
+#[derive(Component, Clone, Debug, ExtractComponent)]
+pub struct OxrCylinderLayer {
+    pub image: Handle<Image>,
+    /// Radius of the cylinder in meters.
+    pub radius: f32,
+    /// The angle of the section of the cylinder the image covers in radians.
+    pub central_angle: f32,
+    /// Width of the image divided by its height. Uses the aspect ratio of `image` if [`None`].
+    pub aspect_ratio: Option<f32>,
+    /// Which eyes the cylinder is shown to.
+    pub eye_visibility: EyeVisibility,
+    /// The space `pose` is relative to. Uses the [`XrPrimaryReferenceSpace`] if [`None`].
+    pub space: Option<XrSpace>,
+    /// Pose of the center of the cylinder. The image is centered on -Z, facing the center.
+    pub pose: Isometry3d,
+}
+
+impl OxrCylinderLayer {
+    pub fn new(image: Handle<Image>, radius: f32, central_angle: f32) -> Self {
+        Self {
+            image,
+            radius,
+            central_angle,
+            aspect_ratio: None,
+            eye_visibility: EyeVisibility::BOTH,
+            space: None,
+            pose: Isometry3d::IDENTITY,
+        }
+    }
+
+    pub fn with_aspect_ratio(mut self, aspect_ratio: f32) -> Self {
+        self.aspect_ratio = Some(aspect_ratio);
+        self
+    }
+
+    pub fn with_eye_visibility(mut self, eye_visibility: EyeVisibility) -> Self {
+        self.eye_visibility = eye_visibility;
+        self
+    }
+
+    pub fn with_space(mut self, space: XrSpace) -> Self {
+        self.space = Some(space);
+        self
+    }
+
+    pub fn with_pose(mut self, pose: impl Into<Isometry3d>) -> Self {
+        self.pose = pose.into();
+        self
+    }
+}
+
+/// Shows the image a camera renders to as a quad at the camera's transform, e.g. for a camera rendering bevy UI.
+///
+/// The camera needs to target a [`RenderTarget::Image`] with [`TextureUsages::COPY_SRC`](wgpu::TextureUsages::COPY_SRC).
+/// An [`OxrQuadLayer`] is inserted on the camera and updated every frame, the camera's transform is
+/// relative to the [`XrTrackingRoot`] like any other tracked entity.
+#[derive(Component, Clone, Copy, Debug)]
+#[require(Transform)]
+pub struct OxrUiLayer {
+    /// Size of the quad in meters.
+    pub size: Vec2,
+    /// Which eyes the quad is shown to.
+    pub eye_visibility: EyeVisibility,
+}
+
+impl OxrUiLayer {
+    pub fn new(size: Vec2) -> Self {
+        Self {
+            size,
+            eye_visibility: EyeVisibility::BOTH,
+        }
+    }
+
+    pub fn with_eye_visibility(mut self, eye_visibility: EyeVisibility) -> Self {
+        self.eye_visibility = eye_visibility;
+        self
+    }
+}
+
+fn update_ui_layers(
+    root_query: Query<&GlobalTransform, With<XrTrackingRoot>>,
+    mut query: Query<(
+        Entity,
+        &OxrUiLayer,
+        &Camera,
+        &GlobalTransform,
+        Option<&mut OxrQuadLayer>,
+    )>,
+    mut commands: Commands,
+) {
+    let root = root_query.get_single().copied().unwrap_or_default();
+    let root_inverse = root.affine().inverse();
+    for (entity, ui_layer, camera, transform, quad) in &mut query {
+        let RenderTarget::Image(image) = &camera.target else {
+            warn_once!("OxrUiLayer needs a camera rendering to an image");
+            continue;
+        };
+        // the quad is submitted relative to the reference space, which the tracking root represents
+        let (_, rotation, translation) =
+            (root_inverse * transform.affine()).to_scale_rotation_translation();
+        let pose = Isometry3d::new(translation, rotation);
+        match quad {
+            Some(mut quad) => {
+                quad.image = image.clone();
+                quad.size = ui_layer.size;
+                quad.eye_visibility = ui_layer.eye_visibility;
+                quad.pose = pose;
+            }
+            None => {
+                commands.entity(entity).insert(
+                    OxrQuadLayer::new(image.clone(), ui_layer.size)
+                        .with_eye_visibility(ui_layer.eye_visibility)
+                        .with_pose(pose),
+                );
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+enum LayerShape {
+    Quad(OxrQuadLayer),
+    Cylinder(OxrCylinderLayer),
+}
+
+impl LayerShape {
+    fn image(&self) -> &Handle<Image> {
+        match self {
+            LayerShape::Quad(quad) => &quad.image,
+            LayerShape::Cylinder(cylinder) => &cylinder.image,
+        }
+    }
+}
+
+struct QuadSwapchain {
+    swapchain: OxrSwapchain,
+    images: OxrSwapchainImages,
+    format: wgpu::TextureFormat,
+    size: UVec2,
+    layer: LayerShape,
+    /// Whether an image was released into the swapchain, layers without one can't be submitted.
+    has_image: bool,
+}
+
+impl QuadSwapchain {
+    fn sub_image(&self) -> SwapchainSubImage<'_> {
+        SwapchainSubImage::new()
+            .swapchain(&self.swapchain)
+            .image_array_index(0)
+            .image_rect(openxr::Rect2Di {
+                offset: openxr::Offset2Di { x: 0, y: 0 },
+                extent: openxr::Extent2Di {
+                    width: self.size.x as _,
+                    height: self.size.y as _,
+                },
+            })
+    }
+}
+
+/// The swapchains of all quad and cylinder layers in the render world.
+#[derive(Resource, Default)]
+struct OxrQuadLayerSwapchains {
+    swapchains: HashMap<Entity, QuadSwapchain>,
+    /// The swapchain formats supported by the runtime, enumerated once per session.
+    formats: Option<Vec<wgpu::TextureFormat>>,
+}
+
+impl OxrQuadLayerSwapchains {
+    /// Returns the format of the swapchain an image with `format` can be copied into,
+    /// which is either the same format or its sRGB or linear counterpart.
+    fn swapchain_format(&self, format: wgpu::TextureFormat) -> Option<wgpu::TextureFormat> {
+        let formats = self.formats.as_ref()?;
+        [
+            format,
+            format.add_srgb_suffix(),
+            format.remove_srgb_suffix(),
+        ]
+        .into_iter()
+        .find(|format| formats.contains(format))
+    }
+}
+
+fn clean_up_swapchains(mut swapchains: ResMut<OxrQuadLayerSwapchains>) {
+    swapchains.swapchains.clear();
+    swapchains.formats = None;
+}
+
+fn update_quad_layers(
+    mut swapchains: ResMut<OxrQuadLayerSwapchains>,
+    quads: Query<(Entity, &OxrQuadLayer)>,
+    cylinders: Query<(Entity, &OxrCylinderLayer)>,
+    layer_support: Res<OxrLayerSupport>,
+    session: Res<OxrSession>,
+    gpu_images: Res<RenderAssets<GpuImage>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    swapchains
+        .swapchains
+        .retain(|entity, _| quads.contains(*entity) || cylinders.contains(*entity));
+    if swapchains.formats.is_none() {
+        match session.enumerate_swapchain_formats() {
+            Ok(formats) => swapchains.formats = Some(formats),
+            Err(e) => {
+                error!("Failed to enumerate quad layer swapchain formats: {e}");
+                return;
+            }
+        }
+    }
+    let cylinders_supported = layer_support.is_supported(OxrLayerType::Cylinder);
+    if !cylinders_supported && !cylinders.is_empty() {
+        warn_once!("Cylinder layers aren't supported, enable XR_KHR_composition_layer_cylinder to show them");
+    }
+    let layers = quads
+        .iter()
+        .map(|(entity, quad)| (entity, LayerShape::Quad(quad.clone())))
+        .chain(
+            cylinders
+                .iter()
+                .filter(|_| cylinders_supported)
+                .map(|(entity, cylinder)| (entity, LayerShape::Cylinder(cylinder.clone()))),
+        );
+
+    let mut encoder = render_device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("xr_quad_layer_encoder"),
+    });
+    let mut acquired = Vec::new();
+    for (entity, layer) in layers {
+        let Some(image) = gpu_images.get(layer.image()) else {
+            continue;
+        };
+        let Some(format) = swapchains.swapchain_format(image.texture_format) else {
+            warn_once!(
+                "The runtime doesn't support quad layer swapchains with format {:?}, skipping the layer",
+                image.texture_format
+            );
+            continue;
+        };
+        let recreate = swapchains
+            .swapchains
+            .get(&entity)
+            .is_none_or(|s| s.size != image.size || s.format != format);
+        if recreate {
+            match create_quad_swapchain(&session, &render_device, image, format, layer.clone()) {
+                Ok(swapchain) => {
+                    swapchains.swapchains.insert(entity, swapchain);
+                }
+                Err(e) => {
+                    warn_once!("Failed to create quad layer swapchain: {e}");
+                    continue;
+                }
+            }
+        }
+        let swapchain = swapchains.swapchains.get_mut(&entity).unwrap();
+        swapchain.layer = layer;
+
+        if let Err(e) = swapchain.swapchain.release_pending_image() {
+            error!("Failed to release pending quad layer image: {e}");
+            continue;
+        }
+        let index = match swapchain.swapchain.acquire_image() {
+            Ok(index) => index,
+            Err(e) => {
+                error!("Failed to acquire quad layer image: {e}");
+                continue;
+            }
+        };
+        if let Err(e) = swapchain.swapchain.wait_image(openxr::Duration::INFINITE) {
+            // the image can't be written to without waiting on it, give it back untouched
+            error!("Failed to wait quad layer image: {e}");
+            if let Err(e) = swapchain.swapchain.release_pending_image() {
+                error!("Failed to release quad layer image: {e}");
+            }
+            continue;
+        }
+        encoder.copy_texture_to_texture(
+            image.texture.as_image_copy(),
+            swapchain.images[index as usize].as_image_copy(),
+            wgpu::Extent3d {
+                width: image.size.x,
+                height: image.size.y,
+                depth_or_array_layers: 1,
+            },
+        );
+        acquired.push(entity);
+    }
+    render_queue.submit([encoder.finish()]);
+
+    for entity in acquired {
+        let swapchain = swapchains.swapchains.get_mut(&entity).unwrap();
+        match swapchain.swapchain.release_image() {
+            Ok(()) => swapchain.has_image = true,
+            Err(e) => error!("Failed to release quad layer image: {e}"),
+        }
+    }
+}
+
+/// The image is copied into the swapchain, which is also sampled by the runtime.
+const QUAD_SWAPCHAIN_USAGE: wgpu::TextureUsages = wgpu::TextureUsages::RENDER_ATTACHMENT
+    .union(wgpu::TextureUsages::TEXTURE_BINDING)
+    .union(wgpu::TextureUsages::COPY_SRC)
+    .union(wgpu::TextureUsages::COPY_DST);
+
+fn create_quad_swapchain(
+    session: &OxrSession,
+    render_device: &RenderDevice,
+    image: &GpuImage,
+    format: wgpu::TextureFormat,
+    layer: LayerShape,
+) -> crate::types::Result<QuadSwapchain> {
+    let swapchain = session.create_simple_swapchain(image.size, format, QUAD_SWAPCHAIN_USAGE)?;
+    let images = swapchain.enumerate_images(
+        render_device.wgpu_device(),
+        format,
+        image.size,
+        1,
+        QUAD_SWAPCHAIN_USAGE,
+    )?;
+    Ok(QuadSwapchain {
+        swapchain,
+        images,
+        format,
+        size: image.size,
+        layer,
+        has_image: false,
+    })
+}
+
+/// Provides the layers of all [`OxrQuadLayer`]s. Added to [`OxrRenderLayers`] by [`OxrQuadLayerPlugin`].
+pub struct QuadLayers;
+
+impl LayerProvider for QuadLayers {
+    fn layer_type(&self) -> Option<OxrLayerType> {
+        Some(OxrLayerType::Quad)
+    }
+
+    fn get<'a>(&'a self, _world: &'a World) -> Option<Box<dyn CompositionLayer + '_>> {
+        None
+    }
+
+    fn layers<'a>(&'a self, world: &'a World) -> Vec<Box<dyn CompositionLayer<'a> + 'a>> {
+        let (Some(swapchains), Some(ref_space)) = (
+            world.get_resource::<OxrQuadLayerSwapchains>(),
+            world.get_resource::<XrPrimaryReferenceSpace>(),
+        ) else {
+            return Vec::new();
+        };
+        let ref_space: &XrSpace = ref_space;
+        swapchains
+            .swapchains
+            .values()
+            .filter(|swapchain| swapchain.has_image)
+            .filter_map(|swapchain| {
+                let LayerShape::Quad(quad) = &swapchain.layer else {
+                    return None;
+                };
+                let layer: Box<dyn CompositionLayer<'a> + 'a> = Box::new(
+                    CompositionLayerQuad::new()
+                        .layer_flags(CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA)
+                        .space(quad.space.as_ref().unwrap_or(ref_space))
+                        .eye_visibility(quad.eye_visibility)
+                        .pose(quad.pose.to_posef())
+                        .size(openxr::Extent2Df {
+                            width: quad.size.x,
+                            height: quad.size.y,
+                        })
+                        .sub_image(swapchain.sub_image()),
+                );
+                Some(layer)
+            })
+            .collect()
+    }
+}
+
+/// Provides the layers of all [`OxrCylinderLayer`]s. Added to [`OxrRenderLayers`] by [`OxrQuadLayerPlugin`].
+pub struct CylinderLayers;
+
+impl LayerProvider for CylinderLayers {
+    fn layer_type(&self) -> Option<OxrLayerType> {
+        Some(OxrLayerType::Cylinder)
+    }
+
+    fn get<'a>(&'a self, _world: &'a World) -> Option<Box<dyn CompositionLayer + '_>> {
+        None
+    }
+
+    fn layers<'a>(&'a self, world: &'a World) -> Vec<Box<dyn CompositionLayer<'a> + 'a>> {
+        let (Some(swapchains), Some(ref_space)) = (
+            world.get_resource::<OxrQuadLayerSwapchains>(),
+            world.get_resource::<XrPrimaryReferenceSpace>(),
+        ) else {
+            return Vec::new();
+        };
+        let ref_space: &XrSpace = ref_space;
+        swapchains
+            .swapchains
+            .values()
+            .filter(|swapchain| swapchain.has_image)
+            .filter_map(|swapchain| {
+                let LayerShape::Cylinder(cylinder) = &swapchain.layer else {
+                    return None;
+                };
+                let aspect_ratio = cylinder
+                    .aspect_ratio
+                    .unwrap_or(swapchain.size.x as f32 / swapchain.size.y as f32);
+                let layer: Box<dyn CompositionLayer<'a> + 'a> = Box::new(
+                    CompositionLayerCylinder::new()
+                        .layer_flags(CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA)
+                        .space(cylinder.space.as_ref().unwrap_or(ref_space))
+                        .eye_visibility(cylinder.eye_visibility)
+                        .pose(cylinder.pose.to_posef())
+                        .radius(cylinder.radius)
+                        .central_angle(cylinder.central_angle)
+                        .aspect_ratio(aspect_ratio)
+                        .sub_image(swapchain.sub_image()),
+                );
+                Some(layer)
+            })
+            .collect()
+    }
+}
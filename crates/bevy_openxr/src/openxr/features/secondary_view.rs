@@ -0,0 +1,543 @@
+//! Secondary view configurations through `XR_MSFT_secondary_view_configuration`, e.g. the first person observer
+//! used for mixed reality capture.
+//!
+//! The runtime decides every frame whether the secondary view is active. While it is, an extra camera renders
+//! the scene from the secondary view's pose into its own swapchain, which is submitted next to the primary views.
+use std::ptr;
+
+use bevy::prelude::*;
+use bevy::render::camera::{
+    ManualTextureView, ManualTextureViewHandle, ManualTextureViews, RenderTarget,
+};
+use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
+use bevy::render::renderer::RenderDevice;
+use bevy::render::{ExtractSchedule, MainWorld, Render, RenderApp};
+use bevy::transform::TransformSystem;
+use bevy_mod_xr::camera::XrProjection;
+use bevy_mod_xr::session::{XrPreDestroySession, XrSessionCreated, XrTracker};
+use bevy_mod_xr::spaces::XrPrimaryReferenceSpace;
+use openxr::{sys, CompositionLayerFlags, SwapchainCreateFlags};
+
+use crate::error::OxrError;
+use crate::exts::OxrEnabledExtensions;
+use crate::helper_traits::{ToQuat, ToVec3};
+use crate::init::should_run_frame_loop;
+use crate::layer_builder::{
+    CompositionLayer, CompositionLayerProjection, CompositionLayerProjectionView, SwapchainSubImage,
+};
+use crate::render::{calculate_projection, OxrRenderStep, XR_TEXTURE_INDEX};
+use crate::resources::{
+    OxrFrameState, OxrFrameStream, OxrFrameWaiter, OxrGraphicsBackend, OxrGraphicsInfo,
+    OxrInstance, OxrSwapchain, OxrSwapchainImageState, OxrSwapchainImages, OxrSystemId, Pipelined,
+};
+use crate::session::OxrSession;
+use crate::types::{swapchain_usage_flags, Result, SwapchainCreateInfo};
+
+/// The texture view the secondary view camera renders to, right below the ones of the primary views.
+const SECONDARY_VIEW_HANDLE: ManualTextureViewHandle =
+    ManualTextureViewHandle(XR_TEXTURE_INDEX - 1);
+
+/// Renders a secondary view configuration whenever the runtime activates it.
+///
+/// [`OxrSecondaryView`] is inserted when the session is created if the runtime supports `view_configuration_type`.
+/// Only secondary view configurations with a single view are supported, which includes the first person observer.
+///
+/// Requires [`enable_msft_first_person_observer`](crate::exts::OxrExtensions::enable_msft_first_person_observer).
+pub struct OxrSecondaryViewPlugin {
+    /// The secondary view configuration that is enabled when the session begins.
+    pub view_configuration_type: openxr::ViewConfigurationType,
+}
+
+impl Default for OxrSecondaryViewPlugin {
+    fn default() -> Self {
+        Self {
+            view_configuration_type:
+                openxr::ViewConfigurationType::SECONDARY_MONO_FIRST_PERSON_OBSERVER_MSFT,
+        }
+    }
+}
+
+impl Plugin for OxrSecondaryViewPlugin {
+    fn build(&self, app: &mut App) {
+        let enabled = app
+            .world()
+            .get_resource::<OxrEnabledExtensions>()
+            .is_some_and(|exts| exts.msft_secondary_view_configuration);
+        if !enabled {
+            return;
+        }
+        app.insert_resource(SecondaryViewConfigurationType(self.view_configuration_type))
+            .add_plugins(ExtractResourcePlugin::<OxrSecondaryView>::default())
+            .add_systems(XrSessionCreated, init_secondary_view)
+            .add_systems(XrPreDestroySession, clean_up_secondary_view)
+            .add_systems(
+                PostUpdate,
+                (locate_secondary_view, update_secondary_view_camera)
+                    .chain()
+                    .before(TransformSystem::TransformPropagate)
+                    .run_if(should_run_frame_loop)
+                    .run_if(resource_exists::<OxrFrameState>)
+                    .run_if(resource_exists::<OxrSecondaryView>),
+            );
+        app.sub_app_mut(RenderApp)
+            .add_systems(ExtractSchedule, transfer_secondary_view_swapchain)
+            .add_systems(XrPreDestroySession, clean_up_render_secondary_view)
+            .add_systems(
+                Render,
+                (
+                    acquire_secondary_view_image.in_set(OxrRenderStep::AcquireImage),
+                    wait_secondary_view_image.in_set(OxrRenderStep::WaitImage),
+                    release_secondary_view_image.in_set(OxrRenderStep::ReleaseImage),
+                )
+                    .run_if(should_run_frame_loop)
+                    .run_if(secondary_view_active),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct SecondaryViewConfigurationType(openxr::ViewConfigurationType);
+
+/// The enabled secondary view configuration of the session.
+#[derive(Clone, Debug, Resource, ExtractResource)]
+pub struct OxrSecondaryView {
+    pub view_configuration_type: openxr::ViewConfigurationType,
+    /// Whether the runtime currently wants the secondary view to be rendered, updated by `xrWaitFrame`.
+    pub active: bool,
+    /// The blend mode the secondary view is submitted with, the primary one if the runtime supports it.
+    pub environment_blend_mode: openxr::EnvironmentBlendMode,
+    pub resolution: UVec2,
+    pub format: wgpu::TextureFormat,
+    /// The views located for the current frame, empty while the secondary view isn't active.
+    pub views: Vec<openxr::View>,
+}
+
+/// The camera rendering the [`OxrSecondaryView`], spawned by [`OxrSecondaryViewPlugin`].
+///
+/// It is only active while the secondary view is.
+#[derive(Clone, Copy, Debug, Component)]
+pub struct OxrSecondaryViewCamera;
+
+/// The swapchain of the [`OxrSecondaryView`]. Moved to the render world once it is created.
+#[derive(Resource)]
+pub struct OxrSecondaryViewSwapchain {
+    pub swapchain: OxrSwapchain,
+    pub images: OxrSwapchainImages,
+    /// Whether an image was released this frame, the secondary view is only submitted if it was.
+    has_image: bool,
+}
+
+impl OxrSession {
+    /// Begins the session like [`begin`](openxr::Session::begin), also enabling a secondary view configuration.
+    ///
+    /// Requires [`XR_MSFT_secondary_view_configuration`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_MSFT_secondary_view_configuration).
+    pub fn begin_with_secondary_view(
+        &self,
+        view_configuration_type: openxr::ViewConfigurationType,
+        secondary_view_configuration_type: openxr::ViewConfigurationType,
+    ) -> openxr::Result<sys::Result> {
+        let secondary_info = sys::SecondaryViewConfigurationSessionBeginInfoMSFT {
+            ty: sys::SecondaryViewConfigurationSessionBeginInfoMSFT::TYPE,
+            next: ptr::null(),
+            view_configuration_count: 1,
+            enabled_view_configuration_types: &secondary_view_configuration_type,
+        };
+        let info = sys::SessionBeginInfo {
+            ty: sys::SessionBeginInfo::TYPE,
+            next: &secondary_info as *const _ as _,
+            primary_view_configuration_type: view_configuration_type,
+        };
+        cvt(unsafe { (self.instance().fp().begin_session)(self.as_raw(), &info) })
+    }
+
+    /// Waits for the next frame like [`wait`](openxr::FrameWaiter::wait), also updating
+    /// whether the secondary view is [`active`](OxrSecondaryView::active).
+    pub fn wait_frame_with_secondary_view(
+        &self,
+        _frame_waiter: &mut OxrFrameWaiter,
+        secondary_view: &mut OxrSecondaryView,
+    ) -> openxr::Result<openxr::FrameState> {
+        let mut view_state = sys::SecondaryViewConfigurationStateMSFT {
+            ty: sys::SecondaryViewConfigurationStateMSFT::TYPE,
+            next: ptr::null_mut(),
+            view_configuration_type: secondary_view.view_configuration_type,
+            active: sys::FALSE,
+        };
+        let mut secondary_state = sys::SecondaryViewConfigurationFrameStateMSFT {
+            ty: sys::SecondaryViewConfigurationFrameStateMSFT::TYPE,
+            next: ptr::null_mut(),
+            view_configuration_count: 1,
+            view_configuration_states: &mut view_state,
+        };
+        let mut state = sys::FrameState {
+            ty: sys::FrameState::TYPE,
+            next: &mut secondary_state as *mut _ as _,
+            predicted_display_time: openxr::Time::from_nanos(0),
+            predicted_display_period: openxr::Duration::from_nanos(0),
+            should_render: sys::FALSE,
+        };
+        cvt(unsafe { (self.instance().fp().wait_frame)(self.as_raw(), ptr::null(), &mut state) })?;
+        secondary_view.active = view_state.active.into();
+        Ok(openxr::FrameState {
+            predicted_display_time: state.predicted_display_time,
+            predicted_display_period: state.predicted_display_period,
+            should_render: state.should_render.into(),
+        })
+    }
+}
+
+impl OxrFrameStream {
+    /// Ends the frame like [`end`](Self::end), also submitting `secondary_layers` for the secondary view.
+    ///
+    /// Unlike [`end`](Self::end), this doesn't check that the layers use the graphics api of the session.
+    pub fn end_with_secondary_view(
+        &mut self,
+        session: &OxrSession,
+        display_time: openxr::Time,
+        environment_blend_mode: openxr::EnvironmentBlendMode,
+        layers: &[&dyn CompositionLayer],
+        secondary_view: &OxrSecondaryView,
+        secondary_layers: &[&dyn CompositionLayer],
+    ) -> Result<()> {
+        let layers = layer_headers(layers);
+        let secondary_layers = layer_headers(secondary_layers);
+        let secondary_layer_info = sys::SecondaryViewConfigurationLayerInfoMSFT {
+            ty: sys::SecondaryViewConfigurationLayerInfoMSFT::TYPE,
+            next: ptr::null(),
+            view_configuration_type: secondary_view.view_configuration_type,
+            environment_blend_mode: secondary_view.environment_blend_mode,
+            layer_count: secondary_layers.len() as u32,
+            layers: secondary_layers.as_ptr(),
+        };
+        let secondary_info = sys::SecondaryViewConfigurationFrameEndInfoMSFT {
+            ty: sys::SecondaryViewConfigurationFrameEndInfoMSFT::TYPE,
+            next: ptr::null(),
+            view_configuration_count: 1,
+            view_configuration_layers_info: &secondary_layer_info,
+        };
+        let info = sys::FrameEndInfo {
+            ty: sys::FrameEndInfo::TYPE,
+            next: &secondary_info as *const _ as _,
+            display_time,
+            environment_blend_mode,
+            layer_count: layers.len() as u32,
+            layers: layers.as_ptr(),
+        };
+        cvt(unsafe { (session.instance().fp().end_frame)(session.as_raw(), &info) })?;
+        Ok(())
+    }
+}
+
+fn layer_headers(layers: &[&dyn CompositionLayer]) -> Vec<*const sys::CompositionLayerBaseHeader> {
+    layers
+        .iter()
+        .map(|layer| layer.header() as *const _)
+        .collect()
+}
+
+/// Builds the projection layer of the secondary view, if an image was rendered for it this frame.
+pub(crate) fn secondary_view_layer(world: &World) -> Option<CompositionLayerProjection<'_>> {
+    let secondary_view = world.get_resource::<OxrSecondaryView>()?;
+    let swapchain = world
+        .get_resource::<OxrSecondaryViewSwapchain>()
+        .filter(|swapchain| swapchain.has_image)?;
+    let space = world.get_resource::<XrPrimaryReferenceSpace>()?;
+    let view = secondary_view.views.first()?;
+    Some(
+        CompositionLayerProjection::new()
+            .layer_flags(CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA)
+            .space(space)
+            .views(&[CompositionLayerProjectionView::new()
+                .pose(view.pose)
+                .fov(view.fov)
+                .sub_image(
+                    SwapchainSubImage::new()
+                        .swapchain(&swapchain.swapchain)
+                        .image_array_index(0)
+                        .image_rect(openxr::Rect2Di {
+                            offset: openxr::Offset2Di { x: 0, y: 0 },
+                            extent: openxr::Extent2Di {
+                                width: secondary_view.resolution.x as _,
+                                height: secondary_view.resolution.y as _,
+                            },
+                        }),
+                )]),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn init_secondary_view(
+    view_configuration_type: Res<SecondaryViewConfigurationType>,
+    instance: Res<OxrInstance>,
+    system_id: Res<OxrSystemId>,
+    session: Res<OxrSession>,
+    graphics_info: Res<OxrGraphicsInfo>,
+    render_device: Res<RenderDevice>,
+    mut manual_texture_views: ResMut<ManualTextureViews>,
+    mut commands: Commands,
+) {
+    let (secondary_view, swapchain) = match create_secondary_view(
+        view_configuration_type.0,
+        &instance,
+        **system_id,
+        &session,
+        &graphics_info,
+        &render_device,
+    ) {
+        Ok(Some(v)) => v,
+        Ok(None) => return,
+        Err(e) => {
+            error!("Failed to create secondary view: {e}");
+            return;
+        }
+    };
+    insert_texture_view(
+        &mut manual_texture_views,
+        &swapchain.images[0],
+        &secondary_view,
+    );
+    commands.spawn((
+        Camera {
+            target: RenderTarget::TextureView(SECONDARY_VIEW_HANDLE),
+            is_active: false,
+            ..default()
+        },
+        Camera3d::default(),
+        XrProjection::default(),
+        XrTracker,
+        OxrSecondaryViewCamera,
+    ));
+    commands.insert_resource(secondary_view);
+    commands.insert_resource(swapchain);
+}
+
+fn create_secondary_view(
+    view_configuration_type: openxr::ViewConfigurationType,
+    instance: &OxrInstance,
+    system_id: openxr::SystemId,
+    session: &OxrSession,
+    graphics_info: &OxrGraphicsInfo,
+    render_device: &RenderDevice,
+) -> Result<Option<(OxrSecondaryView, OxrSecondaryViewSwapchain)>> {
+    if !instance
+        .enumerate_view_configurations(system_id)?
+        .contains(&view_configuration_type)
+    {
+        info!("Secondary view configuration {view_configuration_type:?} is not supported");
+        return Ok(None);
+    }
+    let views = instance.enumerate_view_configuration_views(system_id, view_configuration_type)?;
+    let [view] = views.as_slice() else {
+        warn!(
+            "Secondary view configuration {view_configuration_type:?} has {} views, only single view configurations are supported",
+            views.len()
+        );
+        return Ok(None);
+    };
+    let resolution = UVec2::new(
+        view.recommended_image_rect_width,
+        view.recommended_image_rect_height,
+    );
+    let blend_modes =
+        instance.enumerate_environment_blend_modes(system_id, view_configuration_type)?;
+    let environment_blend_mode = if blend_modes.contains(&graphics_info.blend_mode) {
+        graphics_info.blend_mode
+    } else {
+        *blend_modes.first().ok_or(OxrError::NoAvailableBlendMode)?
+    };
+
+    let swapchain = session.create_swapchain(SwapchainCreateInfo {
+        create_flags: SwapchainCreateFlags::EMPTY,
+        usage_flags: swapchain_usage_flags(graphics_info.swapchain_usage, graphics_info.format),
+        format: graphics_info.format,
+        sample_count: 1,
+        width: resolution.x,
+        height: resolution.y,
+        face_count: 1,
+        array_size: 1,
+        mip_count: 1,
+    })?;
+    let images = swapchain.enumerate_images(
+        render_device.wgpu_device(),
+        graphics_info.format,
+        resolution,
+        1,
+        graphics_info.swapchain_usage,
+    )?;
+    Ok(Some((
+        OxrSecondaryView {
+            view_configuration_type,
+            active: false,
+            environment_blend_mode,
+            resolution,
+            format: graphics_info.format,
+            views: Vec::new(),
+        },
+        OxrSecondaryViewSwapchain {
+            swapchain,
+            images,
+            has_image: false,
+        },
+    )))
+}
+
+fn insert_texture_view(
+    manual_texture_views: &mut ManualTextureViews,
+    texture: &wgpu::Texture,
+    secondary_view: &OxrSecondaryView,
+) {
+    let view = texture.create_view(&wgpu::TextureViewDescriptor {
+        dimension: Some(wgpu::TextureViewDimension::D2),
+        array_layer_count: Some(1),
+        ..default()
+    });
+    manual_texture_views.insert(
+        SECONDARY_VIEW_HANDLE,
+        ManualTextureView {
+            texture_view: view.into(),
+            size: secondary_view.resolution,
+            format: secondary_view.format,
+        },
+    );
+}
+
+fn clean_up_secondary_view(
+    cameras: Query<Entity, With<OxrSecondaryViewCamera>>,
+    mut manual_texture_views: ResMut<ManualTextureViews>,
+    mut commands: Commands,
+) {
+    for e in &cameras {
+        commands.entity(e).despawn_recursive();
+    }
+    manual_texture_views.remove(&SECONDARY_VIEW_HANDLE);
+    commands.remove_resource::<OxrSecondaryView>();
+    commands.remove_resource::<OxrSecondaryViewSwapchain>();
+}
+
+fn clean_up_render_secondary_view(mut commands: Commands) {
+    commands.remove_resource::<OxrSecondaryView>();
+    commands.remove_resource::<OxrSecondaryViewSwapchain>();
+}
+
+fn locate_secondary_view(
+    session: Res<OxrSession>,
+    ref_space: Res<XrPrimaryReferenceSpace>,
+    frame_state: Res<OxrFrameState>,
+    pipelined: Option<Res<Pipelined>>,
+    mut secondary_view: ResMut<OxrSecondaryView>,
+) {
+    if !secondary_view.active {
+        if !secondary_view.views.is_empty() {
+            secondary_view.views.clear();
+        }
+        return;
+    }
+    let time = if pipelined.is_some() {
+        openxr::Time::from_nanos(
+            frame_state.predicted_display_time.as_nanos()
+                + frame_state.predicted_display_period.as_nanos(),
+        )
+    } else {
+        frame_state.predicted_display_time
+    };
+    match session.locate_views(secondary_view.view_configuration_type, time, &ref_space) {
+        Ok((_, views)) => secondary_view.views = views,
+        Err(err) => debug!("Failed to locate secondary views: {err}"),
+    }
+}
+
+fn update_secondary_view_camera(
+    secondary_view: Res<OxrSecondaryView>,
+    frame_state: Res<OxrFrameState>,
+    graphics_backend: Res<OxrGraphicsBackend>,
+    mut cameras: Query<
+        (&mut Camera, &mut Transform, &mut XrProjection),
+        With<OxrSecondaryViewCamera>,
+    >,
+) {
+    let view = secondary_view
+        .views
+        .first()
+        .filter(|_| secondary_view.active && frame_state.should_render);
+    for (mut camera, mut transform, mut projection) in &mut cameras {
+        if camera.is_active != view.is_some() {
+            camera.is_active = view.is_some();
+        }
+        let Some(view) = view else {
+            continue;
+        };
+        transform.translation = view.pose.position.to_vec3();
+        transform.rotation = view.pose.orientation.to_quat();
+        projection.projection_matrix = calculate_projection(
+            projection.near,
+            projection.far,
+            projection.reversed_z,
+            view.fov,
+            graphics_backend.api,
+        );
+    }
+}
+
+fn transfer_secondary_view_swapchain(mut commands: Commands, mut world: ResMut<MainWorld>) {
+    if let Some(swapchain) = world.remove_resource::<OxrSecondaryViewSwapchain>() {
+        commands.insert_resource(swapchain);
+    }
+}
+
+/// A [`Condition`](bevy::ecs::schedule::Condition) system that says if the secondary view is rendered this frame.
+fn secondary_view_active(
+    secondary_view: Option<Res<OxrSecondaryView>>,
+    swapchain: Option<Res<OxrSecondaryViewSwapchain>>,
+) -> bool {
+    secondary_view.is_some_and(|view| view.active) && swapchain.is_some()
+}
+
+fn acquire_secondary_view_image(
+    secondary_view: Res<OxrSecondaryView>,
+    mut swapchain: ResMut<OxrSecondaryViewSwapchain>,
+    mut manual_texture_views: ResMut<ManualTextureViews>,
+) {
+    swapchain.has_image = false;
+    if let Err(e) = swapchain.swapchain.release_pending_image() {
+        warn!("Failed to release pending secondary view image: {e}");
+    }
+    let index = match swapchain.swapchain.acquire_image() {
+        Ok(index) => index,
+        Err(e) => {
+            error!("Failed to acquire secondary view image: {e}");
+            return;
+        }
+    };
+    insert_texture_view(
+        &mut manual_texture_views,
+        &swapchain.images[index as usize],
+        &secondary_view,
+    );
+}
+
+fn wait_secondary_view_image(mut swapchain: ResMut<OxrSecondaryViewSwapchain>) {
+    if swapchain.swapchain.image_state() != OxrSwapchainImageState::Acquired {
+        return;
+    }
+    if let Err(e) = swapchain.swapchain.wait_image(openxr::Duration::INFINITE) {
+        error!("Failed to wait secondary view image: {e}");
+    }
+}
+
+fn release_secondary_view_image(mut swapchain: ResMut<OxrSecondaryViewSwapchain>) {
+    if swapchain.swapchain.image_state() != OxrSwapchainImageState::Waited {
+        return;
+    }
+    match swapchain.swapchain.release_image() {
+        Ok(()) => swapchain.has_image = true,
+        Err(e) => error!("Failed to release secondary view image: {e}"),
+    }
+}
+
+fn cvt(x: sys::Result) -> openxr::Result<sys::Result> {
+    if x.into_raw() >= 0 {
+        Ok(x)
+    } else {
+        Err(x)
+    }
+}
@@ -0,0 +1,350 @@
+//! Application space warp through `XR_FB_space_warp`, which lets the runtime synthesize every other frame
+//! from motion vectors and depth, e.g. to reach 120 Hz while the app renders at 60 Hz.
+//!
+//! The motion vectors and depth have to be rendered by the app, at the [`resolution`](OxrSpaceWarpSwapchains::resolution)
+//! recommended by the runtime. Write them to the [`OxrAcquiredSpaceWarpImages`] from a render graph node,
+//! e.g. by converting the output of a [`MotionVectorPrepass`](bevy::core_pipeline::prepass::MotionVectorPrepass),
+//! and set [`OxrSpaceWarpEnabled`] once that works, nothing is submitted before.
+//! Motion vectors are 3d NDC space deltas from the previous to the current frame stored in the rgb channels.
+use std::ptr;
+
+use bevy::core_pipeline::core_3d::CORE_3D_DEPTH_FORMAT;
+use bevy::prelude::*;
+use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
+use bevy::render::renderer::RenderDevice;
+use bevy::render::{ExtractSchedule, MainWorld, Render, RenderApp};
+use bevy_mod_xr::camera::{XrCamera, XrProjection};
+use bevy_mod_xr::session::{XrPreDestroySession, XrSessionCreated};
+use openxr::{sys, SwapchainCreateFlags};
+
+use crate::exts::OxrEnabledExtensions;
+use crate::init::{should_render, should_run_frame_loop};
+use crate::layer_builder::{CompositionLayerSpaceWarpInfo, SwapchainSubImage};
+use crate::render::OxrRenderStep;
+use crate::resources::{
+    OxrGraphicsInfo, OxrInstance, OxrSwapchain, OxrSwapchainImageState, OxrSwapchainImages,
+    OxrSystemId,
+};
+use crate::session::OxrSession;
+use crate::types::{swapchain_usage_flags, Result, SwapchainCreateInfo};
+
+/// The format of the motion vector swapchain.
+pub const MOTION_VECTOR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Creates the [`OxrSpaceWarpSwapchains`] and submits them with the projection layer while [`OxrSpaceWarpEnabled`].
+///
+/// Requires [`enable_fb_space_warp`](crate::exts::OxrExtensions::enable_fb_space_warp).
+pub struct OxrSpaceWarpPlugin;
+
+impl Plugin for OxrSpaceWarpPlugin {
+    fn build(&self, app: &mut App) {
+        let enabled = app
+            .world()
+            .get_resource::<OxrEnabledExtensions>()
+            .is_some_and(|exts| exts.fb_space_warp);
+        if !enabled {
+            return;
+        }
+        app.init_resource::<OxrSpaceWarpEnabled>()
+            .add_plugins(ExtractResourcePlugin::<OxrSpaceWarpEnabled>::default())
+            .add_systems(XrSessionCreated, init_space_warp)
+            .add_systems(XrPreDestroySession, clean_up_space_warp);
+        app.sub_app_mut(RenderApp)
+            .add_systems(ExtractSchedule, transfer_space_warp_swapchains)
+            .add_systems(XrPreDestroySession, clean_up_space_warp)
+            .add_systems(
+                Render,
+                (
+                    acquire_space_warp_images
+                        .run_if(should_render)
+                        .run_if(|enabled: Option<Res<OxrSpaceWarpEnabled>>| {
+                            enabled.is_some_and(|enabled| enabled.0)
+                        })
+                        .in_set(OxrRenderStep::AcquireImage),
+                    wait_space_warp_images
+                        .run_if(resource_exists::<OxrAcquiredSpaceWarpImages>)
+                        .in_set(OxrRenderStep::WaitImage),
+                    release_space_warp_images.in_set(OxrRenderStep::ReleaseImage),
+                )
+                    .run_if(should_run_frame_loop)
+                    .run_if(resource_exists::<OxrSpaceWarpSwapchains>),
+            );
+    }
+}
+
+/// Whether space warp is used. Defaults to `false`, since the app has to render the motion vectors itself.
+///
+/// Can be toggled at any time, e.g. to only use space warp in scenes that are too heavy for the full frame rate.
+#[derive(Clone, Copy, Debug, Default, Resource, ExtractResource)]
+pub struct OxrSpaceWarpEnabled(pub bool);
+
+/// The motion vector and depth swapchains submitted with the projection layer. Moved to the render world once it is created.
+#[derive(Resource)]
+pub struct OxrSpaceWarpSwapchains {
+    pub motion_vector_swapchain: OxrSwapchain,
+    pub motion_vector_images: OxrSwapchainImages,
+    pub depth_swapchain: OxrSwapchain,
+    pub depth_images: OxrSwapchainImages,
+    pub depth_format: wgpu::TextureFormat,
+    /// Size of a single view in both swapchains, recommended by the runtime.
+    pub resolution: UVec2,
+    /// The distances depth 0.0 and 1.0 map to, taken from the [`XrProjection`] of the first [`XrCamera`].
+    depth_range: (f32, f32),
+    /// Whether images were released this frame, space warp is only submitted if they were.
+    has_image: bool,
+}
+
+impl OxrSpaceWarpSwapchains {
+    pub(crate) fn should_submit(&self) -> bool {
+        self.has_image
+    }
+
+    /// Returns the space warp info of the view at `index`.
+    pub fn info(&self, index: u32) -> CompositionLayerSpaceWarpInfo<'_> {
+        let rect = openxr::Rect2Di {
+            offset: openxr::Offset2Di { x: 0, y: 0 },
+            extent: openxr::Extent2Di {
+                width: self.resolution.x as _,
+                height: self.resolution.y as _,
+            },
+        };
+        let (near_z, far_z) = self.depth_range;
+        CompositionLayerSpaceWarpInfo::new()
+            .motion_vector_sub_image(
+                SwapchainSubImage::new()
+                    .swapchain(&self.motion_vector_swapchain)
+                    .image_array_index(index)
+                    .image_rect(rect),
+            )
+            .depth_sub_image(
+                SwapchainSubImage::new()
+                    .swapchain(&self.depth_swapchain)
+                    .image_array_index(index)
+                    .image_rect(rect),
+            )
+            .min_depth(0.)
+            .max_depth(1.)
+            .near_z(near_z)
+            .far_z(far_z)
+    }
+}
+
+/// The space warp images acquired for the current frame, one array layer per view. Only exists in the render world.
+///
+/// Like [`OxrAcquiredSwapchainImage`](crate::resources::OxrAcquiredSwapchainImage), these can be written to
+/// after [`OxrRenderStep::WaitImage`] until [`OxrRenderStep::ReleaseImage`].
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct OxrAcquiredSpaceWarpImages {
+    pub motion_vectors: &'static wgpu::Texture,
+    pub depth: &'static wgpu::Texture,
+}
+
+impl OxrInstance {
+    /// Returns the motion vector resolution recommended for `XR_FB_space_warp`.
+    pub fn space_warp_resolution(&self, system_id: openxr::SystemId) -> openxr::Result<UVec2> {
+        let mut space_warp = sys::SystemSpaceWarpPropertiesFB {
+            ty: sys::SystemSpaceWarpPropertiesFB::TYPE,
+            next: ptr::null_mut(),
+            recommended_motion_vector_image_rect_width: 0,
+            recommended_motion_vector_image_rect_height: 0,
+        };
+        let mut props = sys::SystemProperties::out(&mut space_warp as *mut _ as _);
+        let result = unsafe {
+            (self.fp().get_system_properties)(self.as_raw(), system_id, props.as_mut_ptr())
+        };
+        if result.into_raw() < 0 {
+            return Err(result);
+        }
+        Ok(UVec2::new(
+            space_warp.recommended_motion_vector_image_rect_width,
+            space_warp.recommended_motion_vector_image_rect_height,
+        ))
+    }
+}
+
+fn init_space_warp(
+    instance: Res<OxrInstance>,
+    system_id: Res<OxrSystemId>,
+    session: Res<OxrSession>,
+    graphics_info: Res<OxrGraphicsInfo>,
+    render_device: Res<RenderDevice>,
+    mut commands: Commands,
+) {
+    match create_space_warp_swapchains(
+        &instance,
+        **system_id,
+        &session,
+        graphics_info.view_count,
+        &render_device,
+    ) {
+        Ok(Some(swapchains)) => commands.insert_resource(swapchains),
+        Ok(None) => {}
+        Err(e) => error!("Failed to create space warp swapchains: {e}"),
+    }
+}
+
+fn create_space_warp_swapchains(
+    instance: &OxrInstance,
+    system_id: openxr::SystemId,
+    session: &OxrSession,
+    view_count: u32,
+    render_device: &RenderDevice,
+) -> Result<Option<OxrSpaceWarpSwapchains>> {
+    let resolution = instance.space_warp_resolution(system_id)?;
+    let available_formats = session.enumerate_swapchain_formats()?;
+    if !available_formats.contains(&MOTION_VECTOR_FORMAT) {
+        warn!(
+            "The runtime doesn't support {MOTION_VECTOR_FORMAT:?} swapchains, not using space warp"
+        );
+        return Ok(None);
+    }
+    let Some(depth_format) = [
+        CORE_3D_DEPTH_FORMAT,
+        wgpu::TextureFormat::Depth24PlusStencil8,
+    ]
+    .into_iter()
+    .find(|format| available_formats.contains(format)) else {
+        warn!(
+            "The runtime doesn't support any depth format bevy can render to, not using space warp"
+        );
+        return Ok(None);
+    };
+
+    let create = |format: wgpu::TextureFormat| -> Result<(OxrSwapchain, OxrSwapchainImages)> {
+        // storage binding allows writing the motion vectors from a compute shader
+        let usage = if format.is_depth_stencil_format() {
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_DST
+        } else {
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::STORAGE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+        };
+        let swapchain = session.create_swapchain(SwapchainCreateInfo {
+            create_flags: SwapchainCreateFlags::EMPTY,
+            usage_flags: swapchain_usage_flags(usage, format),
+            format,
+            sample_count: 1,
+            width: resolution.x,
+            height: resolution.y,
+            face_count: 1,
+            array_size: view_count,
+            mip_count: 1,
+        })?;
+        let images = swapchain.enumerate_images(
+            render_device.wgpu_device(),
+            format,
+            resolution,
+            view_count,
+            usage,
+        )?;
+        Ok((swapchain, images))
+    };
+    let (motion_vector_swapchain, motion_vector_images) = create(MOTION_VECTOR_FORMAT)?;
+    let (depth_swapchain, depth_images) = create(depth_format)?;
+    Ok(Some(OxrSpaceWarpSwapchains {
+        motion_vector_swapchain,
+        motion_vector_images,
+        depth_swapchain,
+        depth_images,
+        depth_format,
+        resolution,
+        depth_range: (0.0, 1.0),
+        has_image: false,
+    }))
+}
+
+fn transfer_space_warp_swapchains(mut commands: Commands, mut world: ResMut<MainWorld>) {
+    if let Some(swapchains) = world.remove_resource::<OxrSpaceWarpSwapchains>() {
+        commands.insert_resource(swapchains);
+    }
+}
+
+fn clean_up_space_warp(mut commands: Commands) {
+    commands.remove_resource::<OxrAcquiredSpaceWarpImages>();
+    commands.remove_resource::<OxrSpaceWarpSwapchains>();
+}
+
+fn acquire_space_warp_images(
+    mut swapchains: ResMut<OxrSpaceWarpSwapchains>,
+    mut commands: Commands,
+) {
+    let swapchains = &mut *swapchains;
+    for swapchain in [
+        &mut swapchains.motion_vector_swapchain,
+        &mut swapchains.depth_swapchain,
+    ] {
+        if swapchain.image_state() != OxrSwapchainImageState::Released {
+            if let Err(e) = swapchain.release_pending_image() {
+                warn!("Failed to release pending space warp image: {e}");
+            }
+        }
+    }
+    let motion_vector_index = match swapchains.motion_vector_swapchain.acquire_image() {
+        Ok(index) => index,
+        Err(e) => {
+            error!("Failed to acquire motion vector image: {e}");
+            return;
+        }
+    };
+    let depth_index = match swapchains.depth_swapchain.acquire_image() {
+        Ok(index) => index,
+        Err(e) => {
+            error!("Failed to acquire space warp depth image: {e}");
+            return;
+        }
+    };
+    commands.insert_resource(OxrAcquiredSpaceWarpImages {
+        motion_vectors: &swapchains.motion_vector_images.0[motion_vector_index as usize],
+        depth: &swapchains.depth_images.0[depth_index as usize],
+    });
+}
+
+fn wait_space_warp_images(mut swapchains: ResMut<OxrSpaceWarpSwapchains>) {
+    let swapchains = &mut *swapchains;
+    for swapchain in [
+        &mut swapchains.motion_vector_swapchain,
+        &mut swapchains.depth_swapchain,
+    ] {
+        if swapchain.image_state() != OxrSwapchainImageState::Acquired {
+            continue;
+        }
+        if let Err(e) = swapchain.wait_image(openxr::Duration::INFINITE) {
+            error!("Failed to wait space warp image: {e}");
+        }
+    }
+}
+
+fn release_space_warp_images(
+    mut swapchains: ResMut<OxrSpaceWarpSwapchains>,
+    cameras: Query<(&XrCamera, &XrProjection)>,
+    mut commands: Commands,
+) {
+    commands.remove_resource::<OxrAcquiredSpaceWarpImages>();
+    let swapchains = &mut *swapchains;
+    swapchains.has_image = false;
+    let mut released = true;
+    for swapchain in [
+        &mut swapchains.motion_vector_swapchain,
+        &mut swapchains.depth_swapchain,
+    ] {
+        if swapchain.image_state() != OxrSwapchainImageState::Waited {
+            released = false;
+            continue;
+        }
+        if let Err(e) = swapchain.release_image() {
+            error!("Failed to release space warp image: {e}");
+            released = false;
+        }
+    }
+    let Some((_, projection)) = cameras.iter().find(|(camera, _)| camera.0 == 0) else {
+        return;
+    };
+    let (near, far) = projection.depth_range();
+    // the runtime wants the distances at depth 0.0 and 1.0, which are swapped for reversed depth
+    swapchains.depth_range = if projection.reversed_z {
+        (far, near)
+    } else {
+        (near, far)
+    };
+    swapchains.has_image = released;
+}
@@ -0,0 +1,352 @@
+use std::fmt;
+use std::ptr;
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use bevy_mod_xr::spaces::{XrSpace, XrSpatialAnchor};
+use openxr::{sys, Event};
+
+use crate::helper_traits::ToPosef;
+use crate::poll_events::{OxrEvent, OxrEventHandlerExt};
+use crate::session::OxrSession;
+use crate::spaces::{OxrSpaceExt, OxrSpaceSyncSet};
+
+/// Sends the results of the asynchronous spatial anchor requests of [`OxrSession`] as bevy [`Event`]s
+/// and keeps the [`XrSpace`] of every [`XrSpatialAnchor`] entity up to date.
+///
+/// Requires [`enable_fb_spatial_anchors`](crate::exts::OxrExtensions::enable_fb_spatial_anchors).
+pub struct OxrSpatialAnchorPlugin;
+
+impl Plugin for OxrSpatialAnchorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OxrSpatialAnchorCreated>()
+            .add_event::<OxrSpatialAnchorSaved>()
+            .add_event::<OxrSpatialAnchorLoaded>()
+            .add_oxr_event_handler(handle_spatial_anchor_events)
+            .add_systems(PreUpdate, insert_anchor_spaces.before(OxrSpaceSyncSet));
+    }
+}
+
+/// The UUID of a spatial anchor, used to load a saved anchor again in a later session.
+///
+/// Formats as and parses from the usual hyphenated hex representation,
+/// e.g. `0f8fad5b-d9cb-469f-a165-70867728950e`, so it can be stored as a string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OxrSpaceUuid(pub [u8; 16]);
+
+impl From<sys::UuidEXT> for OxrSpaceUuid {
+    fn from(uuid: sys::UuidEXT) -> Self {
+        Self(uuid.data)
+    }
+}
+
+impl From<OxrSpaceUuid> for sys::UuidEXT {
+    fn from(uuid: OxrSpaceUuid) -> Self {
+        sys::UuidEXT { data: uuid.0 }
+    }
+}
+
+impl fmt::Display for OxrSpaceUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                f.write_str("-")?;
+            }
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("Invalid spatial anchor UUID")]
+pub struct OxrParseSpaceUuidError;
+
+impl FromStr for OxrSpaceUuid {
+    type Err = OxrParseSpaceUuidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.bytes().filter(|c| *c != b'-').collect::<Vec<_>>();
+        if digits.len() != 32 {
+            return Err(OxrParseSpaceUuidError);
+        }
+        let mut data = [0; 16];
+        for (byte, pair) in data.iter_mut().zip(digits.chunks(2)) {
+            let pair = std::str::from_utf8(pair).map_err(|_| OxrParseSpaceUuidError)?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| OxrParseSpaceUuidError)?;
+        }
+        Ok(Self(data))
+    }
+}
+
+/// Sent when a request of [`OxrSession::create_spatial_anchor`] completed.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct OxrSpatialAnchorCreated {
+    pub request_id: sys::AsyncRequestIdFB,
+    /// The created anchor, spawn it on an entity to have its [`Transform`] updated.
+    pub result: openxr::Result<(XrSpatialAnchor, OxrSpaceUuid)>,
+}
+
+/// Sent when a request of [`OxrSession::save_spatial_anchor`] completed.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct OxrSpatialAnchorSaved {
+    pub request_id: sys::AsyncRequestIdFB,
+    pub anchor: XrSpatialAnchor,
+    /// The UUID to pass to [`OxrSession::load_spatial_anchors`] in a later session.
+    pub result: openxr::Result<OxrSpaceUuid>,
+}
+
+/// Sent for every anchor found by a request of [`OxrSession::load_spatial_anchors`].
+///
+/// Anchors that couldn't be found are skipped, the request completing is only logged.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct OxrSpatialAnchorLoaded {
+    pub request_id: sys::AsyncRequestIdFB,
+    pub anchor: XrSpatialAnchor,
+    pub uuid: OxrSpaceUuid,
+}
+
+impl OxrSession {
+    /// Requests a spatial anchor at `pose` in `base` at `time`.
+    ///
+    /// The anchor is sent with an [`OxrSpatialAnchorCreated`] event with the returned request id.
+    ///
+    /// Requires [`XR_FB_spatial_entity`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_FB_spatial_entity).
+    pub fn create_spatial_anchor(
+        &self,
+        base: &XrSpace,
+        pose: impl ToPosef,
+        time: openxr::Time,
+    ) -> openxr::Result<sys::AsyncRequestIdFB> {
+        let fp = self.spatial_entity_fp()?;
+        let info = sys::SpatialAnchorCreateInfoFB {
+            ty: sys::SpatialAnchorCreateInfoFB::TYPE,
+            next: ptr::null(),
+            space: base.as_raw_openxr_space(),
+            pose_in_space: pose.to_posef(),
+            time,
+        };
+        let mut request_id = sys::AsyncRequestIdFB::from_raw(0);
+        cvt(unsafe { (fp.create_spatial_anchor)(self.as_raw(), &info, &mut request_id) })?;
+        Ok(request_id)
+    }
+
+    /// Requests saving a spatial anchor to the local storage of the device, so it can be loaded in later sessions.
+    ///
+    /// The UUID is sent with an [`OxrSpatialAnchorSaved`] event with the returned request id.
+    ///
+    /// Requires [`XR_FB_spatial_entity_storage`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_FB_spatial_entity_storage).
+    pub fn save_spatial_anchor(
+        &self,
+        anchor: &XrSpatialAnchor,
+    ) -> openxr::Result<sys::AsyncRequestIdFB> {
+        let fp = self
+            .instance()
+            .exts()
+            .fb_spatial_entity_storage
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)?;
+        let info = sys::SpaceSaveInfoFB {
+            ty: sys::SpaceSaveInfoFB::TYPE,
+            next: ptr::null(),
+            space: anchor.as_raw_openxr_space(),
+            location: sys::SpaceStorageLocationFB::LOCAL,
+            persistence_mode: sys::SpacePersistenceModeFB::INDEFINITE,
+        };
+        let mut request_id = sys::AsyncRequestIdFB::from_raw(0);
+        cvt(unsafe { (fp.save_space)(self.as_raw(), &info, &mut request_id) })?;
+        Ok(request_id)
+    }
+
+    /// Requests loading previously saved spatial anchors from the local storage of the device.
+    ///
+    /// Every anchor found is sent with an [`OxrSpatialAnchorLoaded`] event with the returned request id.
+    ///
+    /// Requires [`XR_FB_spatial_entity_query`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_FB_spatial_entity_query).
+    pub fn load_spatial_anchors(
+        &self,
+        uuids: &[OxrSpaceUuid],
+    ) -> openxr::Result<sys::AsyncRequestIdFB> {
+        let fp = self.spatial_entity_query_fp()?;
+        let mut uuids = uuids
+            .iter()
+            .copied()
+            .map(sys::UuidEXT::from)
+            .collect::<Vec<_>>();
+        let location_filter = sys::SpaceStorageLocationFilterInfoFB {
+            ty: sys::SpaceStorageLocationFilterInfoFB::TYPE,
+            next: ptr::null(),
+            location: sys::SpaceStorageLocationFB::LOCAL,
+        };
+        let filter = sys::SpaceUuidFilterInfoFB {
+            ty: sys::SpaceUuidFilterInfoFB::TYPE,
+            next: &location_filter as *const _ as _,
+            uuid_count: uuids.len() as u32,
+            uuids: uuids.as_mut_ptr(),
+        };
+        let info = sys::SpaceQueryInfoFB {
+            ty: sys::SpaceQueryInfoFB::TYPE,
+            next: ptr::null(),
+            query_action: sys::SpaceQueryActionFB::LOAD,
+            max_result_count: uuids.len() as u32,
+            timeout: openxr::Duration::INFINITE,
+            filter: &filter as *const _ as _,
+            exclude_filter: ptr::null(),
+        };
+        let mut request_id = sys::AsyncRequestIdFB::from_raw(0);
+        cvt(unsafe { (fp.query_spaces)(self.as_raw(), &info as *const _ as _, &mut request_id) })?;
+        Ok(request_id)
+    }
+
+    fn retrieve_spatial_anchor_query_results(
+        &self,
+        request_id: sys::AsyncRequestIdFB,
+    ) -> openxr::Result<Vec<sys::SpaceQueryResultFB>> {
+        let fp = self.spatial_entity_query_fp()?;
+        let mut results = sys::SpaceQueryResultsFB {
+            ty: sys::SpaceQueryResultsFB::TYPE,
+            next: ptr::null_mut(),
+            result_capacity_input: 0,
+            result_count_output: 0,
+            results: ptr::null_mut(),
+        };
+        cvt(unsafe { (fp.retrieve_space_query_results)(self.as_raw(), request_id, &mut results) })?;
+        let mut buffer = vec![
+            sys::SpaceQueryResultFB {
+                space: sys::Space::NULL,
+                uuid: sys::UuidEXT { data: [0; 16] },
+            };
+            results.result_count_output as usize
+        ];
+        results.result_capacity_input = buffer.len() as u32;
+        results.results = buffer.as_mut_ptr();
+        cvt(unsafe { (fp.retrieve_space_query_results)(self.as_raw(), request_id, &mut results) })?;
+        buffer.truncate(results.result_count_output as usize);
+        Ok(buffer)
+    }
+
+    /// Enables a component of a spatial entity, e.g. [`LOCATABLE`](sys::SpaceComponentTypeFB::LOCATABLE)
+    /// so it can be located, which isn't the case for loaded anchors by default.
+    ///
+    /// Enabling an already enabled component isn't an error.
+    pub fn enable_space_component(
+        &self,
+        space: &XrSpace,
+        component_type: sys::SpaceComponentTypeFB,
+    ) -> openxr::Result<()> {
+        let fp = self.spatial_entity_fp()?;
+        let info = sys::SpaceComponentStatusSetInfoFB {
+            ty: sys::SpaceComponentStatusSetInfoFB::TYPE,
+            next: ptr::null(),
+            component_type,
+            enabled: true.into(),
+            timeout: openxr::Duration::INFINITE,
+        };
+        let mut request_id = sys::AsyncRequestIdFB::from_raw(0);
+        match cvt(unsafe {
+            (fp.set_space_component_status)(space.as_raw_openxr_space(), &info, &mut request_id)
+        }) {
+            Ok(_) | Err(sys::Result::ERROR_SPACE_COMPONENT_STATUS_ALREADY_SET_FB) => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn spatial_entity_fp(&self) -> openxr::Result<&openxr::raw::SpatialEntityFB> {
+        self.instance()
+            .exts()
+            .fb_spatial_entity
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)
+    }
+
+    fn spatial_entity_query_fp(&self) -> openxr::Result<&openxr::raw::SpatialEntityQueryFB> {
+        self.instance()
+            .exts()
+            .fb_spatial_entity_query
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)
+    }
+}
+
+fn handle_spatial_anchor_events(
+    event: In<OxrEvent>,
+    session: Option<Res<OxrSession>>,
+    mut created: EventWriter<OxrSpatialAnchorCreated>,
+    mut saved: EventWriter<OxrSpatialAnchorSaved>,
+    mut loaded: EventWriter<OxrSpatialAnchorLoaded>,
+) {
+    let Some(session) = session else {
+        return;
+    };
+    // this unwrap will never panic since we are in a valid scope
+    match unsafe { event.get() }.unwrap() {
+        Event::SpatialAnchorCreateCompleteFB(event) => {
+            // registering the space with the destroy space patching keeps openxr from destroying it a second time
+            let result = cvt(event.result()).map(|_| {
+                (
+                    XrSpatialAnchor(XrSpace::from_raw_openxr_space(event.space())),
+                    OxrSpaceUuid::from(event.uuid()),
+                )
+            });
+            created.send(OxrSpatialAnchorCreated {
+                request_id: event.request_id(),
+                result,
+            });
+        }
+        Event::SpaceSaveCompleteFB(event) => {
+            let space = event.space();
+            saved.send(OxrSpatialAnchorSaved {
+                request_id: event.request_id(),
+                anchor: XrSpatialAnchor(unsafe { XrSpace::from_raw(space.into_raw()) }),
+                result: cvt(event.result()).map(|_| OxrSpaceUuid::from(event.uuid())),
+            });
+        }
+        Event::SpaceQueryResultsAvailableFB(event) => {
+            let request_id = event.request_id();
+            let results = match session.retrieve_spatial_anchor_query_results(request_id) {
+                Ok(results) => results,
+                Err(err) => {
+                    error!("Unable to retrieve loaded spatial anchors: {err}");
+                    return;
+                }
+            };
+            for result in results {
+                let space = XrSpace::from_raw_openxr_space(result.space);
+                if let Err(err) =
+                    session.enable_space_component(&space, sys::SpaceComponentTypeFB::LOCATABLE)
+                {
+                    warn!("Unable to make loaded spatial anchor locatable: {err}");
+                }
+                loaded.send(OxrSpatialAnchorLoaded {
+                    request_id,
+                    anchor: XrSpatialAnchor(space),
+                    uuid: OxrSpaceUuid::from(result.uuid),
+                });
+            }
+        }
+        Event::SpaceQueryCompleteFB(event) => {
+            if let Err(err) = cvt(event.result()) {
+                warn!("Loading spatial anchors failed: {err}");
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Adds the [`XrSpace`] of spawned anchors, so they are located like any other space.
+fn insert_anchor_spaces(
+    query: Query<(Entity, &XrSpatialAnchor), Without<XrSpace>>,
+    mut cmds: Commands,
+) {
+    for (entity, anchor) in &query {
+        cmds.entity(entity).insert(anchor.0);
+    }
+}
+
+fn cvt(x: sys::Result) -> openxr::Result<sys::Result> {
+    if x.into_raw() >= 0 {
+        Ok(x)
+    } else {
+        Err(x)
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::transform::TransformSystem;
+use bevy::window::WindowRef;
+use bevy_mod_xr::session::XrTracker;
+
+use crate::helper_traits::{ToQuat, ToVec3};
+use crate::render::update_views;
+use crate::resources::OxrViews;
+
+/// Spawns a flat camera that follows one of the views of the headset and renders into a window,
+/// e.g. to show a mirror of the headset to an audience.
+///
+/// The scene is rendered a second time for this camera, nothing is copied from the swapchain.
+pub struct OxrSpectatorCameraPlugin {
+    pub eye: OxrSpectatorEye,
+    /// The window the spectator camera renders to.
+    pub window: WindowRef,
+}
+
+impl Default for OxrSpectatorCameraPlugin {
+    fn default() -> Self {
+        Self {
+            eye: default(),
+            window: WindowRef::Primary,
+        }
+    }
+}
+
+impl Plugin for OxrSpectatorCameraPlugin {
+    fn build(&self, app: &mut App) {
+        let eye = self.eye;
+        let window = self.window;
+        app.add_systems(Startup, move |mut cmds: Commands| {
+            cmds.spawn((
+                Camera3d::default(),
+                Camera {
+                    target: RenderTarget::Window(window),
+                    ..default()
+                },
+                OxrSpectatorCamera { eye },
+                XrTracker,
+            ));
+        })
+        .add_systems(
+            PostUpdate,
+            update_spectator_cameras
+                .after(update_views)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Which view of the headset a spectator camera follows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OxrSpectatorEye {
+    #[default]
+    Left,
+    Right,
+    /// Halfway between the left and right eye, which is more comfortable to watch.
+    Center,
+}
+
+/// A camera following a view of the headset, spawned by [`OxrSpectatorCameraPlugin`].
+///
+/// Has to be a child of the [`XrTrackingRoot`](bevy_mod_xr::session::XrTrackingRoot) to line up with the headset.
+#[derive(Clone, Copy, Debug, Component)]
+pub struct OxrSpectatorCamera {
+    pub eye: OxrSpectatorEye,
+}
+
+fn update_spectator_cameras(
+    views: Res<OxrViews>,
+    mut query: Query<(&OxrSpectatorCamera, &mut Transform, &mut Projection)>,
+) {
+    let (Some(left), Some(right)) = (views.first(), views.get(1).or(views.first())) else {
+        return;
+    };
+    for (camera, mut transform, mut projection) in &mut query {
+        let (translation, rotation) = match camera.eye {
+            OxrSpectatorEye::Left => (
+                left.pose.position.to_vec3(),
+                left.pose.orientation.to_quat(),
+            ),
+            OxrSpectatorEye::Right => (
+                right.pose.position.to_vec3(),
+                right.pose.orientation.to_quat(),
+            ),
+            OxrSpectatorEye::Center => (
+                left.pose
+                    .position
+                    .to_vec3()
+                    .lerp(right.pose.position.to_vec3(), 0.5),
+                left.pose
+                    .orientation
+                    .to_quat()
+                    .slerp(right.pose.orientation.to_quat(), 0.5),
+            ),
+        };
+        *transform = Transform {
+            translation,
+            rotation,
+            ..default()
+        };
+        let view = if camera.eye == OxrSpectatorEye::Right {
+            right
+        } else {
+            left
+        };
+        // the window has its own aspect ratio, so only the vertical field of view is kept
+        let fov = view.fov.angle_up - view.fov.angle_down;
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            if perspective.fov != fov {
+                perspective.fov = fov;
+            }
+        } else {
+            *projection = Projection::Perspective(PerspectiveProjection { fov, ..default() });
+        }
+    }
+}
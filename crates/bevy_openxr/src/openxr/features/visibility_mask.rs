@@ -0,0 +1,106 @@
+//! The area of each view that is hidden by the lenses, from
+//! [`XR_KHR_visibility_mask`](https://registry.khronos.org/OpenXR/specs/1.0/html/xrspec.html#XR_KHR_visibility_mask).
+//!
+//! Rendering the [`OxrVisibilityMask`] into the depth or stencil buffer before the main passes lets the GPU skip
+//! shading pixels that are never shown, which saves fill rate on headsets with a large hidden area.
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy_mod_xr::session::{XrPreDestroySession, XrSessionCreated};
+use openxr::{Event, VisibilityMaskTypeKHR};
+
+use crate::exts::OxrEnabledExtensions;
+use crate::poll_events::{OxrEvent, OxrEventHandlerExt};
+use crate::resources::OxrGraphicsInfo;
+use crate::session::OxrSession;
+
+/// Keeps [`OxrVisibilityMask`] up to date.
+///
+/// Requires [`enable_khr_visibility_mask`](crate::exts::OxrExtensions::enable_khr_visibility_mask).
+pub struct OxrVisibilityMaskPlugin;
+
+impl Plugin for OxrVisibilityMaskPlugin {
+    fn build(&self, app: &mut App) {
+        if !app
+            .world()
+            .get_resource::<OxrEnabledExtensions>()
+            .is_some_and(|exts| exts.khr_visibility_mask)
+        {
+            return;
+        }
+        app.add_event::<OxrVisibilityMaskChanged>()
+            .add_oxr_event_handler(handle_visibility_mask_event)
+            .add_systems(XrSessionCreated, update_visibility_mask)
+            .add_systems(
+                PreUpdate,
+                update_visibility_mask.run_if(on_event::<OxrVisibilityMaskChanged>),
+            )
+            .add_systems(XrPreDestroySession, remove_visibility_mask);
+    }
+}
+
+/// The hidden area of every view as a triangle list mesh.
+///
+/// The vertices lie on the `z = -1` plane of the view's space, so transforming them with the projection matrix
+/// of the view's camera gives their position in clip space.
+/// Only exists while a session is running and the runtime supports the extension.
+#[derive(Resource, Clone, Debug)]
+pub struct OxrVisibilityMask {
+    pub per_view: Vec<Mesh>,
+}
+
+/// Sent by the runtime when the visibility mask of a view changed, e.g. after the lens distance was adjusted.
+#[derive(Event, Clone, Copy, Debug)]
+struct OxrVisibilityMaskChanged;
+
+fn handle_visibility_mask_event(
+    event: In<OxrEvent>,
+    mut writer: EventWriter<OxrVisibilityMaskChanged>,
+) {
+    // this unwrap will never panic since we are in a valid scope
+    if let Event::VisibilityMaskChangedKHR(_) = unsafe { event.get() }.unwrap() {
+        writer.send(OxrVisibilityMaskChanged);
+    }
+}
+
+fn update_visibility_mask(
+    session: Res<OxrSession>,
+    graphics_info: Option<Res<OxrGraphicsInfo>>,
+    mut commands: Commands,
+) {
+    let Some(graphics_info) = graphics_info else {
+        return;
+    };
+    let mut per_view = Vec::with_capacity(graphics_info.view_count as usize);
+    for view in 0..graphics_info.view_count {
+        let mask = match session.get_visibility_mask_khr(
+            graphics_info.view_configuration_type,
+            view,
+            VisibilityMaskTypeKHR::HIDDEN_TRIANGLE_MESH,
+        ) {
+            Ok(mask) => mask,
+            Err(e) => {
+                warn!("Failed to get the visibility mask of view {view}: {e}");
+                return;
+            }
+        };
+        let positions = mask
+            .vertices
+            .iter()
+            .map(|vertex| [vertex.x, vertex.y, -1.0])
+            .collect::<Vec<_>>();
+        per_view.push(
+            Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+            .with_inserted_indices(Indices::U32(mask.indices)),
+        );
+    }
+    commands.insert_resource(OxrVisibilityMask { per_view });
+}
+
+fn remove_visibility_mask(mut commands: Commands) {
+    commands.remove_resource::<OxrVisibilityMask>();
+}
@@ -1,5 +1,5 @@
-// #[cfg(all(feature = "d3d12", windows))]
-// mod d3d12;
+#[cfg(all(feature = "d3d12", windows))]
+pub mod d3d12;
 #[cfg(feature = "vulkan")]
 pub mod vulkan;
 
@@ -10,7 +10,7 @@ use openxr::{FrameStream, FrameWaiter, Session};
 
 use crate::{
     session::OxrSessionCreateNextChain,
-    types::{AppInfo, OxrExtensions, Result, WgpuGraphics},
+    types::{AppInfo, OxrDeviceDescriptorFn, OxrExtensions, Result, WgpuGraphics},
 };
 
 /// This is an extension trait to the [`Graphics`](openxr::Graphics) trait and is how the graphics API should be interacted with.
@@ -25,20 +25,28 @@ pub unsafe trait GraphicsExt: openxr::Graphics {
     fn into_wgpu_format(format: Self::Format) -> Option<wgpu::TextureFormat>;
     /// Convert an API specific swapchain image to a [`Texture`](wgpu::Texture).
     ///
+    /// `array_size` is the number of array layers the swapchain was created with.
+    ///
     /// # Safety
     ///
-    /// The `image` argument must be a valid handle.
+    /// The `image` argument must be a valid handle and the swapchain must have been created with
+    /// the [`swapchain_usage_flags`](crate::types::swapchain_usage_flags) for `usage`.
     unsafe fn to_wgpu_img(
         image: Self::SwapchainImage,
         device: &wgpu::Device,
         format: wgpu::TextureFormat,
         resolution: UVec2,
+        array_size: u32,
+        usage: wgpu::TextureUsages,
     ) -> Result<wgpu::Texture>;
     /// Initialize graphics for this backend and return a [`WgpuGraphics`] for bevy and an API specific [Self::SessionCreateInfo] for openxr
+    ///
+    /// `device_descriptor` can change the features and limits the render device is created with.
     fn init_graphics(
         app_info: &AppInfo,
         instance: &openxr::Instance,
         system_id: openxr::SystemId,
+        device_descriptor: Option<&OxrDeviceDescriptorFn>,
     ) -> Result<(WgpuGraphics, Self::SessionCreateInfo)>;
     unsafe fn create_session(
         instance: &openxr::Instance,
@@ -77,8 +85,8 @@ impl GraphicsBackend {
     const ALL: &'static [Self] = &[
         #[cfg(feature = "vulkan")]
         Self::Vulkan(()),
-        // #[cfg(all(feature = "d3d12", windows))]
-        // Self::D3D12(()),
+        #[cfg(all(feature = "d3d12", windows))]
+        Self::D3D12(()),
     ];
 
     pub fn available_backends(exts: &OxrExtensions) -> Vec<Self> {
@@ -99,6 +107,16 @@ impl GraphicsBackend {
             _ => Api::required_exts()
         )
     }
+
+    /// The wgpu backend the render device is created with when using this graphics API.
+    pub fn wgpu_backend(&self) -> wgpu::Backend {
+        match self {
+            #[cfg(feature = "vulkan")]
+            Self::Vulkan(()) => wgpu::Backend::Vulkan,
+            #[cfg(all(feature = "d3d12", windows))]
+            Self::D3D12(()) => wgpu::Backend::Dx12,
+        }
+    }
 }
 
 /// This struct is for creating agnostic objects for OpenXR graphics API specific structs.
@@ -106,8 +124,8 @@ impl GraphicsBackend {
 pub enum GraphicsWrap<T: GraphicsType> {
     #[cfg(feature = "vulkan")]
     Vulkan(T::Inner<openxr::Vulkan>),
-    // #[cfg(all(feature = "d3d12", windows))]
-    // D3D12(T::Inner<openxr::D3D12>),
+    #[cfg(all(feature = "d3d12", windows))]
+    D3D12(T::Inner<openxr::D3D12>),
 }
 
 impl<T: GraphicsType> GraphicsWrap<T> {
@@ -176,12 +194,12 @@ macro_rules! graphics_match {
                 type Api = openxr::Vulkan;
                 graphics_match!(@arm_impl Vulkan; $expr $(=> $($return)*)?)
             },
-            // #[cfg(all(feature = "d3d12", windows))]
-            // $crate::graphics::GraphicsWrap::D3D12($var) => {
-            //     #[allow(unused)]
-            //     type Api = openxr::D3D12;
-            //     graphics_match!(@arm_impl D3D12; $expr $(=> $($return)*)?)
-            // },
+            #[cfg(all(feature = "d3d12", windows))]
+            $crate::graphics::GraphicsWrap::D3D12($var) => {
+                #[allow(unused)]
+                type Api = openxr::D3D12;
+                graphics_match!(@arm_impl D3D12; $expr $(=> $($return)*)?)
+            },
         }
     };
 
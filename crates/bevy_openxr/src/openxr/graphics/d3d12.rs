@@ -1,13 +1,22 @@
+use std::ffi::c_void;
+
 use bevy::log::error;
 use openxr::sys;
 use wgpu_hal::{Adapter, Instance};
-use winapi::shared::dxgiformat::DXGI_FORMAT;
-use winapi::um::d3d12 as winapi_d3d12;
+use windows::core::Interface;
+use windows::Win32::Graphics::Direct3D::{
+    D3D_FEATURE_LEVEL, D3D_FEATURE_LEVEL_11_0, D3D_FEATURE_LEVEL_11_1, D3D_FEATURE_LEVEL_12_0,
+    D3D_FEATURE_LEVEL_12_1,
+};
+use windows::Win32::Graphics::Direct3D12::{
+    ID3D12Device, ID3D12Resource, D3D12_FEATURE_DATA_FEATURE_LEVELS, D3D12_FEATURE_FEATURE_LEVELS,
+};
+use windows::Win32::Graphics::Dxgi::Common::*;
 
 use super::{GraphicsExt, GraphicsType, GraphicsWrap};
 use crate::error::OxrError;
 use crate::session::OxrSessionCreateNextChain;
-use crate::types::{AppInfo, OxrExtensions, Result, WgpuGraphics};
+use crate::types::{AppInfo, OxrDeviceDescriptorFn, OxrExtensions, Result, WgpuGraphics};
 
 unsafe impl GraphicsExt for openxr::D3D12 {
     fn wrap<T: GraphicsType>(item: T::Inner<Self>) -> GraphicsWrap<T> {
@@ -21,11 +30,11 @@ unsafe impl GraphicsExt for openxr::D3D12 {
     }
 
     fn from_wgpu_format(format: wgpu::TextureFormat) -> Option<Self::Format> {
-        wgpu_to_d3d12(format)
+        wgpu_to_d3d12(format).map(|format| format.0 as Self::Format)
     }
 
     fn into_wgpu_format(format: Self::Format) -> Option<wgpu::TextureFormat> {
-        d3d12_to_wgpu(format)
+        d3d12_to_wgpu(DXGI_FORMAT(format as i32))
     }
 
     unsafe fn to_wgpu_img(
@@ -33,15 +42,22 @@ unsafe impl GraphicsExt for openxr::D3D12 {
         device: &wgpu::Device,
         format: wgpu::TextureFormat,
         resolution: bevy::prelude::UVec2,
+        array_size: u32,
+        usage: wgpu::TextureUsages,
     ) -> Result<wgpu::Texture> {
+        // the runtime owns the image, so the texture holds its own reference to it
+        let raw_image = image as *mut c_void;
+        let resource = ID3D12Resource::from_raw_borrowed(&raw_image)
+            .ok_or(OxrError::OpenXrError(sys::Result::ERROR_HANDLE_INVALID))?
+            .clone();
         let wgpu_hal_texture = <wgpu_hal::dx12::Api as wgpu_hal::Api>::Device::texture_from_raw(
-            d3d12::ComPtr::from_raw(image as *mut _),
+            resource,
             format,
             wgpu::TextureDimension::D2,
             wgpu::Extent3d {
                 width: resolution.x,
                 height: resolution.y,
-                depth_or_array_layers: 2,
+                depth_or_array_layers: array_size,
             },
             1,
             1,
@@ -53,13 +69,13 @@ unsafe impl GraphicsExt for openxr::D3D12 {
                 size: wgpu::Extent3d {
                     width: resolution.x,
                     height: resolution.y,
-                    depth_or_array_layers: 2,
+                    depth_or_array_layers: array_size,
                 },
                 mip_level_count: 1,
                 sample_count: 1,
                 dimension: wgpu::TextureDimension::D2,
-                format: format,
-                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_DST,
+                format,
+                usage,
                 view_formats: &[],
             },
         );
@@ -70,6 +86,7 @@ unsafe impl GraphicsExt for openxr::D3D12 {
         app_info: &AppInfo,
         instance: &openxr::Instance,
         system_id: openxr::SystemId,
+        device_descriptor: Option<&OxrDeviceDescriptorFn>,
     ) -> Result<(WgpuGraphics, Self::SessionCreateInfo)> {
         let reqs = instance.graphics_requirements::<openxr::D3D12>(system_id)?;
 
@@ -87,13 +104,15 @@ unsafe impl GraphicsExt for openxr::D3D12 {
         let wgpu_raw_instance: wgpu_hal::dx12::Instance =
             unsafe { wgpu_hal::dx12::Instance::init(instance_descriptor)? };
         let wgpu_adapters: Vec<wgpu_hal::ExposedAdapter<wgpu_hal::dx12::Api>> =
-            unsafe { wgpu_raw_instance.enumerate_adapters() };
+            unsafe { wgpu_raw_instance.enumerate_adapters(None) };
 
+        // the runtime requires the adapter the headset is connected to
         let wgpu_exposed_adapter = wgpu_adapters
             .into_iter()
             .find(|a| {
-                let mut desc = unsafe { std::mem::zeroed() };
-                unsafe { a.adapter.raw_adapter().GetDesc1(&mut desc) };
+                let Ok(desc) = (unsafe { a.adapter.raw_adapter().GetDesc2() }) else {
+                    return false;
+                };
                 desc.AdapterLuid.HighPart == reqs.adapter_luid.HighPart
                     && desc.AdapterLuid.LowPart == reqs.adapter_luid.LowPart
             })
@@ -104,41 +123,48 @@ unsafe impl GraphicsExt for openxr::D3D12 {
         let wgpu_instance =
             unsafe { wgpu::Instance::from_hal::<wgpu_hal::api::Dx12>(wgpu_raw_instance) };
 
-        let wgpu_features = wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
-            | wgpu::Features::MULTIVIEW
-            | wgpu::Features::MULTI_DRAW_INDIRECT_COUNT
-            | wgpu::Features::MULTI_DRAW_INDIRECT;
-
-        let wgpu_limits = wgpu_exposed_adapter.capabilities.limits.clone();
+        let mut wgpu_device_descriptor = wgpu::DeviceDescriptor {
+            label: Some("bevy_oxr device"),
+            required_features: wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
+                | wgpu::Features::MULTIVIEW
+                | wgpu::Features::MULTI_DRAW_INDIRECT_COUNT
+                | wgpu::Features::MULTI_DRAW_INDIRECT,
+            required_limits: wgpu_exposed_adapter.capabilities.limits.clone(),
+            memory_hints: wgpu::MemoryHints::Performance,
+        };
+        if let Some(device_descriptor) = device_descriptor {
+            device_descriptor(&mut wgpu_device_descriptor);
+        }
+        let wgpu_features = wgpu_device_descriptor.required_features;
+        let wgpu_limits = wgpu_device_descriptor.required_limits.clone();
 
         let wgpu_open_device = unsafe {
-            wgpu_exposed_adapter
-                .adapter
-                .open(wgpu_features, &wgpu_limits)?
+            wgpu_exposed_adapter.adapter.open(
+                wgpu_features,
+                &wgpu_limits,
+                &wgpu_device_descriptor.memory_hints,
+            )?
         };
 
-        let device_supported_feature_level: d3d12::FeatureLevel =
+        let device_supported_feature_level =
             get_device_feature_level(wgpu_open_device.device.raw_device());
 
-        if (device_supported_feature_level as u32) < (reqs.min_feature_level as u32) {
+        if (device_supported_feature_level.0 as u32) < (reqs.min_feature_level as u32) {
             error!(
-                "OpenXR runtime requires D3D12 feature level >= {}",
-                reqs.min_feature_level
+                "OpenXR runtime requires D3D12 feature level >= {:#x}",
+                reqs.min_feature_level as u32
             );
             return Err(OxrError::FailedGraphicsRequirements);
         }
 
         let wgpu_adapter = unsafe { wgpu_instance.create_adapter_from_hal(wgpu_exposed_adapter) };
-        let raw_device = wgpu_open_device.device.raw_device().as_mut_ptr();
-        let raw_queue = wgpu_open_device.device.raw_queue().as_mut_ptr();
+        // the device and queue stay alive as long as the wgpu device
+        let raw_device = wgpu_open_device.device.raw_device().as_raw();
+        let raw_queue = wgpu_open_device.device.raw_queue().as_raw();
         let (wgpu_device, wgpu_queue) = unsafe {
             wgpu_adapter.create_device_from_hal(
                 wgpu_open_device,
-                &wgpu::DeviceDescriptor {
-                    label: Some("bevy_oxr device"),
-                    required_features: wgpu_features,
-                    required_limits: wgpu_limits,
-                },
+                &wgpu_device_descriptor,
                 None,
             )?
         };
@@ -178,7 +204,7 @@ unsafe impl GraphicsExt for openxr::D3D12 {
             ty: sys::SessionCreateInfo::TYPE,
             next: &binding as *const _ as *const _,
             create_flags: Default::default(),
-            system_id: system_id,
+            system_id,
         };
         let mut out = sys::Session::NULL;
         cvt((instance.fp().create_session)(
@@ -204,36 +230,35 @@ fn cvt(x: sys::Result) -> openxr::Result<sys::Result> {
 
 // Extracted from https://github.com/gfx-rs/wgpu/blob/1161a22f4fbb4fc204eb06f2ac4243f83e0e980d/wgpu-hal/src/dx12/adapter.rs#L73-L94
 // license: MIT OR Apache-2.0
-fn get_device_feature_level(
-    device: &d3d12::ComPtr<winapi_d3d12::ID3D12Device>,
-) -> d3d12::FeatureLevel {
+fn get_device_feature_level(device: &ID3D12Device) -> D3D_FEATURE_LEVEL {
     // Detect the highest supported feature level.
     let d3d_feature_level = [
-        d3d12::FeatureLevel::L12_1,
-        d3d12::FeatureLevel::L12_0,
-        d3d12::FeatureLevel::L11_1,
-        d3d12::FeatureLevel::L11_0,
+        D3D_FEATURE_LEVEL_12_1,
+        D3D_FEATURE_LEVEL_12_0,
+        D3D_FEATURE_LEVEL_11_1,
+        D3D_FEATURE_LEVEL_11_0,
     ];
-    type FeatureLevelsInfo = winapi_d3d12::D3D12_FEATURE_DATA_FEATURE_LEVELS;
-    let mut device_levels: FeatureLevelsInfo = unsafe { std::mem::zeroed() };
-    device_levels.NumFeatureLevels = d3d_feature_level.len() as u32;
-    device_levels.pFeatureLevelsRequested = d3d_feature_level.as_ptr().cast();
-    unsafe {
+    let mut device_levels = D3D12_FEATURE_DATA_FEATURE_LEVELS {
+        NumFeatureLevels: d3d_feature_level.len() as u32,
+        pFeatureLevelsRequested: d3d_feature_level.as_ptr(),
+        MaxSupportedFeatureLevel: Default::default(),
+    };
+    let result = unsafe {
         device.CheckFeatureSupport(
-            winapi_d3d12::D3D12_FEATURE_FEATURE_LEVELS,
-            (&mut device_levels as *mut FeatureLevelsInfo).cast(),
-            std::mem::size_of::<FeatureLevelsInfo>() as _,
+            D3D12_FEATURE_FEATURE_LEVELS,
+            <*mut _>::cast(&mut device_levels),
+            size_of_val(&device_levels) as u32,
         )
     };
-    // This cast should never fail because we only requested feature levels that are already in the enum.
-    let max_feature_level = d3d12::FeatureLevel::try_from(device_levels.MaxSupportedFeatureLevel)
-        .expect("Unexpected feature level");
-    max_feature_level
+    if let Err(err) = result {
+        error!("Failed to query the D3D12 feature level: {err}");
+        return D3D_FEATURE_LEVEL_11_0;
+    }
+    device_levels.MaxSupportedFeatureLevel
 }
 
 fn d3d12_to_wgpu(format: DXGI_FORMAT) -> Option<wgpu::TextureFormat> {
     use wgpu::TextureFormat as Tf;
-    use winapi::shared::dxgiformat::*;
 
     Some(match format {
         DXGI_FORMAT_R8_UNORM => Tf::R8Unorm,
@@ -267,7 +292,7 @@ fn d3d12_to_wgpu(format: DXGI_FORMAT) -> Option<wgpu::TextureFormat> {
         DXGI_FORMAT_R9G9B9E5_SHAREDEXP => Tf::Rgb9e5Ufloat,
         DXGI_FORMAT_R10G10B10A2_UINT => Tf::Rgb10a2Uint,
         DXGI_FORMAT_R10G10B10A2_UNORM => Tf::Rgb10a2Unorm,
-        DXGI_FORMAT_R11G11B10_FLOAT => Tf::Rg11b10Float,
+        DXGI_FORMAT_R11G11B10_FLOAT => Tf::Rg11b10Ufloat,
         DXGI_FORMAT_R32G32_UINT => Tf::Rg32Uint,
         DXGI_FORMAT_R32G32_SINT => Tf::Rg32Sint,
         DXGI_FORMAT_R32G32_FLOAT => Tf::Rg32Float,
@@ -307,7 +332,6 @@ fn wgpu_to_d3d12(format: wgpu::TextureFormat) -> Option<DXGI_FORMAT> {
     // https://github.com/gfx-rs/wgpu/blob/v0.19/wgpu-hal/src/auxil/dxgi/conv.rs#L12-L94
     // license: MIT OR Apache-2.0
     use wgpu::TextureFormat as Tf;
-    use winapi::shared::dxgiformat::*;
 
     Some(match format {
         Tf::R8Unorm => DXGI_FORMAT_R8_UNORM,
@@ -341,7 +365,7 @@ fn wgpu_to_d3d12(format: wgpu::TextureFormat) -> Option<DXGI_FORMAT> {
         Tf::Rgb9e5Ufloat => DXGI_FORMAT_R9G9B9E5_SHAREDEXP,
         Tf::Rgb10a2Uint => DXGI_FORMAT_R10G10B10A2_UINT,
         Tf::Rgb10a2Unorm => DXGI_FORMAT_R10G10B10A2_UNORM,
-        Tf::Rg11b10Float => DXGI_FORMAT_R11G11B10_FLOAT,
+        Tf::Rg11b10Ufloat => DXGI_FORMAT_R11G11B10_FLOAT,
         Tf::Rg32Uint => DXGI_FORMAT_R32G32_UINT,
         Tf::Rg32Sint => DXGI_FORMAT_R32G32_SINT,
         Tf::Rg32Float => DXGI_FORMAT_R32G32_FLOAT,
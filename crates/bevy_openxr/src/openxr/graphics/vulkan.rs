@@ -10,7 +10,7 @@ use wgpu_hal::Api;
 use super::{GraphicsExt, GraphicsType, GraphicsWrap};
 use crate::error::OxrError;
 use crate::session::OxrSessionCreateNextChain;
-use crate::types::{AppInfo, OxrExtensions, Result, WgpuGraphics};
+use crate::types::{AppInfo, OxrDeviceDescriptorFn, OxrExtensions, Result, WgpuGraphics};
 
 #[cfg(not(target_os = "android"))]
 const VK_TARGET_VERSION: Version = Version::new(1, 2, 0);
@@ -48,8 +48,30 @@ unsafe impl GraphicsExt for openxr::Vulkan {
         device: &wgpu::Device,
         format: wgpu::TextureFormat,
         resolution: UVec2,
+        array_size: u32,
+        usage: wgpu::TextureUsages,
     ) -> Result<wgpu::Texture> {
         let color_image = ash::vk::Image::from_raw(color_image);
+        let mut hal_usage = wgpu_hal::TextureUses::empty();
+        if usage.contains(wgpu::TextureUsages::RENDER_ATTACHMENT) {
+            hal_usage |= if format.is_depth_stencil_format() {
+                wgpu_hal::TextureUses::DEPTH_STENCIL_WRITE
+            } else {
+                wgpu_hal::TextureUses::COLOR_TARGET
+            };
+        }
+        if usage.contains(wgpu::TextureUsages::TEXTURE_BINDING) {
+            hal_usage |= wgpu_hal::TextureUses::RESOURCE;
+        }
+        if usage.contains(wgpu::TextureUsages::STORAGE_BINDING) {
+            hal_usage |= wgpu_hal::TextureUses::STORAGE_READ_WRITE;
+        }
+        if usage.contains(wgpu::TextureUsages::COPY_SRC) {
+            hal_usage |= wgpu_hal::TextureUses::COPY_SRC;
+        }
+        if usage.contains(wgpu::TextureUsages::COPY_DST) {
+            hal_usage |= wgpu_hal::TextureUses::COPY_DST;
+        }
         let wgpu_hal_texture = unsafe {
             <wgpu_hal::vulkan::Api as wgpu_hal::Api>::Device::texture_from_raw(
                 color_image,
@@ -58,13 +80,13 @@ unsafe impl GraphicsExt for openxr::Vulkan {
                     size: wgpu::Extent3d {
                         width: resolution.x,
                         height: resolution.y,
-                        depth_or_array_layers: 2,
+                        depth_or_array_layers: array_size,
                     },
                     mip_level_count: 1,
                     sample_count: 1,
                     dimension: wgpu::TextureDimension::D2,
                     format,
-                    usage: wgpu_hal::TextureUses::COLOR_TARGET | wgpu_hal::TextureUses::COPY_DST,
+                    usage: hal_usage,
                     memory_flags: wgpu_hal::MemoryFlags::empty(),
                     view_formats: vec![],
                 },
@@ -79,13 +101,13 @@ unsafe impl GraphicsExt for openxr::Vulkan {
                     size: wgpu::Extent3d {
                         width: resolution.x,
                         height: resolution.y,
-                        depth_or_array_layers: 2,
+                        depth_or_array_layers: array_size,
                     },
                     mip_level_count: 1,
                     sample_count: 1,
                     dimension: wgpu::TextureDimension::D2,
                     format,
-                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_DST,
+                    usage,
                     view_formats: &[],
                 },
             )
@@ -97,6 +119,7 @@ unsafe impl GraphicsExt for openxr::Vulkan {
         app_info: &AppInfo,
         instance: &openxr::Instance,
         system_id: openxr::SystemId,
+        device_descriptor: Option<&OxrDeviceDescriptorFn>,
     ) -> Result<(WgpuGraphics, Self::SessionCreateInfo)> {
         let reqs = instance.graphics_requirements::<openxr::Vulkan>(system_id)?;
         if VK_TARGET_VERSION < reqs.min_api_version_supported
@@ -229,7 +252,19 @@ unsafe impl GraphicsExt for openxr::Vulkan {
             error!("WGPU failed to provide an adapter");
             return Err(OxrError::FailedGraphicsRequirements);
         };
-        let wgpu_features = wgpu_exposed_adapter.features;
+        let mut wgpu_device_descriptor = wgpu::DeviceDescriptor {
+            label: None,
+            required_features: wgpu_exposed_adapter.features,
+            required_limits: wgpu_exposed_adapter.capabilities.limits.clone(),
+            memory_hints: wgpu::MemoryHints::Performance,
+        };
+        if let Some(device_descriptor) = device_descriptor {
+            device_descriptor(&mut wgpu_device_descriptor);
+        }
+        // features the adapter doesn't expose can't be enabled on the vulkan device
+        let wgpu_features =
+            wgpu_device_descriptor.required_features & wgpu_exposed_adapter.features;
+        wgpu_device_descriptor.required_features = wgpu_features;
         debug!("wgpu features: {wgpu_features:#?}");
 
         let enabled_extensions = wgpu_exposed_adapter
@@ -281,7 +316,7 @@ unsafe impl GraphicsExt for openxr::Vulkan {
                     None,
                     &enabled_extensions,
                     wgpu_features,
-                    &wgpu::MemoryHints::Performance,
+                    &wgpu_device_descriptor.memory_hints,
                     family_info.queue_family_index,
                     0,
                 )
@@ -297,19 +332,9 @@ unsafe impl GraphicsExt for openxr::Vulkan {
         let wgpu_instance =
             unsafe { wgpu::Instance::from_hal::<wgpu_hal::api::Vulkan>(wgpu_vk_instance) };
         let wgpu_adapter = unsafe { wgpu_instance.create_adapter_from_hal(wgpu_exposed_adapter) };
-        let limits = wgpu_adapter.limits();
-        debug!("wgpu_limits: {limits:#?}");
+        debug!("wgpu_limits: {:#?}", wgpu_device_descriptor.required_limits);
         let (wgpu_device, wgpu_queue) = unsafe {
-            wgpu_adapter.create_device_from_hal(
-                wgpu_open_device,
-                &wgpu::DeviceDescriptor {
-                    label: None,
-                    required_features: wgpu_features,
-                    required_limits: limits,
-                    memory_hints: wgpu::MemoryHints::Performance,
-                },
-                None,
-            )
+            wgpu_adapter.create_device_from_hal(wgpu_open_device, &wgpu_device_descriptor, None)
         }?;
 
         Ok((
@@ -0,0 +1,216 @@
+//! Plays [`XrHapticPattern`]s on the controllers.
+//!
+//! Add [`OxrHapticsPlugin`] to your app and send an [`XrPlayHapticEvent`] to play a pattern.
+//! The steps of a pattern are advanced by the [`OxrFrameTiming::delta`] of every frame,
+//! so a step starts on the first frame after the previous one ended.
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_mod_xr::hands::HandSide;
+use bevy_mod_xr::haptics::{XrHapticPattern, XrPlayHapticEvent};
+use bevy_mod_xr::session::{XrPreDestroySession, XrSessionCreated};
+
+use crate::action_binding::{OxrSendActionBindings, OxrSuggestActionBinding};
+use crate::action_set_attaching::OxrAttachActionSet;
+use crate::action_set_syncing::{OxrActionSetSyncSet, OxrSyncActionSet};
+use crate::controller_poses::INTERACTION_PROFILES;
+use crate::resources::{OxrFrameTiming, OxrInstance};
+use crate::session::OxrSession;
+use crate::{openxr_session_available, openxr_session_running};
+
+/// Plays the patterns sent with [`XrPlayHapticEvent`] through a haptic action in its own action set.
+pub struct OxrHapticsPlugin;
+
+impl Plugin for OxrHapticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<XrPlayHapticEvent>()
+            .init_resource::<OxrHapticPlayback>()
+            .add_systems(
+                Startup,
+                create_haptic_action.run_if(openxr_session_available),
+            )
+            .add_systems(OxrSendActionBindings, suggest_bindings)
+            .add_systems(XrSessionCreated, attach_set)
+            .add_systems(XrPreDestroySession, clear_playback)
+            .add_systems(
+                PreUpdate,
+                sync_set
+                    .before(OxrActionSetSyncSet)
+                    .run_if(openxr_session_running),
+            )
+            .add_systems(
+                PostUpdate,
+                play_haptic_patterns.run_if(openxr_session_running),
+            );
+    }
+}
+
+/// The haptic action of both controllers used by [`OxrHapticsPlugin`].
+#[derive(Resource)]
+pub struct OxrHapticAction {
+    pub set: openxr::ActionSet,
+    pub action: openxr::Action<openxr::Haptic>,
+    pub left: openxr::Path,
+    pub right: openxr::Path,
+}
+
+impl OxrHapticAction {
+    /// Creates a `haptic` action with the `/user/hand/left` and `/user/hand/right` subaction paths
+    /// in the given action set.
+    pub fn new(instance: &OxrInstance, set: &openxr::ActionSet) -> openxr::Result<Self> {
+        let left = instance.string_to_path("/user/hand/left")?;
+        let right = instance.string_to_path("/user/hand/right")?;
+        Ok(Self {
+            set: set.clone(),
+            action: set.create_action("haptic", "Haptic Feedback", &[left, right])?,
+            left,
+            right,
+        })
+    }
+
+    /// Returns the subaction path of `hand`.
+    pub fn path(&self, hand: HandSide) -> openxr::Path {
+        match hand {
+            HandSide::Left => self.left,
+            HandSide::Right => self.right,
+        }
+    }
+}
+
+/// The pattern currently playing on each hand.
+#[derive(Resource, Default)]
+struct OxrHapticPlayback([Option<ActivePattern>; 2]);
+
+struct ActivePattern {
+    pattern: XrHapticPattern,
+    step: usize,
+    /// Time since the current step started.
+    elapsed: Duration,
+    /// Whether the current step was sent to the runtime.
+    applied: bool,
+}
+
+fn create_haptic_action(instance: Res<OxrInstance>, mut cmds: Commands) {
+    let action = instance
+        .create_action_set("haptics", "Haptics", 0)
+        .and_then(|set| OxrHapticAction::new(&instance, &set));
+    match action {
+        Ok(action) => cmds.insert_resource(action),
+        Err(err) => error!("Failed to create haptic action: {err}"),
+    }
+}
+
+fn suggest_bindings(
+    action: Option<Res<OxrHapticAction>>,
+    mut bindings: EventWriter<OxrSuggestActionBinding>,
+) {
+    let Some(action) = action else {
+        return;
+    };
+    for profile in INTERACTION_PROFILES {
+        bindings.send(OxrSuggestActionBinding {
+            action: action.action.as_raw(),
+            interaction_profile: (*profile).into(),
+            bindings: vec![
+                "/user/hand/left/output/haptic".into(),
+                "/user/hand/right/output/haptic".into(),
+            ],
+        });
+    }
+}
+
+fn attach_set(action: Option<Res<OxrHapticAction>>, mut attach: EventWriter<OxrAttachActionSet>) {
+    if let Some(action) = action {
+        attach.send(OxrAttachActionSet(action.set.clone()));
+    }
+}
+
+fn sync_set(action: Option<Res<OxrHapticAction>>, mut sync: EventWriter<OxrSyncActionSet>) {
+    if let Some(action) = action {
+        sync.send(OxrSyncActionSet(action.set.clone()));
+    }
+}
+
+fn clear_playback(mut playback: ResMut<OxrHapticPlayback>) {
+    playback.0 = default();
+}
+
+fn play_haptic_patterns(
+    mut events: EventReader<XrPlayHapticEvent>,
+    action: Option<Res<OxrHapticAction>>,
+    session: Res<OxrSession>,
+    timing: Option<Res<OxrFrameTiming>>,
+    mut playback: ResMut<OxrHapticPlayback>,
+) {
+    let Some(action) = action else {
+        events.clear();
+        return;
+    };
+    let delta = timing.map(|timing| timing.delta).unwrap_or_default();
+    for active in playback.0.iter_mut().flatten() {
+        active.elapsed += delta;
+    }
+
+    for event in events.read() {
+        let slot = &mut playback.0[event.hand as usize];
+        if event.pattern.steps.is_empty() {
+            if let Err(err) = session.stop_haptic_feedback(&action.action, action.path(event.hand))
+            {
+                warn!("Failed to stop haptic feedback: {err}");
+            }
+            *slot = None;
+            continue;
+        }
+        *slot = Some(ActivePattern {
+            pattern: event.pattern.clone(),
+            step: 0,
+            elapsed: Duration::ZERO,
+            applied: false,
+        });
+    }
+
+    for (hand, slot) in [HandSide::Left, HandSide::Right]
+        .into_iter()
+        .zip(playback.0.iter_mut())
+    {
+        let Some(active) = slot else {
+            continue;
+        };
+        // skip every step that ended since the last frame
+        let step = loop {
+            let Some(step) = active.pattern.steps.get(active.step) else {
+                break None;
+            };
+            if active.elapsed < step.duration {
+                break Some(*step);
+            }
+            active.elapsed -= step.duration;
+            active.step += 1;
+            active.applied = false;
+        };
+        let Some(step) = step else {
+            *slot = None;
+            continue;
+        };
+        if active.applied {
+            continue;
+        }
+        active.applied = true;
+
+        let path = action.path(hand);
+        let result = if step.amplitude <= 0.0 {
+            session.stop_haptic_feedback(&action.action, path)
+        } else {
+            session.apply_haptic_feedback(
+                &action.action,
+                path,
+                step.amplitude.min(1.0),
+                step.duration - active.elapsed,
+                step.frequency.unwrap_or(openxr::FREQUENCY_UNSPECIFIED),
+            )
+        };
+        if let Err(err) = result {
+            warn!("Failed to apply haptic feedback: {err}");
+        }
+    }
+}
@@ -1,11 +1,101 @@
+//! Conversions between OpenXR and Bevy math types.
+//!
+//! [`OxrPose`] converts with [`From`]/[`Into`] to and from [`openxr::Posef`], [`Transform`], [`Isometry3d`]
+//! and `(Vec3, Quat)` tuples of a translation and rotation.
+//! Functions creating spaces, like [`create_reference_space`](crate::session::OxrSession::create_reference_space),
+//! take `impl Into<OxrPose>`, so any of these can be passed directly.
+//! Note that converting a [`Transform`] into a pose discards its scale, the translation and rotation round-trip unchanged.
+//!
+//! The `To*` traits below convert between the foreign types directly, e.g. [`openxr::Posef`] to [`Transform`].
 use bevy::{math::Vec3A, prelude::*};
 
+/// A position and orientation, convertible from and into every pose type used with OpenXR.
+///
+/// Unlike the deprecated `bevy_mod_xr::types::XrPose` alias, this is its own type, so it can implement [`From`]
+/// for [`openxr::Posef`] as well as the bevy types. It also implements [`ToPosef`], [`ToTransform`] and [`ToIsometry3d`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OxrPose {
+    pub translation: Vec3,
+    pub rotation: Quat,
+}
+
+impl OxrPose {
+    pub const IDENTITY: Self = Self {
+        translation: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+    };
+
+    pub fn new(translation: Vec3, rotation: Quat) -> Self {
+        Self {
+            translation,
+            rotation,
+        }
+    }
+}
+
+impl Default for OxrPose {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl From<openxr::Posef> for OxrPose {
+    fn from(pose: openxr::Posef) -> Self {
+        pose.to_transform().into()
+    }
+}
+
+impl From<OxrPose> for openxr::Posef {
+    fn from(pose: OxrPose) -> Self {
+        pose.to_posef()
+    }
+}
+
+impl From<Transform> for OxrPose {
+    fn from(transform: Transform) -> Self {
+        Self::new(transform.translation, transform.rotation)
+    }
+}
+
+impl From<OxrPose> for Transform {
+    fn from(pose: OxrPose) -> Self {
+        pose.to_transform()
+    }
+}
+
+impl From<Isometry3d> for OxrPose {
+    fn from(isometry: Isometry3d) -> Self {
+        Self::new(isometry.translation.into(), isometry.rotation)
+    }
+}
+
+impl From<OxrPose> for Isometry3d {
+    fn from(pose: OxrPose) -> Self {
+        pose.to_xr_pose()
+    }
+}
+
+impl From<(Vec3, Quat)> for OxrPose {
+    fn from((translation, rotation): (Vec3, Quat)) -> Self {
+        Self::new(translation, rotation)
+    }
+}
+
+impl From<OxrPose> for (Vec3, Quat) {
+    fn from(pose: OxrPose) -> Self {
+        (pose.translation, pose.rotation)
+    }
+}
+
+/// Converts a pose into an [`openxr::Posef`].
 pub trait ToPosef {
     fn to_posef(&self) -> openxr::Posef;
 }
+/// Converts a pose into a [`Transform`] with a scale of [`Vec3::ONE`].
 pub trait ToTransform {
     fn to_transform(&self) -> Transform;
 }
+/// Converts a pose into an [`Isometry3d`].
 pub trait ToIsometry3d {
     fn to_xr_pose(&self) -> Isometry3d;
 }
@@ -27,6 +117,20 @@ pub trait ToVector2f {
 pub trait ToVec2 {
     fn to_vec2(&self) -> Vec2;
 }
+/// Converts a duration into an [`openxr::Duration`], for functions that accept both OpenXR and [`std`] durations.
+pub trait ToDuration {
+    fn to_duration(&self) -> openxr::Duration;
+}
+/// Converts an [`openxr::Time`] into a [`std::time::Duration`] since the epoch of the runtime's clock.
+///
+/// Negative times are clamped to zero.
+pub trait ToStdDuration {
+    fn to_std_duration(&self) -> std::time::Duration;
+}
+/// Converts a [`std::time::Duration`] since the epoch of the runtime's clock into an [`openxr::Time`].
+pub trait ToTime {
+    fn to_time(&self) -> openxr::Time;
+}
 impl ToPosef for Transform {
     fn to_posef(&self) -> openxr::Posef {
         openxr::Posef {
@@ -57,6 +161,57 @@ impl ToPosef for Isometry3d {
         }
     }
 }
+impl ToPosef for OxrPose {
+    fn to_posef(&self) -> openxr::Posef {
+        openxr::Posef {
+            orientation: self.rotation.to_quaternionf(),
+            position: self.translation.to_vector3f(),
+        }
+    }
+}
+impl ToTransform for OxrPose {
+    fn to_transform(&self) -> Transform {
+        Transform::from_translation(self.translation).with_rotation(self.rotation)
+    }
+}
+impl ToIsometry3d for OxrPose {
+    fn to_xr_pose(&self) -> Isometry3d {
+        Isometry3d::new(self.translation, self.rotation)
+    }
+}
+impl ToPosef for openxr::Posef {
+    fn to_posef(&self) -> openxr::Posef {
+        *self
+    }
+}
+impl ToPosef for (Vec3, Quat) {
+    fn to_posef(&self) -> openxr::Posef {
+        openxr::Posef {
+            orientation: self.1.to_quaternionf(),
+            position: self.0.to_vector3f(),
+        }
+    }
+}
+impl ToTransform for (Vec3, Quat) {
+    fn to_transform(&self) -> Transform {
+        Transform::from_translation(self.0).with_rotation(self.1)
+    }
+}
+impl ToIsometry3d for (Vec3, Quat) {
+    fn to_xr_pose(&self) -> Isometry3d {
+        Isometry3d::new(self.0, self.1)
+    }
+}
+impl ToTransform for Isometry3d {
+    fn to_transform(&self) -> Transform {
+        Transform::from_isometry(*self)
+    }
+}
+impl ToIsometry3d for Transform {
+    fn to_xr_pose(&self) -> Isometry3d {
+        self.to_isometry()
+    }
+}
 
 impl ToQuaternionf for Quat {
     fn to_quaternionf(&self) -> openxr::Quaternionf {
@@ -123,3 +278,118 @@ impl ToVec2 for openxr::Vector2f {
         }
     }
 }
+impl ToDuration for openxr::Duration {
+    fn to_duration(&self) -> openxr::Duration {
+        *self
+    }
+}
+impl ToDuration for std::time::Duration {
+    fn to_duration(&self) -> openxr::Duration {
+        openxr::Duration::from_nanos(self.as_nanos().min(i64::MAX as u128) as i64)
+    }
+}
+impl ToStdDuration for openxr::Time {
+    fn to_std_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_nanos(self.as_nanos().max(0) as u64)
+    }
+}
+impl ToStdDuration for openxr::Duration {
+    fn to_std_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_nanos(self.as_nanos().max(0) as u64)
+    }
+}
+impl ToTime for std::time::Duration {
+    fn to_time(&self) -> openxr::Time {
+        openxr::Time::from_nanos(self.as_nanos().min(i64::MAX as u128) as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_pose_eq(a: OxrPose, b: OxrPose) {
+        assert!(
+            a.translation.abs_diff_eq(b.translation, 1e-6),
+            "{a:?} != {b:?}"
+        );
+        // q and -q are the same rotation
+        assert!(
+            a.rotation.abs_diff_eq(b.rotation, 1e-6) || a.rotation.abs_diff_eq(-b.rotation, 1e-6),
+            "{a:?} != {b:?}"
+        );
+    }
+
+    fn test_pose() -> OxrPose {
+        OxrPose::new(
+            Vec3::new(1.5, -0.25, 3.0),
+            Quat::from_euler(EulerRot::YXZ, 0.7, -0.3, 1.2),
+        )
+    }
+
+    #[test]
+    fn posef_round_trip() {
+        let pose = test_pose();
+        let posef: openxr::Posef = pose.into();
+        assert_pose_eq(OxrPose::from(posef), pose);
+    }
+
+    #[test]
+    fn transform_round_trip() {
+        let pose = test_pose();
+        let transform: Transform = pose.into();
+        assert_eq!(transform.scale, Vec3::ONE);
+        assert_pose_eq(OxrPose::from(transform), pose);
+    }
+
+    #[test]
+    fn transform_scale_is_discarded() {
+        let transform = Transform::from_translation(Vec3::X)
+            .with_rotation(Quat::from_rotation_y(1.0))
+            .with_scale(Vec3::splat(2.0));
+        let posef: openxr::Posef = OxrPose::from(transform).into();
+        let back: Transform = OxrPose::from(posef).into();
+        assert_pose_eq(back.into(), transform.into());
+        assert_eq!(back.scale, Vec3::ONE);
+    }
+
+    #[test]
+    fn isometry_and_tuple_round_trip() {
+        let pose = test_pose();
+        let isometry: Isometry3d = pose.into();
+        assert_pose_eq(OxrPose::from(isometry), pose);
+        let tuple: (Vec3, Quat) = pose.into();
+        assert_pose_eq(OxrPose::from(tuple), pose);
+    }
+
+    #[test]
+    fn from_matches_to_posef() {
+        let pose = test_pose();
+        let from: openxr::Posef = pose.into();
+        let to = Transform::from(pose).to_posef();
+        assert_pose_eq(from.into(), to.into());
+    }
+
+    #[test]
+    fn to_transform_matches_posef() {
+        let pose = test_pose();
+        let transform = pose.to_transform();
+        assert_eq!(transform.scale, Vec3::ONE);
+        assert_pose_eq(transform.into(), pose.to_posef().to_transform().into());
+        assert_pose_eq(pose.to_xr_pose().into(), pose);
+    }
+
+    #[test]
+    fn zero_orientation_becomes_identity() {
+        let posef = openxr::Posef {
+            orientation: openxr::Quaternionf {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+                w: 0.0,
+            },
+            position: openxr::Vector3f::default(),
+        };
+        assert_eq!(OxrPose::from(posef), OxrPose::IDENTITY);
+    }
+}
@@ -1,7 +1,9 @@
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
+use bevy::core_pipeline::core_3d::CORE_3D_DEPTH_FORMAT;
 use bevy::prelude::*;
+use bevy::render::camera::ManualTextureViews;
 use bevy::render::extract_resource::ExtractResourcePlugin;
 use bevy::render::renderer::RenderAdapter;
 use bevy::render::renderer::RenderAdapterInfo;
@@ -16,15 +18,20 @@ use bevy::render::RenderApp;
 use bevy::render::RenderPlugin;
 use bevy::winit::UpdateMode;
 use bevy::winit::WinitSettings;
+use bevy_mod_xr::camera::XrCamera;
 use bevy_mod_xr::session::*;
 use openxr::Event;
 
-use crate::error::OxrError;
+use crate::error::{OxrError, OxrInitError};
+use crate::features::overlay::OxrOverlaySettings;
+use crate::features::secondary_view::OxrSecondaryView;
 use crate::graphics::*;
+use crate::render::add_texture_view;
 use crate::resources::*;
 use crate::session::OxrSession;
 use crate::session::OxrSessionCreateNextChain;
 use crate::types::*;
+use crate::OxrPreUpdateSet;
 
 use super::exts::OxrEnabledExtensions;
 use super::poll_events::OxrEvent;
@@ -42,26 +49,63 @@ pub fn should_run_frame_loop(
         && state.is_some_and(|state| *state != XrState::Stopping)
 }
 
+/// A [`Condition`](bevy::ecs::schedule::Condition) system that says if the runtime wants the current frame to be rendered.
+/// The swapchain images are only acquired when this is true, otherwise the frame is ended without layers.
+/// Also false while the session is hidden, see [`OxrSkipRenderingWhenHidden`](crate::resources::OxrSkipRenderingWhenHidden).
 pub fn should_render(frame_state: Option<Res<OxrFrameState>>) -> bool {
     frame_state.is_some_and(|frame_state| frame_state.should_render)
 }
 
+/// Enumerates the extensions supported by the OpenXR runtime, without creating an instance.
+///
+/// This can be used before adding [`OxrInitPlugin`] to decide which extensions to enable.
+pub fn available_extensions() -> Result<OxrExtensions> {
+    OxrEntry::load()?.enumerate_extensions()
+}
+
 pub struct OxrInitPlugin {
     /// Information about the app this is being used to build.
     pub app_info: AppInfo,
-    /// Extensions wanted for this session.
+    /// Extensions wanted for this session. Extensions the runtime doesn't support are skipped with a warning,
+    /// check [`OxrEnabledExtensions`] for the ones that were enabled.
     // TODO!() This should be changed to take a simpler list of features wanted that this crate supports. i.e. hand tracking
     pub exts: OxrExtensions,
-    /// List of blend modes the openxr session can use. If [None], pick the first available blend mode.
+    /// Extensions the app can't run without. Unlike [`exts`](Self::exts), initialization fails
+    /// if any of these aren't supported by the runtime.
+    pub required_exts: OxrExtensions,
+    /// Blend modes the openxr session can use, in order of preference. The first one the runtime supports is used,
+    /// and session creation fails if there is none. If [None], pick the first available blend mode.
+    ///
+    /// The blend mode can be changed at runtime through [`OxrBlendMode`].
     pub blend_modes: Option<Vec<EnvironmentBlendMode>>,
-    /// List of backends the openxr session can use. If [None], pick the first available backend.
+    /// List of view configurations the openxr session can use, e.g. [`PRIMARY_MONO`](openxr::ViewConfigurationType::PRIMARY_MONO)
+    /// for handheld devices. If [None], pick the first available view configuration.
+    pub view_configuration_types: Option<Vec<openxr::ViewConfigurationType>>,
+    /// List of backends the openxr session can use. If [None], pick the first available backend,
+    /// preferring the one selected with the `WGPU_BACKEND` environment variable, e.g. `WGPU_BACKEND=dx12`.
     pub backends: Option<Vec<GraphicsBackend>>,
-    /// List of formats the openxr session can use. If [None], pick the first available format
+    /// Swapchain formats the openxr session can use, in order of preference. The first one the runtime supports is used,
+    /// and session creation fails with [`OxrInitError::NoSupportedFormat`] if there is none. If [None], pick the runtime's preferred format.
     pub formats: Option<Vec<wgpu::TextureFormat>>,
     /// List of resolutions that the openxr swapchain can use. If [None] pick the first available resolution.
     pub resolutions: Option<Vec<UVec2>>,
+    /// Multisampling of the spawned [`XrCamera`]s. Falls back to the highest sample count the runtime supports
+    /// with a warning if it supports fewer samples, see [`OxrGraphicsInfo::msaa`].
+    ///
+    /// Submitting depth needs [`OxrMsaa::Off`], see [`OxrDepthSwapchain`].
+    pub msaa: OxrMsaa,
+    /// Usages of the swapchain textures the [`XrCamera`]s render to, e.g. add
+    /// [`TEXTURE_BINDING`](wgpu::TextureUsages::TEXTURE_BINDING) to sample the eye textures in a shader.
+    /// The matching [`swapchain_usage_flags`] are requested from the runtime.
+    pub swapchain_usage: wgpu::TextureUsages,
+    /// Called with the [`wgpu::DeviceDescriptor`] before the render device is created, to change its features and limits.
+    /// It starts out with everything the adapter supports, features the adapter doesn't support can't be enabled.
+    pub device_descriptor: Option<OxrDeviceDescriptorFn>,
     /// Passed into the render plugin when added to the app.
     pub synchronous_pipeline_compilation: bool,
+    /// Creates an overlay session rendering on top of other OpenXR apps with these settings.
+    /// Makes [`XR_EXTX_overlay`](https://registry.khronos.org/OpenXR/specs/1.0/html/xrspec.html#XR_EXTX_overlay) required.
+    pub overlay: Option<OxrOverlaySettings>,
 }
 impl Default for OxrInitPlugin {
     fn default() -> Self {
@@ -73,18 +117,35 @@ impl Default for OxrInitPlugin {
                 exts.enable_hand_tracking();
                 exts
             },
+            required_exts: default(),
             blend_modes: default(),
+            view_configuration_types: default(),
             backends: default(),
             formats: Some(vec![wgpu::TextureFormat::Rgba8UnormSrgb]),
             resolutions: default(),
+            msaa: default(),
+            swapchain_usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            device_descriptor: None,
             synchronous_pipeline_compilation: false,
+            overlay: None,
         }
     }
 }
 
 impl Plugin for OxrInitPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<OxrInteractionProfileChanged>();
+        app.add_event::<OxrInteractionProfileChanged>()
+            .configure_sets(
+                PreUpdate,
+                (
+                    OxrPreUpdateSet::SyncActions,
+                    OxrPreUpdateSet::UpdateCriticalComponents,
+                    OxrPreUpdateSet::UpdateNonCriticalComponents,
+                )
+                    .chain(),
+            );
         match self.init_xr() {
             Ok((
                 instance,
@@ -92,7 +153,15 @@ impl Plugin for OxrInitPlugin {
                 WgpuGraphics(device, queue, adapter_info, adapter, wgpu_instance),
                 session_create_info,
                 enabled_exts,
+                system_info,
             )) => {
+                let layer_support = OxrLayerSupport::new(&enabled_exts);
+                let graphics_backend = OxrGraphicsBackend {
+                    api: OxrGraphicsApi::from_wgpu(adapter_info.backend)
+                        .expect("wgpu adapters created from OpenXR use an OpenXR graphics API"),
+                    backend: instance.backend(),
+                    adapter_info: adapter_info.clone(),
+                };
                 app.insert_resource(enabled_exts)
                     .add_plugins((
                         RenderPlugin {
@@ -134,11 +203,18 @@ impl Plugin for OxrInitPlugin {
                                 .run_if(session_created)
                                 .run_if(on_event::<XrRequestExitEvent>),
                             detect_session_destroyed,
+                            recreate_swapchain
+                                .run_if(session_created)
+                                .run_if(on_event::<XrStateChanged>),
                         )
                             .in_set(XrHandleEvents::SessionStateUpdateEvents),
                     )
                     .insert_resource(instance.clone())
                     .insert_resource(system_id)
+                    .insert_resource(system_info.system.clone())
+                    .insert_resource(system_info.clone())
+                    .insert_resource(layer_support.clone())
+                    .insert_resource(graphics_backend.clone())
                     .insert_resource(XrState::Available)
                     .insert_resource(WinitSettings {
                         focused_mode: UpdateMode::Continuous,
@@ -147,6 +223,9 @@ impl Plugin for OxrInitPlugin {
                     .insert_resource(OxrSessionStarted(false))
                     .insert_non_send_resource(session_create_info)
                     .init_non_send_resource::<OxrSessionCreateNextChain>();
+                if let Some(overlay) = self.overlay {
+                    app.insert_resource(overlay);
+                }
 
                 app.world_mut()
                     .resource_mut::<Events<XrStateChanged>>()
@@ -155,14 +234,26 @@ impl Plugin for OxrInitPlugin {
                 let render_app = app.sub_app_mut(RenderApp);
 
                 render_app
-                    .add_systems(ExtractSchedule, transfer_xr_resources)
+                    .add_systems(
+                        ExtractSchedule,
+                        (transfer_xr_resources, transfer_recreated_swapchain),
+                    )
                     .insert_resource(instance)
                     .insert_resource(system_id)
+                    .insert_resource(system_info.system.clone())
+                    .insert_resource(system_info)
+                    .insert_resource(layer_support)
+                    .insert_resource(graphics_backend)
                     .insert_resource(XrState::Available)
                     .insert_resource(OxrSessionStarted(false));
             }
             Err(e) => {
-                error!("Failed to initialize openxr: {e}");
+                match e {
+                    OxrInitError::FormFactorUnavailable => {
+                        warn!("{e}, continuing without XR. Connect a headset and restart the app to use XR.")
+                    }
+                    e => error!("Failed to initialize openxr: {e}"),
+                }
                 app.add_plugins(RenderPlugin::default())
                     .insert_resource(XrState::Unavailable);
             }
@@ -191,6 +282,7 @@ impl Plugin for OxrInitPlugin {
 fn detect_session_destroyed(
     mut last_state: Local<bool>,
     state: Res<XrDestroySessionRender>,
+    xr_state: Res<XrState>,
     mut sender: EventWriter<XrSessionDestroyedEvent>,
     mut cmds: Commands,
 ) {
@@ -198,7 +290,10 @@ fn detect_session_destroyed(
     if *last_state && !state {
         debug!("XrSession was fully destroyed!");
         sender.send_default();
-        cmds.insert_resource(XrState::Available);
+        // a session destroyed because the instance was lost can't be recreated
+        if *xr_state != XrState::Lost {
+            cmds.insert_resource(XrState::Available);
+        }
     }
     *last_state = state;
 }
@@ -206,26 +301,34 @@ fn detect_session_destroyed(
 impl OxrInitPlugin {
     fn init_xr(
         &self,
-    ) -> Result<(
-        OxrInstance,
-        OxrSystemId,
-        WgpuGraphics,
-        SessionConfigInfo,
-        OxrEnabledExtensions,
-    )> {
-        #[cfg(windows)]
-        let entry = OxrEntry(openxr::Entry::linked());
-        #[cfg(not(windows))]
-        let entry = OxrEntry(unsafe { openxr::Entry::load()? });
-
-        #[cfg(target_os = "android")]
-        entry.initialize_android_loader()?;
+    ) -> std::result::Result<
+        (
+            OxrInstance,
+            OxrSystemId,
+            WgpuGraphics,
+            SessionConfigInfo,
+            OxrEnabledExtensions,
+            OxrSystemInfo,
+        ),
+        OxrInitError,
+    > {
+        let entry = OxrEntry::load()?;
 
         let available_exts = entry.enumerate_extensions()?;
 
+        let mut required_exts = self.required_exts.clone();
+        if self.overlay.is_some() {
+            required_exts.enable_extx_overlay();
+        }
+
+        let missing_required_exts = available_exts.unavailable_exts(&required_exts);
+        if !missing_required_exts.is_empty() {
+            return Err(OxrError::from(missing_required_exts).into());
+        }
+
         // check available extensions and send a warning for any wanted extensions that aren't available.
         for ext in available_exts.unavailable_exts(&self.exts) {
-            error!(
+            warn!(
                 "Extension \"{ext}\" not available in the current OpenXR runtime. Disabling extension."
             );
         }
@@ -243,11 +346,17 @@ impl OxrInitPlugin {
             }
             backend
         } else {
-            available_backends.first().copied()
+            // prefer the backend picked with the WGPU_BACKEND environment variable, like bevy does without XR
+            let wanted = wgpu::util::backend_bits_from_env().unwrap_or(wgpu::Backends::all());
+            available_backends
+                .iter()
+                .copied()
+                .find(|backend| wanted.contains(backend.wgpu_backend().into()))
+                .or_else(|| available_backends.first().copied())
         }
         .ok_or(OxrError::NoAvailableBackend)?;
 
-        let exts = self.exts.clone() & available_exts;
+        let exts = self.exts.intersect(&available_exts) | required_exts;
 
         let instance = entry.create_instance(
             self.app_info.clone(),
@@ -256,31 +365,46 @@ impl OxrInitPlugin {
             &[],
             backend,
         )?;
-        let instance_props = instance.properties()?;
+        let (runtime_name, runtime_version) = instance.runtime_properties()?;
 
-        info!(
-            "Loaded OpenXR runtime: {} {}",
-            instance_props.runtime_name, instance_props.runtime_version
-        );
+        info!("Loaded OpenXR runtime: {runtime_name} {runtime_version}");
 
-        let system_id = instance.system(openxr::FormFactor::HEAD_MOUNTED_DISPLAY)?;
+        let system_id = instance
+            .system(openxr::FormFactor::HEAD_MOUNTED_DISPLAY)
+            .map_err(|e| match e {
+                openxr::sys::Result::ERROR_FORM_FACTOR_UNAVAILABLE => {
+                    OxrInitError::FormFactorUnavailable
+                }
+                e => OxrError::from(e).into(),
+            })?;
         let system_props = instance.system_properties(system_id)?;
 
         info!(
             "Using system: {}",
-            if system_props.system_name.is_empty() {
+            if system_props.system_name().is_empty() {
                 "<unnamed>"
             } else {
-                &system_props.system_name
+                system_props.system_name()
             }
         );
+        if let Some(headset_id) = system_props.headset_id {
+            debug!("Headset id: {headset_id:02x?}");
+        }
+        debug!(
+            "System supports a maximum of {} composition layers",
+            system_props.max_layer_count()
+        );
 
-        let (graphics, graphics_info) = instance.init_graphics(system_id)?;
+        let (graphics, graphics_info) =
+            instance.init_graphics(system_id, self.device_descriptor.as_ref())?;
 
         let session_create_info = SessionConfigInfo {
             blend_modes: self.blend_modes.clone(),
+            view_configuration_types: self.view_configuration_types.clone(),
             formats: self.formats.clone(),
             resolutions: self.resolutions.clone(),
+            msaa: self.msaa,
+            swapchain_usage: self.swapchain_usage | wgpu::TextureUsages::RENDER_ATTACHMENT,
             graphics_info,
         };
 
@@ -290,6 +414,11 @@ impl OxrInitPlugin {
             graphics,
             session_create_info,
             OxrEnabledExtensions(exts),
+            OxrSystemInfo {
+                runtime_name,
+                runtime_version,
+                system: system_props,
+            },
         ))
     }
 }
@@ -299,6 +428,7 @@ pub struct OxrInteractionProfileChanged;
 pub fn handle_events(
     event: In<OxrEvent>,
     mut status: ResMut<XrState>,
+    mut visibility: ResMut<XrSessionVisibility>,
     mut changed_event: EventWriter<XrStateChanged>,
     mut interaction_profile_changed_event: EventWriter<OxrInteractionProfileChanged>,
 ) {
@@ -325,7 +455,15 @@ pub fn handle_events(
                 SessionState::LOSS_PENDING => XrState::Exiting {
                     should_restart: true,
                 },
-                _ => unreachable!(),
+                state => {
+                    warn!("unknown XR session state {:?}", state);
+                    return;
+                }
+            };
+            *visibility = match state {
+                SessionState::VISIBLE => XrSessionVisibility::Visible,
+                SessionState::FOCUSED => XrSessionVisibility::Focused,
+                _ => XrSessionVisibility::Hidden,
             };
             changed_event.send(XrStateChanged(new_status));
             *status = new_status;
@@ -345,35 +483,139 @@ fn init_xr_session(
     instance: &OxrInstance,
     system_id: openxr::SystemId,
     chain: &mut OxrSessionCreateNextChain,
+    depth: bool,
     SessionConfigInfo {
         blend_modes,
+        view_configuration_types,
         formats,
         resolutions,
+        msaa,
+        swapchain_usage,
         graphics_info,
     }: SessionConfigInfo,
-) -> Result<(
-    OxrSession,
-    OxrFrameWaiter,
-    OxrFrameStream,
-    OxrSwapchain,
-    OxrSwapchainImages,
-    OxrGraphicsInfo,
-)> {
+) -> std::result::Result<
+    (
+        OxrSession,
+        OxrFrameWaiter,
+        OxrFrameStream,
+        OxrSwapchain,
+        OxrSwapchainImages,
+        Option<OxrDepthSwapchain>,
+        OxrGraphicsInfo,
+    ),
+    OxrInitError,
+> {
     let (session, frame_waiter, frame_stream) =
         unsafe { instance.create_session(system_id, graphics_info, chain)? };
 
-    // TODO!() support other view configurations
     let available_view_configurations = instance.enumerate_view_configurations(system_id)?;
-    if !available_view_configurations.contains(&openxr::ViewConfigurationType::PRIMARY_STEREO) {
-        return Err(OxrError::NoAvailableViewConfiguration);
-    }
 
-    let view_configuration_type = openxr::ViewConfigurationType::PRIMARY_STEREO;
+    // view configuration selection
+    let view_configuration_type =
+        if let Some(wanted_view_configurations) = &view_configuration_types {
+            let mut view_configuration = None;
+            for wanted_view_configuration in wanted_view_configurations {
+                if available_view_configurations.contains(wanted_view_configuration) {
+                    view_configuration = Some(*wanted_view_configuration);
+                    break;
+                }
+            }
+            view_configuration
+        } else {
+            available_view_configurations.first().copied()
+        }
+        .ok_or(OxrError::NoAvailableViewConfiguration)?;
 
     let view_configuration_views =
         instance.enumerate_view_configuration_views(system_id, view_configuration_type)?;
+    let view_count = view_configuration_views.len() as u32;
+
+    let resolution = select_resolution(&view_configuration_views, resolutions.as_deref())?;
+
+    let max_samples = view_configuration_views
+        .iter()
+        .map(|view| view.max_swapchain_sample_count)
+        .min()
+        .unwrap_or(1);
+    let supported_msaa = msaa.clamp_to(max_samples);
+    if supported_msaa != msaa {
+        warn!("{msaa:?} is not supported by the runtime, which allows up to {max_samples} samples. Falling back to {supported_msaa:?}");
+    }
+    let msaa = supported_msaa;
+
+    // only contains the formats that convert to a wgpu format and back, so any of them can be imported into wgpu
+    let available_formats = session.enumerate_swapchain_formats()?;
 
-    let (resolution, _view) = if let Some(resolutions) = &resolutions {
+    // format selection, the runtime lists its formats in order of preference
+    let format = match &formats {
+        Some(formats) => formats
+            .iter()
+            .find(|format| available_formats.contains(format))
+            .copied(),
+        None => available_formats.first().copied(),
+    }
+    .ok_or_else(|| OxrInitError::NoSupportedFormat {
+        requested: formats.clone().unwrap_or_default(),
+        available: available_formats.clone(),
+    })?;
+    info!("Using swapchain format: {format:?}");
+
+    let (swapchain, images) = create_swapchain(
+        &session,
+        device,
+        format,
+        resolution,
+        view_count,
+        swapchain_usage,
+    )?;
+
+    let depth_swapchain = if depth {
+        create_depth_swapchain(&session, device, resolution, view_count, &available_formats)?
+    } else {
+        None
+    };
+
+    let available_blend_modes =
+        instance.enumerate_environment_blend_modes(system_id, view_configuration_type)?;
+
+    // blend mode selection
+    let blend_mode = if let Some(wanted_blend_modes) = &blend_modes {
+        wanted_blend_modes
+            .iter()
+            .find(|wanted| available_blend_modes.contains(wanted))
+            .copied()
+    } else {
+        available_blend_modes.first().copied()
+    }
+    .ok_or(OxrError::NoAvailableBlendMode)?;
+
+    let graphics_info = OxrGraphicsInfo {
+        blend_mode,
+        resolution,
+        format,
+        view_configuration_type,
+        view_count,
+        msaa,
+        swapchain_usage,
+    };
+
+    Ok((
+        session,
+        frame_waiter,
+        frame_stream,
+        swapchain,
+        images,
+        depth_swapchain,
+        graphics_info,
+    ))
+}
+
+/// Picks the swapchain resolution from the `resolutions` wanted by the app and the ones recommended by the runtime.
+fn select_resolution(
+    view_configuration_views: &[openxr::ViewConfigurationView],
+    resolutions: Option<&[UVec2]>,
+) -> Result<UVec2> {
+    let (resolution, _view) = if let Some(resolutions) = resolutions {
         let mut preferred = None;
         for resolution in resolutions {
             for view_config in view_configuration_views.iter() {
@@ -411,68 +653,72 @@ fn init_xr_session(
     }
     .ok_or(OxrError::NoAvailableViewConfiguration)?;
 
-    let available_formats = session.enumerate_swapchain_formats()?;
-
-    let format = if let Some(formats) = &formats {
-        let mut format = None;
-        for wanted_format in formats {
-            if available_formats.contains(wanted_format) {
-                format = Some(*wanted_format);
-            }
-        }
-        format
-    } else {
-        available_formats.first().copied()
-    }
-    .ok_or(OxrError::NoAvailableFormat)?;
+    Ok(resolution)
+}
 
+fn create_swapchain(
+    session: &OxrSession,
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    resolution: UVec2,
+    view_count: u32,
+    usage: wgpu::TextureUsages,
+) -> Result<(OxrSwapchain, OxrSwapchainImages)> {
     let swapchain = session.create_swapchain(SwapchainCreateInfo {
         create_flags: SwapchainCreateFlags::EMPTY,
-        usage_flags: SwapchainUsageFlags::COLOR_ATTACHMENT | SwapchainUsageFlags::SAMPLED,
+        usage_flags: swapchain_usage_flags(usage, format),
         format,
-        // TODO() add support for multisampling
+        // the cameras render multisampled and resolve into the swapchain, see `OxrInitPlugin::msaa`
         sample_count: 1,
         width: resolution.x,
         height: resolution.y,
         face_count: 1,
-        array_size: 2,
+        array_size: view_count,
         mip_count: 1,
     })?;
 
-    let images = swapchain.enumerate_images(device, format, resolution)?;
+    let images = swapchain.enumerate_images(device, format, resolution, view_count, usage)?;
+    if images.0.is_empty() {
+        return Err(OxrError::NoSwapchainImages);
+    }
 
-    let available_blend_modes =
-        instance.enumerate_environment_blend_modes(system_id, view_configuration_type)?;
+    Ok((swapchain, images))
+}
 
-    // blend mode selection
-    let blend_mode = if let Some(wanted_blend_modes) = &blend_modes {
-        let mut blend_mode = None;
-        for wanted_blend_mode in wanted_blend_modes {
-            if available_blend_modes.contains(wanted_blend_mode) {
-                blend_mode = Some(*wanted_blend_mode);
-                break;
-            }
-        }
-        blend_mode
-    } else {
-        available_blend_modes.first().copied()
+fn create_depth_swapchain(
+    session: &OxrSession,
+    device: &wgpu::Device,
+    resolution: UVec2,
+    view_count: u32,
+    available_formats: &[wgpu::TextureFormat],
+) -> Result<Option<OxrDepthSwapchain>> {
+    // the depth is copied from the cameras, which only works if the formats match
+    let format = CORE_3D_DEPTH_FORMAT;
+    if !available_formats.contains(&format) {
+        warn!("The runtime doesn't support {format:?} swapchains, not submitting depth");
+        return Ok(None);
     }
-    .ok_or(OxrError::NoAvailableBackend)?;
-
-    let graphics_info = OxrGraphicsInfo {
-        blend_mode,
-        resolution,
+    let usage = wgpu::TextureUsages::RENDER_ATTACHMENT
+        | wgpu::TextureUsages::COPY_SRC
+        | wgpu::TextureUsages::COPY_DST;
+    let swapchain = session.create_swapchain(SwapchainCreateInfo {
+        create_flags: SwapchainCreateFlags::EMPTY,
+        usage_flags: swapchain_usage_flags(usage, format),
         format,
-    };
-
-    Ok((
-        session,
-        frame_waiter,
-        frame_stream,
+        sample_count: 1,
+        width: resolution.x,
+        height: resolution.y,
+        face_count: 1,
+        array_size: view_count,
+        mip_count: 1,
+    })?;
+    let images = swapchain.enumerate_images(device, format, resolution, view_count, usage)?;
+    Ok(Some(OxrDepthSwapchain {
         swapchain,
         images,
-        graphics_info,
-    ))
+        format,
+        depth_ranges: Vec::new(),
+    }))
 }
 
 pub fn create_xr_session(world: &mut World) {
@@ -483,23 +729,37 @@ pub fn create_xr_session(world: &mut World) {
     let instance = world.resource::<OxrInstance>();
     let create_info = world.non_send_resource::<SessionConfigInfo>();
     let system_id = world.resource::<OxrSystemId>();
+    let depth = world
+        .get_resource::<OxrEnabledExtensions>()
+        .is_some_and(|exts| exts.khr_composition_layer_depth);
     match init_xr_session(
         device.wgpu_device(),
         instance,
         **system_id,
         &mut chain,
+        depth,
         create_info.clone(),
     ) {
-        Ok((session, frame_waiter, frame_stream, swapchain, images, graphics_info)) => {
+        Ok((
+            session,
+            frame_waiter,
+            frame_stream,
+            swapchain,
+            images,
+            depth_swapchain,
+            graphics_info,
+        )) => {
             world.insert_resource(session.clone());
             world.insert_resource(frame_waiter);
             world.insert_resource(images);
             world.insert_resource(graphics_info);
+            world.insert_resource(OxrBlendMode(graphics_info.blend_mode));
             world.insert_resource(OxrRenderResources {
                 session,
                 frame_stream,
                 swapchain,
                 images,
+                depth_swapchain,
                 graphics_info,
                 session_destroy_flag: world
                     .get_resource::<XrDestroySessionRender>()
@@ -507,13 +767,110 @@ pub fn create_xr_session(world: &mut World) {
                     .clone(),
             });
         }
-        Err(e) => error!("Failed to initialize XrSession: {e}"),
+        Err(e) => {
+            error!("Failed to initialize XrSession: {e}");
+            world.insert_non_send_resource(chain);
+            return;
+        }
     }
     world.insert_non_send_resource(chain);
     world.run_schedule(XrSessionCreated);
     world.send_event(XrSessionCreatedEvent);
 }
 
+/// Recreates the swapchain if the resolution picked from the runtime's recommended view configuration
+/// no longer matches the one the current swapchain was created with.
+pub fn recreate_swapchain(world: &mut World) {
+    let Some(graphics_info) = world.get_resource::<OxrGraphicsInfo>().copied() else {
+        return;
+    };
+    let session = world.resource::<OxrSession>();
+    let device = world.resource::<RenderDevice>().wgpu_device();
+    let instance = world.resource::<OxrInstance>();
+    let system_id = world.resource::<OxrSystemId>();
+    let create_info = world.non_send_resource::<SessionConfigInfo>();
+    let depth = world
+        .get_resource::<OxrEnabledExtensions>()
+        .is_some_and(|exts| exts.khr_composition_layer_depth);
+
+    let result = instance
+        .enumerate_view_configuration_views(**system_id, graphics_info.view_configuration_type)
+        .map_err(OxrError::from)
+        .and_then(|views| select_resolution(&views, create_info.resolutions.as_deref()))
+        .and_then(|resolution| {
+            if resolution == graphics_info.resolution {
+                return Ok(None);
+            }
+            info!(
+                "Recreating swapchain, resolution changed from {} to {}",
+                graphics_info.resolution, resolution
+            );
+            let (swapchain, images) = create_swapchain(
+                session,
+                device,
+                graphics_info.format,
+                resolution,
+                graphics_info.view_count,
+                graphics_info.swapchain_usage,
+            )?;
+            let depth_swapchain = if depth {
+                let available_formats = session.enumerate_swapchain_formats()?;
+                create_depth_swapchain(
+                    session,
+                    device,
+                    resolution,
+                    graphics_info.view_count,
+                    &available_formats,
+                )?
+            } else {
+                None
+            };
+            Ok(Some(OxrRecreatedSwapchain {
+                swapchain,
+                images,
+                depth_swapchain,
+                graphics_info: OxrGraphicsInfo {
+                    resolution,
+                    ..graphics_info
+                },
+            }))
+        });
+
+    let recreated = match result {
+        Ok(Some(recreated)) => recreated,
+        Ok(None) => return,
+        Err(e) => {
+            error!("Failed to recreate swapchain: {e}");
+            return;
+        }
+    };
+
+    let Some(temp_tex) = recreated.images.0.first() else {
+        error!(
+            "Failed to recreate swapchain: {}",
+            OxrError::NoSwapchainImages
+        );
+        return;
+    };
+    let indices: Vec<u32> = world
+        .query::<&XrCamera>()
+        .iter(world)
+        .map(|camera| camera.0)
+        .collect();
+    let mut manual_texture_views = world.resource_mut::<ManualTextureViews>();
+    for index in indices {
+        add_texture_view(
+            &mut manual_texture_views,
+            temp_tex,
+            &recreated.graphics_info,
+            index,
+        );
+    }
+    world.insert_resource(recreated.images);
+    world.insert_resource(recreated.graphics_info);
+    world.insert_resource(recreated);
+}
+
 pub fn destroy_xr_session(world: &mut World) {
     world.run_schedule(XrPreDestroySession);
     world.remove_resource::<OxrSession>();
@@ -521,7 +878,10 @@ pub fn destroy_xr_session(world: &mut World) {
     world.remove_resource::<OxrFrameStream>();
     world.remove_resource::<OxrSwapchain>();
     world.remove_resource::<OxrSwapchainImages>();
+    world.remove_resource::<OxrDepthSwapchain>();
     world.remove_resource::<OxrGraphicsInfo>();
+    world.remove_resource::<OxrBlendMode>();
+    world.remove_resource::<OxrFrameTiming>();
     world.insert_resource(XrState::Available);
 }
 
@@ -530,11 +890,16 @@ pub fn begin_xr_session(
     // session: Res<OxrSession>, mut session_started: ResMut<OxrSessionStarted>
 ) {
     let _span = debug_span!("xr_begin_session").entered();
-    world
-        .get_resource::<OxrSession>()
-        .unwrap()
-        .begin(openxr::ViewConfigurationType::PRIMARY_STEREO)
-        .expect("Failed to begin session");
+    let view_configuration_type = world.resource::<OxrGraphicsInfo>().view_configuration_type;
+    let session = world.resource::<OxrSession>();
+    match world.get_resource::<OxrSecondaryView>() {
+        Some(secondary_view) => session.begin_with_secondary_view(
+            view_configuration_type,
+            secondary_view.view_configuration_type,
+        ),
+        None => session.begin(view_configuration_type),
+    }
+    .expect("Failed to begin session");
     drop(_span);
     world.get_resource_mut::<OxrSessionStarted>().unwrap().0 = true;
     world.run_schedule(XrPostSessionBegin);
@@ -547,16 +912,37 @@ pub fn end_xr_session(
     // Maybe this could be an event?
     world.run_schedule(XrPreSessionEnd);
     let _span = debug_span!("xr_end_session").entered();
-    world
-        .get_resource::<OxrSession>()
-        .unwrap()
-        .end()
-        .expect("Failed to end session");
+    if let Err(err) = world.get_resource::<OxrSession>().unwrap().end() {
+        error!("Failed to end session: {err}");
+    }
     world.get_resource_mut::<OxrSessionStarted>().unwrap().0 = false;
 }
 
-pub fn request_exit_xr_session(session: Res<OxrSession>) {
-    session.request_exit().expect("Failed to request exit");
+/// Asks the runtime to exit the session. The runtime then moves it to [`XrState::Stopping`],
+/// where the frame loop stops and the session is ended, and to [`XrState::Exiting`], where it is destroyed.
+///
+/// A session that isn't running can't be exited through the runtime, so it is moved to [`XrState::Exiting`] right away.
+pub fn request_exit_xr_session(
+    session: Res<OxrSession>,
+    started: Res<OxrSessionStarted>,
+    mut state: ResMut<XrState>,
+    mut changed_event: EventWriter<XrStateChanged>,
+) {
+    if started.0 {
+        match session.request_exit() {
+            Ok(()) => return,
+            Err(openxr::sys::Result::ERROR_SESSION_NOT_RUNNING) => {}
+            Err(err) => {
+                error!("Failed to request exit: {err}");
+                return;
+            }
+        }
+    }
+    let new_state = XrState::Exiting {
+        should_restart: false,
+    };
+    changed_event.send(XrStateChanged(new_state));
+    *state = new_state;
 }
 
 /// This is used solely to transport resources from the main world to the render world.
@@ -566,10 +952,65 @@ struct OxrRenderResources {
     frame_stream: OxrFrameStream,
     swapchain: OxrSwapchain,
     images: OxrSwapchainImages,
+    depth_swapchain: Option<OxrDepthSwapchain>,
     graphics_info: OxrGraphicsInfo,
     session_destroy_flag: XrDestroySessionRender,
 }
 
+/// This is used to transport a swapchain recreated by [`recreate_swapchain`] to the render world.
+#[derive(Resource)]
+struct OxrRecreatedSwapchain {
+    swapchain: OxrSwapchain,
+    images: OxrSwapchainImages,
+    depth_swapchain: Option<OxrDepthSwapchain>,
+    graphics_info: OxrGraphicsInfo,
+}
+
+/// This system replaces the swapchain in the render world after it was recreated in the main world.
+///
+/// It runs during extraction, which is after [`OxrRenderStep::EndFrame`](crate::render::OxrRenderStep::EndFrame)
+/// of the previous frame and before [`OxrRenderStep::AcquireImage`](crate::render::OxrRenderStep::AcquireImage) of the next,
+/// so the old swapchain only still has an image acquired if a frame was skipped midway.
+/// That image is waited on and released before the old swapchain is dropped.
+pub fn transfer_recreated_swapchain(
+    mut commands: Commands,
+    mut world: ResMut<MainWorld>,
+    old_swapchain: Option<ResMut<OxrSwapchain>>,
+    waiter: Option<Res<OxrImageWaiter>>,
+) {
+    let Some(OxrRecreatedSwapchain {
+        swapchain,
+        images,
+        depth_swapchain,
+        graphics_info,
+    }) = world.remove_resource()
+    else {
+        return;
+    };
+
+    if let Some(mut old_swapchain) = old_swapchain {
+        if let Some(waiter) = waiter
+            .as_ref()
+            .filter(|_| old_swapchain.image_state() == OxrSwapchainImageState::Waiting)
+        {
+            if let Err(e) = old_swapchain.finish_wait_image(waiter) {
+                warn!("Failed to finish waiting for the image of the old swapchain: {e}");
+            }
+        }
+        if let Err(e) = old_swapchain.release_pending_image() {
+            warn!("Failed to release the image of the old swapchain: {e}");
+        }
+    }
+    commands.remove_resource::<OxrAcquiredSwapchainImage>();
+    commands.insert_resource(swapchain);
+    commands.insert_resource(images);
+    match depth_swapchain {
+        Some(depth_swapchain) => commands.insert_resource(depth_swapchain),
+        None => commands.remove_resource::<OxrDepthSwapchain>(),
+    }
+    commands.insert_resource(graphics_info);
+}
+
 /// This system transfers important render resources from the main world to the render world when a session is created.
 pub fn transfer_xr_resources(mut commands: Commands, mut world: ResMut<MainWorld>) {
     let Some(OxrRenderResources {
@@ -577,6 +1018,7 @@ pub fn transfer_xr_resources(mut commands: Commands, mut world: ResMut<MainWorld
         frame_stream,
         swapchain,
         images,
+        depth_swapchain,
         graphics_info,
         session_destroy_flag,
     }) = world.remove_resource()
@@ -588,6 +1030,9 @@ pub fn transfer_xr_resources(mut commands: Commands, mut world: ResMut<MainWorld
     commands.insert_resource(frame_stream);
     commands.insert_resource(swapchain);
     commands.insert_resource(images);
+    if let Some(depth_swapchain) = depth_swapchain {
+        commands.insert_resource(depth_swapchain);
+    }
     commands.insert_resource(graphics_info);
     commands.insert_resource(session_destroy_flag);
 }
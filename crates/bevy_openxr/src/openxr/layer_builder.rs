@@ -1,25 +1,141 @@
-use std::mem;
+use std::{mem, ptr};
 
 use bevy::ecs::world::World;
 use bevy_mod_xr::spaces::{XrPrimaryReferenceSpace, XrSpace};
-use openxr::{sys, CompositionLayerFlags, Fovf, Posef, Rect2Di};
+use openxr::{
+    sys, CompositionLayerFlags, EnvironmentBlendMode, Extent2Df, EyeVisibility, Fovf, Posef,
+    Rect2Di,
+};
 
+use crate::exts::OxrExtensions;
+use crate::features::space_warp::OxrSpaceWarpSwapchains;
 use crate::graphics::graphics_match;
 use crate::resources::*;
 use crate::spaces::OxrSpaceExt as _;
 
 pub trait LayerProvider {
     fn get<'a>(&'a self, world: &'a World) -> Option<Box<dyn CompositionLayer + '_>>;
+
+    /// The type of composition layer this provides. Layers of a type that isn't supported by the runtime
+    /// are skipped when submitting, see [`OxrLayerSupport`].
+    ///
+    /// Returns [`None`] by default, which means the layer is always submitted.
+    fn layer_type(&self) -> Option<OxrLayerType> {
+        None
+    }
+
+    /// Returns all layers of this provider. Providers that submit a variable number of layers,
+    /// e.g. one per entity, should override this instead of [`get`](Self::get).
+    ///
+    /// Defaults to the layer returned by [`get`](Self::get).
+    fn layers<'a>(&'a self, world: &'a World) -> Vec<Box<dyn CompositionLayer<'a> + 'a>> {
+        self.get(world).into_iter().collect()
+    }
+}
+
+/// The types of composition layers, and the extensions they depend on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OxrLayerType {
+    Projection,
+    Quad,
+    Cylinder,
+    Cube,
+    Equirect,
+    Equirect2,
+    Passthrough,
+}
+
+impl OxrLayerType {
+    pub const ALL: [OxrLayerType; 7] = [
+        OxrLayerType::Projection,
+        OxrLayerType::Quad,
+        OxrLayerType::Cylinder,
+        OxrLayerType::Cube,
+        OxrLayerType::Equirect,
+        OxrLayerType::Equirect2,
+        OxrLayerType::Passthrough,
+    ];
+
+    /// The name of the extension required for this layer type, [`None`] for layer types in the core specification.
+    pub fn required_extension(self) -> Option<&'static str> {
+        match self {
+            OxrLayerType::Projection | OxrLayerType::Quad => None,
+            OxrLayerType::Cylinder => Some("XR_KHR_composition_layer_cylinder"),
+            OxrLayerType::Cube => Some("XR_KHR_composition_layer_cube"),
+            OxrLayerType::Equirect => Some("XR_KHR_composition_layer_equirect"),
+            OxrLayerType::Equirect2 => Some("XR_KHR_composition_layer_equirect2"),
+            OxrLayerType::Passthrough => Some("XR_FB_passthrough"),
+        }
+    }
+
+    /// Returns true if the extension required for this layer type is in `exts`.
+    pub fn is_enabled(self, exts: &OxrExtensions) -> bool {
+        match self {
+            OxrLayerType::Projection | OxrLayerType::Quad => true,
+            OxrLayerType::Cylinder => exts.khr_composition_layer_cylinder,
+            OxrLayerType::Cube => exts.khr_composition_layer_cube,
+            OxrLayerType::Equirect => exts.khr_composition_layer_equirect,
+            OxrLayerType::Equirect2 => exts.khr_composition_layer_equirect2,
+            OxrLayerType::Passthrough => exts.fb_passthrough,
+        }
+    }
+}
+
+/// Submits the views rendered by the [`XrCamera`](bevy_mod_xr::camera::XrCamera)s.
+///
+/// To use other flags than the default ones, replace it in [`OxrRenderLayers`],
+/// e.g. `OxrRenderLayers(vec![Box::new(ProjectionLayer::with_flags(flags))])`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProjectionLayer {
+    /// The flags of the layer. If [`None`], the layer is blended with its alpha channel
+    /// ([`BLEND_TEXTURE_SOURCE_ALPHA`](CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA)) while the environment
+    /// or a passthrough layer is visible behind it, and opaque otherwise. The alpha is premultiplied
+    /// unless [`UNPREMULTIPLIED_ALPHA`](CompositionLayerFlags::UNPREMULTIPLIED_ALPHA) is set.
+    pub flags: Option<CompositionLayerFlags>,
 }
 
-pub struct ProjectionLayer;
+impl ProjectionLayer {
+    pub const fn with_flags(flags: CompositionLayerFlags) -> Self {
+        Self { flags: Some(flags) }
+    }
+
+    /// Returns the flags the layer is submitted with in `world`.
+    pub fn flags(&self, world: &World) -> CompositionLayerFlags {
+        if let Some(flags) = self.flags {
+            return flags;
+        }
+        let blend_mode = world
+            .get_resource::<OxrBlendMode>()
+            .map(|mode| mode.0)
+            .or(world
+                .get_resource::<OxrGraphicsInfo>()
+                .map(|info| info.blend_mode));
+        let passthrough = world
+            .get_resource::<OxrPassthroughEnabled>()
+            .is_some_and(|enabled| enabled.0);
+        if passthrough || blend_mode.is_some_and(|mode| mode != EnvironmentBlendMode::OPAQUE) {
+            CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA
+        } else {
+            CompositionLayerFlags::EMPTY
+        }
+    }
+}
 
 pub struct PassthroughLayer;
 
 impl LayerProvider for ProjectionLayer {
+    fn layer_type(&self) -> Option<OxrLayerType> {
+        Some(OxrLayerType::Projection)
+    }
+
     fn get<'a>(&self, world: &'a World) -> Option<Box<dyn CompositionLayer<'a> + 'a>> {
         let stage = world.get_resource::<XrPrimaryReferenceSpace>()?;
-        let openxr_views = world.get_resource::<OxrViews>()?;
+        let openxr_views = match world.get_resource::<OxrFreezeFrame>() {
+            Some(freeze) if !freeze.should_render_views() => {
+                &world.get_resource::<OxrRenderedViews>()?.0
+            }
+            _ => &world.get_resource::<OxrViews>()?.0,
+        };
         let swapchain = world.get_resource::<OxrSwapchain>()?;
         let graphics_info = world.get_resource::<OxrGraphicsInfo>()?;
         let rect = openxr::Rect2Di {
@@ -30,40 +146,75 @@ impl LayerProvider for ProjectionLayer {
             },
         };
 
-        if openxr_views.len() < 2 {
+        if openxr_views.len() < graphics_info.view_count as usize {
             return None;
         }
 
+        let depth = world
+            .get_resource::<OxrDepthSwapchain>()
+            .filter(|depth| depth.depth_ranges.len() >= graphics_info.view_count as usize);
+        let space_warp = world
+            .get_resource::<OxrSpaceWarpSwapchains>()
+            .filter(|space_warp| space_warp.should_submit());
+        let view = |index: usize| {
+            let mut view = CompositionLayerProjectionView::new()
+                .pose(openxr_views[index].pose)
+                .fov(openxr_views[index].fov)
+                .sub_image(
+                    SwapchainSubImage::new()
+                        .swapchain(swapchain)
+                        .image_array_index(index as u32)
+                        .image_rect(rect),
+                );
+            if let Some(space_warp) = space_warp {
+                view = view.space_warp_info(space_warp.info(index as u32));
+            }
+            match depth {
+                Some(depth) => {
+                    let (near_z, far_z) = depth.depth_ranges[index];
+                    view.depth_info(
+                        CompositionLayerDepthInfo::new()
+                            .sub_image(
+                                SwapchainSubImage::new()
+                                    .swapchain(&depth.swapchain)
+                                    .image_array_index(index as u32)
+                                    .image_rect(rect),
+                            )
+                            .min_depth(0.)
+                            .max_depth(1.)
+                            .near_z(near_z)
+                            .far_z(far_z),
+                    )
+                }
+                None => view,
+            }
+        };
+
         Some(Box::new(
             CompositionLayerProjection::new()
-                .layer_flags(CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA)
+                .layer_flags(self.flags(world))
                 .space(stage)
-                .views(&[
-                    CompositionLayerProjectionView::new()
-                        .pose(openxr_views.0[0].pose)
-                        .fov(openxr_views.0[0].fov)
-                        .sub_image(
-                            SwapchainSubImage::new()
-                                .swapchain(swapchain)
-                                .image_array_index(0)
-                                .image_rect(rect),
-                        ),
-                    CompositionLayerProjectionView::new()
-                        .pose(openxr_views.0[1].pose)
-                        .fov(openxr_views.0[1].fov)
-                        .sub_image(
-                            SwapchainSubImage::new()
-                                .swapchain(swapchain)
-                                .image_array_index(1)
-                                .image_rect(rect),
-                        ),
-                ]),
+                .views(
+                    &(0..graphics_info.view_count as usize)
+                        .map(view)
+                        .collect::<Vec<_>>(),
+                ),
         ))
     }
 }
 
 impl LayerProvider for PassthroughLayer {
+    fn layer_type(&self) -> Option<OxrLayerType> {
+        Some(OxrLayerType::Passthrough)
+    }
+
     fn get<'a>(&'a self, world: &'a World) -> Option<Box<dyn CompositionLayer + '_>> {
+        if world
+            .get_resource::<OxrPassthroughEnabled>()
+            .is_some_and(|enabled| !enabled.0)
+        {
+            return None;
+        }
         Some(Box::new(
             CompositionLayerPassthrough::new()
                 .layer_handle(world.get_resource::<OxrPassthroughLayer>()?)
@@ -123,10 +274,154 @@ impl<'a> Default for SwapchainSubImage<'a> {
     }
 }
 
+#[derive(Copy, Clone)]
+pub struct CompositionLayerDepthInfo<'a> {
+    inner: sys::CompositionLayerDepthInfoKHR,
+    swapchain: Option<&'a OxrSwapchain>,
+}
+
+impl<'a> CompositionLayerDepthInfo<'a> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            inner: sys::CompositionLayerDepthInfoKHR {
+                ty: sys::StructureType::COMPOSITION_LAYER_DEPTH_INFO_KHR,
+                ..unsafe { mem::zeroed() }
+            },
+            swapchain: None,
+        }
+    }
+    #[inline]
+    pub fn into_raw(self) -> sys::CompositionLayerDepthInfoKHR {
+        self.inner
+    }
+    #[inline]
+    pub fn as_raw(&self) -> &sys::CompositionLayerDepthInfoKHR {
+        &self.inner
+    }
+    #[inline]
+    pub fn sub_image(mut self, value: SwapchainSubImage<'a>) -> Self {
+        self.inner.sub_image = value.inner;
+        self.swapchain = value.swapchain;
+        self
+    }
+    #[inline]
+    pub fn min_depth(mut self, value: f32) -> Self {
+        self.inner.min_depth = value;
+        self
+    }
+    #[inline]
+    pub fn max_depth(mut self, value: f32) -> Self {
+        self.inner.max_depth = value;
+        self
+    }
+    /// Distance in meters of `min_depth`. Can be larger than `far_z` for reversed depth and infinite.
+    #[inline]
+    pub fn near_z(mut self, value: f32) -> Self {
+        self.inner.near_z = value;
+        self
+    }
+    /// Distance in meters of `max_depth`. Can be infinite.
+    #[inline]
+    pub fn far_z(mut self, value: f32) -> Self {
+        self.inner.far_z = value;
+        self
+    }
+}
+
+impl<'a> Default for CompositionLayerDepthInfo<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct CompositionLayerSpaceWarpInfo<'a> {
+    inner: sys::CompositionLayerSpaceWarpInfoFB,
+    motion_vector_swapchain: Option<&'a OxrSwapchain>,
+    depth_swapchain: Option<&'a OxrSwapchain>,
+}
+
+impl<'a> CompositionLayerSpaceWarpInfo<'a> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            inner: sys::CompositionLayerSpaceWarpInfoFB {
+                ty: sys::StructureType::COMPOSITION_LAYER_SPACE_WARP_INFO_FB,
+                app_space_delta_pose: Posef::IDENTITY,
+                ..unsafe { mem::zeroed() }
+            },
+            motion_vector_swapchain: None,
+            depth_swapchain: None,
+        }
+    }
+    #[inline]
+    pub fn into_raw(self) -> sys::CompositionLayerSpaceWarpInfoFB {
+        self.inner
+    }
+    #[inline]
+    pub fn as_raw(&self) -> &sys::CompositionLayerSpaceWarpInfoFB {
+        &self.inner
+    }
+    #[inline]
+    pub fn layer_flags(mut self, value: sys::CompositionLayerSpaceWarpInfoFlagsFB) -> Self {
+        self.inner.layer_flags = value;
+        self
+    }
+    #[inline]
+    pub fn motion_vector_sub_image(mut self, value: SwapchainSubImage<'a>) -> Self {
+        self.inner.motion_vector_sub_image = value.inner;
+        self.motion_vector_swapchain = value.swapchain;
+        self
+    }
+    /// How the space of the layer moved since the last frame, e.g. when the player was moved artificially.
+    #[inline]
+    pub fn app_space_delta_pose(mut self, value: Posef) -> Self {
+        self.inner.app_space_delta_pose = value;
+        self
+    }
+    #[inline]
+    pub fn depth_sub_image(mut self, value: SwapchainSubImage<'a>) -> Self {
+        self.inner.depth_sub_image = value.inner;
+        self.depth_swapchain = value.swapchain;
+        self
+    }
+    #[inline]
+    pub fn min_depth(mut self, value: f32) -> Self {
+        self.inner.min_depth = value;
+        self
+    }
+    #[inline]
+    pub fn max_depth(mut self, value: f32) -> Self {
+        self.inner.max_depth = value;
+        self
+    }
+    /// Distance in meters of `min_depth`. Can be larger than `far_z` for reversed depth and infinite.
+    #[inline]
+    pub fn near_z(mut self, value: f32) -> Self {
+        self.inner.near_z = value;
+        self
+    }
+    /// Distance in meters of `max_depth`. Can be infinite.
+    #[inline]
+    pub fn far_z(mut self, value: f32) -> Self {
+        self.inner.far_z = value;
+        self
+    }
+}
+
+impl<'a> Default for CompositionLayerSpaceWarpInfo<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct CompositionLayerProjectionView<'a> {
     inner: sys::CompositionLayerProjectionView,
     swapchain: Option<&'a OxrSwapchain>,
+    depth_info: Option<CompositionLayerDepthInfo<'a>>,
+    space_warp_info: Option<CompositionLayerSpaceWarpInfo<'a>>,
 }
 
 impl<'a> CompositionLayerProjectionView<'a> {
@@ -138,6 +433,8 @@ impl<'a> CompositionLayerProjectionView<'a> {
                 ..unsafe { mem::zeroed() }
             },
             swapchain: None,
+            depth_info: None,
+            space_warp_info: None,
         }
     }
     #[inline]
@@ -164,6 +461,18 @@ impl<'a> CompositionLayerProjectionView<'a> {
         self.swapchain = value.swapchain;
         self
     }
+    /// Submits depth for this view. Requires `XR_KHR_composition_layer_depth`.
+    #[inline]
+    pub fn depth_info(mut self, value: CompositionLayerDepthInfo<'a>) -> Self {
+        self.depth_info = Some(value);
+        self
+    }
+    /// Submits motion vectors and depth for this view. Requires `XR_FB_space_warp`.
+    #[inline]
+    pub fn space_warp_info(mut self, value: CompositionLayerSpaceWarpInfo<'a>) -> Self {
+        self.space_warp_info = Some(value);
+        self
+    }
 }
 impl<'a> Default for CompositionLayerProjectionView<'a> {
     fn default() -> Self {
@@ -179,6 +488,8 @@ pub struct CompositionLayerProjection<'a> {
     inner: sys::CompositionLayerProjection,
     swapchain: Option<&'a OxrSwapchain>,
     views: Vec<sys::CompositionLayerProjectionView>,
+    depth_infos: Vec<sys::CompositionLayerDepthInfoKHR>,
+    space_warp_infos: Vec<sys::CompositionLayerSpaceWarpInfoFB>,
 }
 impl<'a> CompositionLayerProjection<'a> {
     #[inline]
@@ -190,6 +501,8 @@ impl<'a> CompositionLayerProjection<'a> {
             },
             swapchain: None,
             views: Vec::new(),
+            depth_infos: Vec::new(),
+            space_warp_infos: Vec::new(),
         }
     }
     #[inline]
@@ -212,7 +525,45 @@ impl<'a> CompositionLayerProjection<'a> {
     }
     #[inline]
     pub fn views(mut self, value: &[CompositionLayerProjectionView<'a>]) -> Self {
-        self.views = value.iter().map(|view| view.inner).collect();
+        // the views point to the depth and space warp infos, so these can't be reallocated after this
+        self.space_warp_infos = value
+            .iter()
+            .filter_map(|view| view.space_warp_info.map(|info| info.inner))
+            .collect();
+        let mut space_warp_infos = self.space_warp_infos.iter();
+        let space_warp_nexts: Vec<*const sys::CompositionLayerSpaceWarpInfoFB> = value
+            .iter()
+            .map(|view| match view.space_warp_info {
+                Some(_) => space_warp_infos.next().unwrap() as *const _,
+                None => ptr::null(),
+            })
+            .collect();
+        // the space warp info is chained behind the depth info if a view has both
+        self.depth_infos = value
+            .iter()
+            .zip(&space_warp_nexts)
+            .filter_map(|(view, next)| {
+                view.depth_info.map(|info| {
+                    let mut inner = info.inner;
+                    inner.next = *next as _;
+                    inner
+                })
+            })
+            .collect();
+        let mut depth_infos = self.depth_infos.iter();
+        self.views = value
+            .iter()
+            .zip(&space_warp_nexts)
+            .map(|(view, next)| {
+                let mut inner = view.inner;
+                if view.depth_info.is_some() {
+                    inner.next = depth_infos.next().unwrap() as *const _ as _;
+                } else if !next.is_null() {
+                    inner.next = *next as _;
+                }
+                inner
+            })
+            .collect();
         self.inner.views = self.views.as_slice().as_ptr() as *const _ as _;
         self.inner.view_count = self.views.len() as u32;
         self
@@ -232,6 +583,161 @@ impl<'a> Default for CompositionLayerProjection<'a> {
         Self::new()
     }
 }
+#[derive(Copy, Clone)]
+pub struct CompositionLayerQuad<'a> {
+    inner: sys::CompositionLayerQuad,
+    swapchain: Option<&'a OxrSwapchain>,
+}
+impl<'a> CompositionLayerQuad<'a> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            inner: sys::CompositionLayerQuad {
+                ty: sys::StructureType::COMPOSITION_LAYER_QUAD,
+                ..unsafe { mem::zeroed() }
+            },
+            swapchain: None,
+        }
+    }
+    #[inline]
+    pub fn into_raw(self) -> sys::CompositionLayerQuad {
+        self.inner
+    }
+    #[inline]
+    pub fn as_raw(&self) -> &sys::CompositionLayerQuad {
+        &self.inner
+    }
+    #[inline]
+    pub fn layer_flags(mut self, value: CompositionLayerFlags) -> Self {
+        self.inner.layer_flags = value;
+        self
+    }
+    #[inline]
+    pub fn space(mut self, value: &XrSpace) -> Self {
+        self.inner.space = value.as_raw_openxr_space();
+        self
+    }
+    #[inline]
+    pub fn eye_visibility(mut self, value: EyeVisibility) -> Self {
+        self.inner.eye_visibility = value;
+        self
+    }
+    #[inline]
+    pub fn sub_image(mut self, value: SwapchainSubImage<'a>) -> Self {
+        self.inner.sub_image = value.inner;
+        self.swapchain = value.swapchain;
+        self
+    }
+    #[inline]
+    pub fn pose(mut self, value: Posef) -> Self {
+        self.inner.pose = value;
+        self
+    }
+    /// Size of the quad in meters.
+    #[inline]
+    pub fn size(mut self, value: Extent2Df) -> Self {
+        self.inner.size = value;
+        self
+    }
+}
+unsafe impl<'a> CompositionLayer<'a> for CompositionLayerQuad<'a> {
+    fn swapchain(&self) -> Option<&'a OxrSwapchain> {
+        self.swapchain
+    }
+
+    fn header(&self) -> &sys::CompositionLayerBaseHeader {
+        unsafe { mem::transmute(&self.inner) }
+    }
+}
+impl<'a> Default for CompositionLayerQuad<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[derive(Copy, Clone)]
+pub struct CompositionLayerCylinder<'a> {
+    inner: sys::CompositionLayerCylinderKHR,
+    swapchain: Option<&'a OxrSwapchain>,
+}
+impl<'a> CompositionLayerCylinder<'a> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            inner: sys::CompositionLayerCylinderKHR {
+                ty: sys::CompositionLayerCylinderKHR::TYPE,
+                ..unsafe { mem::zeroed() }
+            },
+            swapchain: None,
+        }
+    }
+    #[inline]
+    pub fn into_raw(self) -> sys::CompositionLayerCylinderKHR {
+        self.inner
+    }
+    #[inline]
+    pub fn as_raw(&self) -> &sys::CompositionLayerCylinderKHR {
+        &self.inner
+    }
+    #[inline]
+    pub fn layer_flags(mut self, value: CompositionLayerFlags) -> Self {
+        self.inner.layer_flags = value;
+        self
+    }
+    #[inline]
+    pub fn space(mut self, value: &XrSpace) -> Self {
+        self.inner.space = value.as_raw_openxr_space();
+        self
+    }
+    #[inline]
+    pub fn eye_visibility(mut self, value: EyeVisibility) -> Self {
+        self.inner.eye_visibility = value;
+        self
+    }
+    #[inline]
+    pub fn sub_image(mut self, value: SwapchainSubImage<'a>) -> Self {
+        self.inner.sub_image = value.inner;
+        self.swapchain = value.swapchain;
+        self
+    }
+    /// Pose of the center of the cylinder.
+    #[inline]
+    pub fn pose(mut self, value: Posef) -> Self {
+        self.inner.pose = value;
+        self
+    }
+    /// Radius of the cylinder in meters.
+    #[inline]
+    pub fn radius(mut self, value: f32) -> Self {
+        self.inner.radius = value;
+        self
+    }
+    /// The angle of the visible section of the cylinder in radians, from 0 to 2π.
+    #[inline]
+    pub fn central_angle(mut self, value: f32) -> Self {
+        self.inner.central_angle = value;
+        self
+    }
+    /// Width of the image divided by its height, which determines the height of the cylinder.
+    #[inline]
+    pub fn aspect_ratio(mut self, value: f32) -> Self {
+        self.inner.aspect_ratio = value;
+        self
+    }
+}
+unsafe impl<'a> CompositionLayer<'a> for CompositionLayerCylinder<'a> {
+    fn swapchain(&self) -> Option<&'a OxrSwapchain> {
+        self.swapchain
+    }
+
+    fn header(&self) -> &sys::CompositionLayerBaseHeader {
+        unsafe { mem::transmute(&self.inner) }
+    }
+}
+impl<'a> Default for CompositionLayerCylinder<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 pub struct CompositionLayerPassthrough {
     inner: sys::CompositionLayerPassthroughFB,
 }
@@ -0,0 +1,160 @@
+//! Swapchains for custom composition layers, cycled by the frame loop together with the projection swapchain.
+//!
+//! Create a swapchain with [`OxrLayerSwapchains::create`] in the render world. Its image is acquired in
+//! [`OxrRenderStep::AcquireImage`](crate::render::OxrRenderStep::AcquireImage), can be written to after
+//! [`OxrRenderStep::WaitImage`](crate::render::OxrRenderStep::WaitImage) through
+//! [`OxrLayerSwapchain::acquired_texture`], and is released in
+//! [`OxrRenderStep::ReleaseImage`](crate::render::OxrRenderStep::ReleaseImage).
+//! Reference it from a layer with [`OxrLayerSwapchain::sub_image`].
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::layer_builder::SwapchainSubImage;
+use crate::resources::{OxrSwapchain, OxrSwapchainImages};
+use crate::session::OxrSession;
+use crate::types::Result;
+
+/// Identifies a swapchain in [`OxrLayerSwapchains`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OxrLayerSwapchainId(u32);
+
+/// A swapchain owned by [`OxrLayerSwapchains`].
+pub struct OxrLayerSwapchain {
+    pub swapchain: OxrSwapchain,
+    pub images: OxrSwapchainImages,
+    pub format: wgpu::TextureFormat,
+    pub resolution: UVec2,
+    acquired: Option<u32>,
+    has_image: bool,
+}
+
+impl OxrLayerSwapchain {
+    /// The image acquired for this frame. Only write to it between
+    /// [`OxrRenderStep::WaitImage`](crate::render::OxrRenderStep::WaitImage) and
+    /// [`OxrRenderStep::ReleaseImage`](crate::render::OxrRenderStep::ReleaseImage).
+    pub fn acquired_texture(&self) -> Option<&'static wgpu::Texture> {
+        self.acquired.map(|index| &self.images.0[index as usize])
+    }
+
+    /// The whole image of the swapchain, for use in a layer.
+    ///
+    /// Returns [`None`] until an image was released, a layer referencing the swapchain before that
+    /// would be rejected by the runtime.
+    pub fn sub_image(&self) -> Option<SwapchainSubImage<'_>> {
+        self.has_image.then(|| {
+            SwapchainSubImage::new()
+                .swapchain(&self.swapchain)
+                .image_array_index(0)
+                .image_rect(openxr::Rect2Di {
+                    offset: openxr::Offset2Di { x: 0, y: 0 },
+                    extent: openxr::Extent2Di {
+                        width: self.resolution.x as _,
+                        height: self.resolution.y as _,
+                    },
+                })
+        })
+    }
+}
+
+/// Swapchains of custom composition layers in the render world.
+///
+/// Cleared when the session is destroyed.
+#[derive(Resource, Default)]
+pub struct OxrLayerSwapchains {
+    next_id: u32,
+    swapchains: HashMap<OxrLayerSwapchainId, OxrLayerSwapchain>,
+}
+
+impl OxrLayerSwapchains {
+    /// Creates a swapchain and adds it to the frame loop.
+    ///
+    /// `usage` has to include the usages needed to write the image, e.g.
+    /// [`COPY_DST`](wgpu::TextureUsages::COPY_DST) to copy into it.
+    pub fn create(
+        &mut self,
+        session: &OxrSession,
+        device: &wgpu::Device,
+        resolution: UVec2,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+    ) -> Result<OxrLayerSwapchainId> {
+        let swapchain = session.create_simple_swapchain(resolution, format, usage)?;
+        let images = swapchain.enumerate_images(device, format, resolution, 1, usage)?;
+        let id = OxrLayerSwapchainId(self.next_id);
+        self.next_id += 1;
+        self.swapchains.insert(
+            id,
+            OxrLayerSwapchain {
+                swapchain,
+                images,
+                format,
+                resolution,
+                acquired: None,
+                has_image: false,
+            },
+        );
+        Ok(id)
+    }
+
+    pub fn get(&self, id: OxrLayerSwapchainId) -> Option<&OxrLayerSwapchain> {
+        self.swapchains.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: OxrLayerSwapchainId) -> Option<&mut OxrLayerSwapchain> {
+        self.swapchains.get_mut(&id)
+    }
+
+    /// Removes and destroys a swapchain. Layers must no longer reference it.
+    pub fn remove(&mut self, id: OxrLayerSwapchainId) -> Option<OxrLayerSwapchain> {
+        self.swapchains.remove(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (OxrLayerSwapchainId, &OxrLayerSwapchain)> {
+        self.swapchains
+            .iter()
+            .map(|(id, swapchain)| (*id, swapchain))
+    }
+}
+
+pub(crate) fn acquire_layer_images(mut swapchains: ResMut<OxrLayerSwapchains>) {
+    for swapchain in swapchains.swapchains.values_mut() {
+        if let Err(e) = swapchain.swapchain.release_pending_image() {
+            warn!("Failed to release pending layer swapchain image: {e}");
+        }
+        swapchain.acquired = match swapchain.swapchain.acquire_image() {
+            Ok(index) => Some(index),
+            Err(e) => {
+                error!("Failed to acquire layer swapchain image: {e}");
+                None
+            }
+        };
+    }
+}
+
+pub(crate) fn wait_layer_images(mut swapchains: ResMut<OxrLayerSwapchains>) {
+    for swapchain in swapchains.swapchains.values_mut() {
+        if swapchain.acquired.is_none() {
+            continue;
+        }
+        if let Err(e) = swapchain.swapchain.wait_image(openxr::Duration::INFINITE) {
+            error!("Failed to wait for layer swapchain image: {e}");
+            swapchain.acquired = None;
+        }
+    }
+}
+
+pub(crate) fn release_layer_images(mut swapchains: ResMut<OxrLayerSwapchains>) {
+    for swapchain in swapchains.swapchains.values_mut() {
+        if swapchain.acquired.take().is_none() {
+            continue;
+        }
+        match swapchain.swapchain.release_image() {
+            Ok(()) => swapchain.has_image = true,
+            Err(e) => error!("Failed to release layer swapchain image: {e}"),
+        }
+    }
+}
+
+pub(crate) fn clear_layer_swapchains(mut swapchains: ResMut<OxrLayerSwapchains>) {
+    swapchains.swapchains.clear();
+}
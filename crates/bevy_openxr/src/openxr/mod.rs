@@ -1,7 +1,7 @@
 // use actions::XrActionPlugin;
 use bevy::{
     app::{PluginGroup, PluginGroupBuilder},
-    prelude::Res,
+    prelude::{Res, SystemSet},
     render::RenderPlugin,
     utils::default,
     window::{PresentMode, Window, WindowPlugin},
@@ -15,20 +15,30 @@ use resources::OxrInstance;
 use session::OxrSession;
 
 use self::{
-    features::{handtracking::HandTrackingPlugin, passthrough::OxrPassthroughPlugin},
+    features::{
+        body_tracking::OxrBodyTrackingPlugin, foveation::OxrFoveationPlugin,
+        handtracking::HandTrackingPlugin, passthrough::OxrPassthroughPlugin,
+    },
     reference_space::OxrReferenceSpacePlugin,
 };
 
 pub mod action_binding;
+#[cfg(feature = "action_manifest")]
+pub mod action_manifest;
 pub mod action_set_attaching;
 pub mod action_set_syncing;
+pub mod battery;
+pub mod capture;
+pub mod controller_poses;
 pub mod error;
 pub mod exts;
 pub mod features;
 pub mod graphics;
+pub mod haptics;
 pub mod helper_traits;
 pub mod init;
 pub mod layer_builder;
+pub mod layer_swapchains;
 pub mod next_chain;
 pub mod poll_events;
 pub mod reference_space;
@@ -36,14 +46,29 @@ pub mod render;
 pub mod resources;
 pub mod session;
 pub mod spaces;
+pub mod standard_input;
+pub mod time;
 pub mod types;
+pub mod watchdog;
+
+/// System sets in [`PreUpdate`](bevy::app::PreUpdate) that update the XR state for the frame, run in this order.
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum OxrPreUpdateSet {
+    /// Syncs the actions, contains [`OxrActionSetSyncSet`](action_set_syncing::OxrActionSetSyncSet).
+    SyncActions,
+    /// Locates the spaces, contains [`OxrSpaceSyncSet`](spaces::OxrSpaceSyncSet).
+    UpdateCriticalComponents,
+    /// Updates tracking data that isn't needed to locate spaces, e.g. hand and body joints.
+    UpdateNonCriticalComponents,
+}
 
 /// A [`Condition`](bevy::ecs::schedule::Condition) system that says if the OpenXR session is available.
 pub fn openxr_session_available(
     status: Option<Res<XrState>>,
     instance: Option<Res<OxrInstance>>,
 ) -> bool {
-    status.is_some_and(|s| *s != XrState::Unavailable) && instance.is_some()
+    status.is_some_and(|s| !matches!(*s, XrState::Unavailable | XrState::Lost))
+        && instance.is_some()
 }
 
 /// A [`Condition`](bevy::ecs::schedule::Condition) system that says if the OpenXR is running.
@@ -67,11 +92,18 @@ pub fn add_xr_plugins<G: PluginGroup>(plugins: G) -> PluginGroupBuilder {
         .add(OxrRenderPlugin::default())
         .add(OxrPassthroughPlugin)
         .add(HandTrackingPlugin::default())
+        .add(OxrBodyTrackingPlugin)
+        .add(features::face_tracking::OxrFaceTrackingPlugin)
+        .add(OxrFoveationPlugin)
+        .add(features::spatial_anchors::OxrSpatialAnchorPlugin)
         .add(XrCameraPlugin)
         .add(action_set_attaching::OxrActionAttachingPlugin)
         .add(action_binding::OxrActionBindingPlugin)
         .add(action_set_syncing::OxrActionSyncingPlugin)
         .add(features::overlay::OxrOverlayPlugin)
+        .add(features::display_refresh_rate::OxrDisplayRefreshRatePlugin)
+        .add(features::space_warp::OxrSpaceWarpPlugin)
+        .add(features::visibility_mask::OxrVisibilityMaskPlugin)
         .add(spaces::OxrSpatialPlugin)
         .add(spaces::OxrSpacePatchingPlugin)
         // .add(XrActionPlugin)
@@ -1,7 +1,11 @@
-use std::{cell::RefCell, mem, ops::Deref, rc::Rc};
+use std::{cell::RefCell, mem, ops::Deref, rc::Rc, sync::atomic::Ordering};
 
-use bevy::{ecs::system::SystemId, prelude::*};
-use bevy_mod_xr::session::{XrFirst, XrHandleEvents};
+use bevy::{ecs::system::SystemId, prelude::*, utils::HashMap};
+use bevy_mod_xr::actions::XrInteractionProfileChanged;
+use bevy_mod_xr::session::{
+    XrDestroySessionRender, XrFirst, XrHandleEvents, XrInstanceLost, XrPreDestroySession, XrState,
+    XrStateChanged,
+};
 use openxr::{Event, EventDataBuffer};
 
 pub struct OxrEventsPlugin;
@@ -9,24 +13,50 @@ pub struct OxrEventsPlugin;
 impl Plugin for OxrEventsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<OxrEventHandlers>();
+        app.init_resource::<OxrInteractionProfiles>();
+        app.add_event::<OxrSessionStateChanged>()
+            .add_event::<OxrInstanceLossPending>()
+            .add_event::<OxrReferenceSpaceChangePending>()
+            .add_event::<XrInteractionProfileChanged>()
+            .add_oxr_event_handler(forward_events);
         app.add_systems(
             XrFirst,
             poll_events
                 .in_set(XrHandleEvents::Poll)
                 .run_if(openxr_session_available),
         );
+        app.add_systems(
+            PreUpdate,
+            update_interaction_profiles
+                .after(OxrActionSetSyncSet)
+                .run_if(on_event::<OxrInteractionProfileChanged>)
+                .run_if(openxr_session_running),
+        );
+        app.add_systems(XrPreDestroySession, clear_interaction_profiles);
     }
 }
 /// Polls any OpenXR events and handles them accordingly
+///
+/// If the instance was lost, the session is destroyed and the [`XrState`] moves to [`Lost`](XrState::Lost).
 pub fn poll_events(world: &mut World) {
     let _span = debug_span!("xr_poll_events").entered();
     let instance = world.resource::<OxrInstance>().clone();
     let handlers = world.remove_resource::<OxrEventHandlers>().unwrap();
     let mut buffer = EventDataBuffer::default();
-    while let Some(event) = instance
-        .poll_event(&mut buffer)
-        .expect("Failed to poll event")
-    {
+    let mut instance_lost = false;
+    loop {
+        let event = match instance.poll_event(&mut buffer) {
+            Ok(Some(event)) => event,
+            Ok(None) => break,
+            Err(openxr::sys::Result::ERROR_INSTANCE_LOST) => {
+                instance_lost = true;
+                break;
+            }
+            Err(err) => {
+                error!("Failed to poll event: {err}");
+                break;
+            }
+        };
         let event = Rc::new(RefCell::new(Some(event)));
         for handler in handlers.handlers.iter() {
             if let Err(err) =
@@ -38,9 +68,166 @@ pub fn poll_events(world: &mut World) {
         event.deref().take();
     }
     world.insert_resource(handlers);
+    if instance_lost {
+        handle_instance_lost(world);
+    }
 }
 
-use super::{openxr_session_available, resources::OxrInstance};
+fn handle_instance_lost(world: &mut World) {
+    error!("The OpenXR instance was lost, XR is unavailable until the app is restarted");
+    if world.contains_resource::<OxrSession>() {
+        destroy_xr_session(world);
+        world
+            .resource::<XrDestroySessionRender>()
+            .0
+            .store(true, Ordering::Relaxed);
+    }
+    world.resource_mut::<OxrSessionStarted>().0 = false;
+    world.insert_resource(XrState::Lost);
+    world.send_event(XrStateChanged(XrState::Lost));
+    world.send_event(XrInstanceLost);
+}
+
+use super::{
+    action_set_syncing::OxrActionSetSyncSet,
+    init::{destroy_xr_session, OxrInteractionProfileChanged},
+    openxr_session_available, openxr_session_running,
+    resources::{OxrInstance, OxrSessionStarted},
+    session::OxrSession,
+};
+
+/// Top level user paths [`XrInteractionProfileChanged`] is sent for.
+const INTERACTION_PROFILE_USER_PATHS: [&str; 2] = ["/user/hand/left", "/user/hand/right"];
+
+/// Sent when the OpenXR session changed its state.
+///
+/// [`XrState`](bevy_mod_xr::session::XrState) is usually more convenient, this is for reacting to the exact OpenXR state, e.g. [`FOCUSED`](openxr::SessionState::FOCUSED).
+#[derive(Event, Clone, Copy, Debug)]
+pub struct OxrSessionStateChanged {
+    pub state: openxr::SessionState,
+    /// When the state changed.
+    pub time: openxr::Time,
+}
+
+/// Sent when the OpenXR instance is about to be lost, e.g. because the runtime is being updated.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct OxrInstanceLossPending {
+    /// When the instance will be lost.
+    pub loss_time: openxr::Time,
+}
+
+/// Sent when the origin of a reference space is about to change, e.g. after the user recentered.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct OxrReferenceSpaceChangePending {
+    pub reference_space_type: openxr::ReferenceSpaceType,
+    /// When the change takes effect.
+    pub change_time: openxr::Time,
+    /// Whether `pose_in_previous_space` is valid.
+    pub pose_valid: bool,
+    /// The pose of the new origin in the previous space.
+    pub pose_in_previous_space: openxr::Posef,
+}
+
+/// The active interaction profile of every top level user path [`XrInteractionProfileChanged`] is sent for.
+///
+/// Bindings can't be suggested again once the action sets are attached, instead the runtime rebinds the actions
+/// to the new controllers itself, e.g. after the controllers were put down and picked up again.
+/// After every `XR_TYPE_EVENT_DATA_INTERACTION_PROFILE_CHANGED` the profiles are queried again once the actions of the frame
+/// were synced, and [`XrInteractionProfileChanged`] is sent for every user path whose profile differs from the one stored here.
+/// Cleared when the session is destroyed.
+#[derive(Resource, Debug, Default)]
+pub struct OxrInteractionProfiles(HashMap<&'static str, Option<String>>);
+
+impl OxrInteractionProfiles {
+    /// Returns the active interaction profile of `user_path`, e.g. `/user/hand/left`.
+    /// [`None`] if it has no active profile or wasn't queried yet.
+    pub fn get(&self, user_path: &str) -> Option<&str> {
+        self.0.get(user_path)?.as_deref()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, Option<&str>)> {
+        self.0
+            .iter()
+            .map(|(user_path, profile)| (*user_path, profile.as_deref()))
+    }
+}
+
+fn update_interaction_profiles(
+    instance: Res<OxrInstance>,
+    session: Res<OxrSession>,
+    mut profiles: ResMut<OxrInteractionProfiles>,
+    mut interaction_profile_changed: EventWriter<XrInteractionProfileChanged>,
+) {
+    // the event doesn't say which user paths changed, so query all of them
+    for user_path in INTERACTION_PROFILE_USER_PATHS {
+        let profile = instance
+            .string_to_path(user_path)
+            .and_then(|path| session.current_interaction_profile(path))
+            .and_then(|profile| {
+                if profile == openxr::Path::NULL {
+                    Ok(None)
+                } else {
+                    instance.path_to_string(profile).map(Some)
+                }
+            });
+        let profile = match profile {
+            Ok(profile) => profile,
+            Err(err) => {
+                warn!("Unable to get the interaction profile of {user_path}: {err}");
+                continue;
+            }
+        };
+        if profiles.0.get(user_path) == Some(&profile) {
+            continue;
+        }
+        debug!(
+            "Interaction profile of {user_path} changed to {}",
+            profile.as_deref().unwrap_or("<none>")
+        );
+        profiles.0.insert(user_path, profile.clone());
+        interaction_profile_changed.send(XrInteractionProfileChanged {
+            user_path: user_path.to_string(),
+            profile,
+        });
+    }
+}
+
+fn clear_interaction_profiles(mut profiles: ResMut<OxrInteractionProfiles>) {
+    profiles.0.clear();
+}
+
+/// Forwards the common OpenXR events as owned bevy [`Event`]s, so they can be read from ordinary systems.
+/// [`OxrInteractionProfileChanged`] is sent by the init plugin, [`XrInteractionProfileChanged`] along with [`OxrInteractionProfiles`].
+fn forward_events(
+    event: In<OxrEvent>,
+    mut state_changed: EventWriter<OxrSessionStateChanged>,
+    mut instance_loss_pending: EventWriter<OxrInstanceLossPending>,
+    mut reference_space_change_pending: EventWriter<OxrReferenceSpaceChangePending>,
+) {
+    // this unwrap will never panic since we are in a valid scope
+    match unsafe { event.get() }.unwrap() {
+        Event::SessionStateChanged(event) => {
+            state_changed.send(OxrSessionStateChanged {
+                state: event.state(),
+                time: event.time(),
+            });
+        }
+        Event::InstanceLossPending(event) => {
+            instance_loss_pending.send(OxrInstanceLossPending {
+                loss_time: event.loss_time(),
+            });
+        }
+        Event::ReferenceSpaceChangePending(event) => {
+            reference_space_change_pending.send(OxrReferenceSpaceChangePending {
+                reference_space_type: event.reference_space_type(),
+                change_time: event.change_time(),
+                pose_valid: event.pose_valid(),
+                pose_in_previous_space: event.pose_in_previous_space(),
+            });
+        }
+        _ => {}
+    }
+}
 #[derive(Resource, Debug, Default)]
 pub struct OxrEventHandlers {
     pub handlers: Vec<OxrEventHandler>,
@@ -59,6 +246,8 @@ impl OxrEvent {
         }
     }
     /// always returns [Some] if called in a valid scope
+    ///
+    /// For the common events, reading the owned events like [`OxrSessionStateChanged`] is easier.
     /// # Safety
     /// The event is only valid for the duration of the poll event callback,
     /// don't Store the [Event] anywhere!!
@@ -1,21 +1,32 @@
 use bevy::{
     prelude::*,
     render::{extract_resource::ExtractResourcePlugin, RenderApp},
+    transform::TransformSystem,
 };
 use bevy_mod_xr::{
-    session::{XrPreDestroySession, XrSessionCreated},
-    spaces::{XrPrimaryReferenceSpace, XrReferenceSpace},
+    session::{XrPreDestroySession, XrRecenterEvent, XrSessionCreated, XrTrackingRoot},
+    spaces::{XrPrimaryReferenceSpace, XrReferenceSpace, XrReferenceSpaceChanged},
 };
 
-use crate::session::OxrSession;
+use crate::{
+    helper_traits::{ToIsometry3d, ToQuat, ToVec3},
+    poll_events::OxrReferenceSpaceChangePending,
+    render::update_views,
+    resources::OxrViews,
+    session::OxrSession,
+};
 
 pub struct OxrReferenceSpacePlugin {
     pub default_primary_ref_space: openxr::ReferenceSpaceType,
+    /// What happens to the world when the origin of the primary reference space changes, e.g. when the user recenters.
+    /// Can be changed at runtime through the [`OxrRecenterPolicy`] resource.
+    pub recenter_policy: OxrRecenterPolicy,
 }
 impl Default for OxrReferenceSpacePlugin {
     fn default() -> Self {
         Self {
             default_primary_ref_space: openxr::ReferenceSpaceType::STAGE,
+            recenter_policy: OxrRecenterPolicy::FollowRuntime,
         }
     }
 }
@@ -24,6 +35,18 @@ impl Default for OxrReferenceSpacePlugin {
 #[derive(Resource)]
 struct OxrDefaultPrimaryReferenceSpaceType(openxr::ReferenceSpaceType);
 
+/// How the [`XrTrackingRoot`] reacts to the runtime changing the origin of the primary reference space.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OxrRecenterPolicy {
+    /// Leaves the tracking root alone, so world content moves together with the new origin,
+    /// e.g. it ends up in front of the user after they recentered.
+    #[default]
+    FollowRuntime,
+    /// Moves the tracking root by the inverse of the change, so world content stays where it was in the room
+    /// and tracked entities don't jump. Does nothing if the runtime doesn't report where the new origin is.
+    KeepContentStable,
+}
+
 /// The Default Reference space used for locating things
 // #[derive(Resource, Deref, ExtrctResource, Clone)]
 // pub struct OxrPrimaryReferenceSpace(pub Arc<openxr::Space>);
@@ -38,7 +61,21 @@ impl Plugin for OxrReferenceSpacePlugin {
             .insert_resource(OxrDefaultPrimaryReferenceSpaceType(
                 self.default_primary_ref_space,
             ))
+            .insert_resource(self.recenter_policy)
+            .add_event::<XrReferenceSpaceChanged>()
+            .add_event::<OxrReferenceSpaceChangePending>()
             .add_systems(XrSessionCreated, set_primary_ref_space)
+            .add_systems(
+                PreUpdate,
+                handle_reference_space_change.run_if(on_event::<OxrReferenceSpaceChangePending>),
+            )
+            .add_systems(
+                PostUpdate,
+                recenter_root
+                    .run_if(on_event::<XrRecenterEvent>)
+                    .after(update_views)
+                    .before(TransformSystem::TransformPropagate),
+            )
             .add_systems(XrPreDestroySession, cleanup);
 
         let render_app = app.sub_app_mut(RenderApp);
@@ -69,3 +106,64 @@ fn set_primary_ref_space(
         Err(err) => error!("Error while creating reference space: {}", err.to_string()),
     };
 }
+
+fn recenter_root(
+    mut events: EventReader<XrRecenterEvent>,
+    views: Res<OxrViews>,
+    mut root: Query<&mut Transform, With<XrTrackingRoot>>,
+) {
+    events.clear();
+    let Some(first) = views.first() else {
+        warn!("Can't recenter, the headset isn't located yet");
+        return;
+    };
+    // the head is between the eyes
+    let head = views
+        .iter()
+        .map(|view| view.pose.position.to_vec3())
+        .sum::<Vec3>()
+        / views.len() as f32;
+    let forward = first.pose.orientation.to_quat() * Vec3::NEG_Z;
+    let yaw = (-forward.x).atan2(-forward.z);
+    let rotation = Quat::from_rotation_y(-yaw);
+    let offset = rotation * Vec3::new(head.x, 0.0, head.z);
+    for mut transform in &mut root {
+        transform.rotation = rotation;
+        transform.translation = Vec3::new(-offset.x, transform.translation.y, -offset.z);
+    }
+}
+
+/// The primary reference space keeps its handle when its origin changes, so only the tracking root has to be updated.
+fn handle_reference_space_change(
+    mut events: EventReader<OxrReferenceSpaceChangePending>,
+    space_type: Res<OxrDefaultPrimaryReferenceSpaceType>,
+    policy: Res<OxrRecenterPolicy>,
+    mut root: Query<&mut Transform, With<XrTrackingRoot>>,
+    mut changed: EventWriter<XrReferenceSpaceChanged>,
+) {
+    for event in events.read() {
+        if event.reference_space_type != space_type.0 {
+            continue;
+        }
+        let pose = event
+            .pose_valid
+            .then(|| event.pose_in_previous_space.to_xr_pose());
+        if *policy == OxrRecenterPolicy::KeepContentStable {
+            match pose {
+                // the new origin is at `pose` in the old space, so moving the root by it keeps everything in place
+                Some(pose) => {
+                    for mut transform in &mut root {
+                        *transform = transform.mul_transform(Transform::from_isometry(pose));
+                    }
+                }
+                None => warn!(
+                    "The origin of the {:?} reference space changed to an unknown pose, tracked entities might jump",
+                    event.reference_space_type
+                ),
+            }
+        }
+        changed.send(XrReferenceSpaceChanged {
+            pose_in_previous_space: pose,
+        });
+    }
+}
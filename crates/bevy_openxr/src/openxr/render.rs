@@ -1,26 +1,46 @@
 use bevy::{
+    core_pipeline::{
+        bloom::Bloom,
+        tonemapping::{DebandDither, Tonemapping},
+        Skybox,
+    },
     prelude::*,
     render::{
         camera::{ManualTextureView, ManualTextureViewHandle, ManualTextureViews, RenderTarget},
         extract_resource::ExtractResourcePlugin,
         pipelined_rendering::PipelinedRenderingPlugin,
-        view::ExtractedView,
-        Render, RenderApp,
+        renderer::{render_system, RenderDevice, RenderQueue},
+        view::{ExtractedView, ViewDepthTexture},
+        Render, RenderApp, RenderSet,
     },
     transform::TransformSystem,
 };
 use bevy_mod_xr::{
-    camera::{XrCamera, XrProjection, XrViewInit},
+    camera::{XrCamera, XrHeadPose, XrProjection, XrViewInit},
     session::{
-        XrFirst, XrHandleEvents, XrPreDestroySession, XrRenderSet, XrRootTransform,
-        XrSessionCreated,
+        update_root_transform, XrFirst, XrHandleEvents, XrPreDestroySession, XrRenderSet,
+        XrRootTransform, XrSessionCreated, XrSessionVisibility,
     },
     spaces::XrPrimaryReferenceSpace,
 };
 use openxr::ViewStateFlags;
+use std::time::Duration;
 
-use crate::{init::should_run_frame_loop, resources::*};
-use crate::{layer_builder::ProjectionLayer, session::OxrSession};
+use crate::features::secondary_view::{secondary_view_layer, OxrSecondaryView};
+use crate::{
+    error::{OxrError, OxrRenderError},
+    init::{should_render, should_run_frame_loop},
+    resources::*,
+};
+use crate::{
+    layer_builder::{CompositionLayer, ProjectionLayer},
+    layer_swapchains::{
+        acquire_layer_images, clear_layer_swapchains, release_layer_images, wait_layer_images,
+        OxrLayerSwapchains,
+    },
+    session::OxrSession,
+    watchdog::OxrFrameWatchdog,
+};
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, SystemSet)]
 pub struct OxrRenderBegin;
@@ -28,14 +48,42 @@ pub struct OxrRenderBegin;
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, SystemSet)]
 pub struct OxrRenderEnd;
 
+/// The individual steps of submitting a frame in the render world, in the order they run.
+///
+/// Use these to order custom render systems precisely against the frame submission,
+/// e.g. writing to the [`OxrAcquiredSwapchainImage`] has to happen after [`WaitImage`](Self::WaitImage)
+/// and before [`ReleaseImage`](Self::ReleaseImage).
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, SystemSet)]
+pub enum OxrRenderStep {
+    /// Calls `xrBeginFrame`. Part of [`XrRenderSet::PreRender`].
+    BeginFrame,
+    /// Acquires the swapchain image and inserts the texture views for the cameras. Part of [`XrRenderSet::PreRender`].
+    AcquireImage,
+    /// Locates the views and updates the extracted camera views with the latest poses. Part of [`XrRenderSet::PreRender`].
+    LocateViews,
+    /// Waits until the acquired swapchain image can be written to. Part of [`XrRenderSet::PreRender`].
+    ///
+    /// With [`OxrRenderPlugin::wait_image_off_thread`] the wait only finishes right before the render graph runs.
+    WaitImage,
+    /// Releases the swapchain image back to the runtime. Part of [`XrRenderSet::PostRender`].
+    ReleaseImage,
+    /// Submits the composition layers with `xrEndFrame`. Part of [`XrRenderSet::PostRender`].
+    EndFrame,
+}
+
 pub struct OxrRenderPlugin {
     pub spawn_cameras: bool,
+    /// Waits for the acquired swapchain image on an [`OxrImageWaiter`] thread, starting right after it was acquired
+    /// and finishing just before the render graph runs, instead of blocking in [`OxrRenderStep::WaitImage`].
+    /// This lets the runtime release the image while the render world prepares the frame.
+    pub wait_image_off_thread: bool,
 }
 
 impl Default for OxrRenderPlugin {
     fn default() -> Self {
         Self {
             spawn_cameras: true,
+            wait_image_off_thread: false,
         }
     }
 }
@@ -51,12 +99,18 @@ impl Plugin for OxrRenderPlugin {
             ExtractResourcePlugin::<OxrGraphicsInfo>::default(),
             ExtractResourcePlugin::<OxrSwapchainImages>::default(),
             ExtractResourcePlugin::<OxrViews>::default(),
+            ExtractResourcePlugin::<OxrFreezeFrame>::default(),
+            ExtractResourcePlugin::<OxrBlendMode>::default(),
+            ExtractResourcePlugin::<OxrViewPredictionBias>::default(),
         ))
         .add_systems(XrPreDestroySession, clean_views)
         .add_systems(
             XrFirst,
             (
-                wait_frame.run_if(should_run_frame_loop),
+                clear_freeze_frame_dirty.run_if(should_run_frame_loop),
+                wait_frame
+                    .pipe(handle_render_error)
+                    .run_if(should_run_frame_loop),
                 update_cameras.run_if(should_run_frame_loop),
             )
                 .chain()
@@ -73,39 +127,165 @@ impl Plugin for OxrRenderPlugin {
         )
         .add_systems(
             PostUpdate,
-            (locate_views, update_views)
+            (
+                validate_blend_mode
+                    .run_if(resource_exists::<OxrGraphicsInfo>)
+                    .run_if(resource_exists_and_changed::<OxrBlendMode>),
+                update_clear_color.run_if(resource_exists::<OxrGraphicsInfo>),
+                update_additive_post_processing.run_if(resource_exists::<OxrGraphicsInfo>),
+                update_skybox.run_if(resource_exists::<OxrGraphicsInfo>),
+            )
+                .chain(),
+        )
+        .add_systems(
+            PostUpdate,
+            (
+                locate_views,
+                if self.spawn_cameras {
+                    sync_view_cameras::<true>
+                } else {
+                    sync_view_cameras::<false>
+                }
+                .run_if(resource_exists::<OxrSwapchainImages>),
+                update_views,
+                update_camera_activity,
+            )
                 .before(TransformSystem::TransformPropagate)
                 .chain()
                 // .run_if(should_render)
+                .run_if(should_run_frame_loop)
+                .run_if(resource_exists::<OxrFrameState>),
+        )
+        .add_systems(
+            PostUpdate,
+            update_head_pose
+                .after(update_root_transform)
                 .run_if(should_run_frame_loop),
         )
-        .init_resource::<OxrViews>();
+        .init_resource::<OxrViews>()
+        .init_resource::<XrHeadPose>()
+        .init_resource::<OxrFreezeFrame>()
+        .init_resource::<OxrViewPredictionBias>()
+        .init_resource::<OxrClearMode>()
+        .init_resource::<OxrSkipRenderingWhenHidden>()
+        .init_resource::<OxrAdditiveDisplaySettings>()
+        .init_resource::<OxrSkyboxPolicy>()
+        .register_required_components::<XrCamera, OxrViewInfo>();
 
         let render_app = app.sub_app_mut(RenderApp);
 
+        if self.wait_image_off_thread {
+            if let Some(instance) = render_app.world().get_resource::<OxrInstance>() {
+                match OxrImageWaiter::new(instance.clone()) {
+                    Ok(waiter) => {
+                        render_app.insert_resource(waiter).add_systems(
+                            Render,
+                            finish_wait_image
+                                .pipe(handle_render_error)
+                                .run_if(resource_exists::<OxrAcquiredSwapchainImage>)
+                                .in_set(RenderSet::Render)
+                                .before(render_system),
+                        );
+                    }
+                    Err(e) => error!("Failed to spawn the image wait thread: {e}"),
+                }
+            }
+        }
+
         render_app
-            .add_systems(XrPreDestroySession, clean_views)
-            .add_systems(
+            .configure_sets(
                 Render,
                 (
-                    begin_frame,
-                    insert_texture_views,
-                    locate_views,
-                    update_views_render_world,
-                    wait_image,
+                    OxrRenderStep::BeginFrame,
+                    OxrRenderStep::AcquireImage,
+                    OxrRenderStep::LocateViews,
+                    OxrRenderStep::WaitImage,
                 )
                     .chain()
-                    .in_set(XrRenderSet::PreRender)
-                    .run_if(should_run_frame_loop),
+                    .in_set(XrRenderSet::PreRender),
             )
-            .add_systems(
+            .configure_sets(
                 Render,
-                (release_image, end_frame)
+                (OxrRenderStep::ReleaseImage, OxrRenderStep::EndFrame)
                     .chain()
+                    .in_set(XrRenderSet::PostRender),
+            )
+            .configure_sets(
+                Render,
+                (
+                    OxrRenderStep::AcquireImage,
+                    OxrRenderStep::WaitImage,
+                    OxrRenderStep::ReleaseImage,
+                    OxrRenderStep::EndFrame,
+                )
+                    .run_if(not(resource_exists::<OxrFrameSkipped>)),
+            )
+            .init_resource::<OxrLayerSwapchains>()
+            .add_systems(XrPreDestroySession, (clean_views, clear_layer_swapchains))
+            .add_systems(
+                Render,
+                (
+                    begin_frame
+                        .pipe(handle_render_error)
+                        .in_set(OxrRenderStep::BeginFrame),
+                    insert_texture_views
+                        .pipe(handle_render_error)
+                        .run_if(should_render_views)
+                        .run_if(should_render)
+                        .in_set(OxrRenderStep::AcquireImage),
+                    acquire_depth_image
+                        .run_if(should_render_views)
+                        .run_if(should_render)
+                        .run_if(resource_exists::<OxrDepthSwapchain>)
+                        .in_set(OxrRenderStep::AcquireImage),
+                    (locate_views, update_views_render_world)
+                        .chain()
+                        .in_set(OxrRenderStep::LocateViews),
+                    wait_image
+                        .pipe(handle_render_error)
+                        .run_if(should_render_views)
+                        .run_if(resource_exists::<OxrAcquiredSwapchainImage>)
+                        .in_set(OxrRenderStep::WaitImage),
+                    wait_depth_image
+                        .run_if(resource_exists::<OxrAcquiredDepthImage>)
+                        .in_set(OxrRenderStep::WaitImage),
+                    acquire_layer_images
+                        .run_if(should_render)
+                        .in_set(OxrRenderStep::AcquireImage),
+                    wait_layer_images.in_set(OxrRenderStep::WaitImage),
+                )
                     .run_if(should_run_frame_loop)
+                    .run_if(resource_exists::<OxrFrameState>),
+            )
+            .add_systems(
+                Render,
+                (
+                    (
+                        release_image.pipe(handle_render_error),
+                        store_rendered_views,
+                    )
+                        .run_if(should_render_views)
+                        .run_if(resource_exists::<OxrAcquiredSwapchainImage>)
+                        .in_set(OxrRenderStep::ReleaseImage),
+                    (copy_view_depth, release_depth_image)
+                        .chain()
+                        .run_if(resource_exists::<OxrAcquiredDepthImage>)
+                        .in_set(OxrRenderStep::ReleaseImage),
+                    release_layer_images.in_set(OxrRenderStep::ReleaseImage),
+                    end_frame
+                        .pipe(handle_render_error)
+                        .in_set(OxrRenderStep::EndFrame),
+                )
+                    .run_if(should_run_frame_loop)
+                    .run_if(resource_exists::<OxrFrameState>),
+            )
+            .add_systems(
+                Render,
+                clear_frame_skipped
+                    .after(OxrRenderStep::EndFrame)
                     .in_set(XrRenderSet::PostRender),
             )
-            .insert_resource(OxrRenderLayers(vec![Box::new(ProjectionLayer)]));
+            .insert_resource(OxrRenderLayers(vec![Box::new(ProjectionLayer::default())]));
     }
 }
 
@@ -157,41 +337,422 @@ pub fn init_views<const SPAWN_CAMERAS: bool>(
     mut commands: Commands,
 ) {
     let temp_tex = swapchain_images.first().unwrap();
-    // this for loop is to easily add support for quad or mono views in the future.
-    for index in 0..2 {
+    for index in 0..graphics_info.view_count {
         let _span = debug_span!("xr_init_view").entered();
         info!("XrCamera resolution: {}", graphics_info.resolution);
-        let view_handle =
-            add_texture_view(&mut manual_texture_views, temp_tex, &graphics_info, index);
-        if SPAWN_CAMERAS {
-            commands.spawn((
-                Camera {
-                    target: RenderTarget::TextureView(view_handle),
-                    ..Default::default()
-                },
-                XrCamera(index),
-            ));
+        init_view::<SPAWN_CAMERAS>(
+            &mut manual_texture_views,
+            temp_tex,
+            &graphics_info,
+            index,
+            &mut commands,
+        );
+    }
+}
+
+fn init_view<const SPAWN_CAMERAS: bool>(
+    manual_texture_views: &mut ManualTextureViews,
+    texture: &wgpu::Texture,
+    graphics_info: &OxrGraphicsInfo,
+    index: u32,
+    commands: &mut Commands,
+) {
+    let view_handle = add_texture_view(manual_texture_views, texture, graphics_info, index);
+    if SPAWN_CAMERAS {
+        commands.spawn((
+            Camera {
+                target: RenderTarget::TextureView(view_handle),
+                ..Default::default()
+            },
+            Msaa::from(graphics_info.msaa),
+            XrCamera(index),
+        ));
+    }
+}
+
+/// Adds or removes [`XrCamera`]s and their texture views when the number of located views no longer matches
+/// the number of cameras. Without [`OxrRenderPlugin::spawn_cameras`] only the texture views are updated.
+///
+/// Only as many views as the swapchain has array layers can be rendered, so the cameras are capped at
+/// [`OxrGraphicsInfo::view_count`] and a warning is logged if the runtime locates more views than that.
+pub fn sync_view_cameras<const SPAWN_CAMERAS: bool>(
+    views: Res<OxrViews>,
+    graphics_info: Res<OxrGraphicsInfo>,
+    swapchain_images: Res<OxrSwapchainImages>,
+    mut manual_texture_views: ResMut<ManualTextureViews>,
+    cameras: Query<(Entity, &XrCamera)>,
+    mut last_view_count: Local<Option<usize>>,
+    mut commands: Commands,
+) {
+    // the views aren't located yet, or the runtime failed to locate them this frame
+    if views.is_empty() || *last_view_count == Some(views.len()) {
+        return;
+    }
+    if last_view_count.is_some() {
+        info!(
+            "The number of located views changed from {} to {}",
+            last_view_count.unwrap_or_default(),
+            views.len()
+        );
+    }
+    *last_view_count = Some(views.len());
+    if views.len() > graphics_info.view_count as usize {
+        warn!(
+            "The runtime located {} views, but the swapchain only has layers for {}, the other views aren't rendered",
+            views.len(),
+            graphics_info.view_count
+        );
+    }
+    let view_count = (views.len() as u32).min(graphics_info.view_count);
+
+    let mut existing = Vec::new();
+    for (entity, camera) in &cameras {
+        if camera.0 >= view_count {
+            // cameras spawned by the app are left to the app
+            if SPAWN_CAMERAS {
+                commands.entity(entity).despawn_recursive();
+            }
+            manual_texture_views.remove(&ManualTextureViewHandle(XR_TEXTURE_INDEX + camera.0));
+        } else {
+            existing.push(camera.0);
         }
     }
+    let Some(texture) = swapchain_images.first() else {
+        return;
+    };
+    for index in (0..view_count).filter(|index| !existing.contains(index)) {
+        init_view::<SPAWN_CAMERAS>(
+            &mut manual_texture_views,
+            texture,
+            &graphics_info,
+            index,
+            &mut commands,
+        );
+    }
 }
 
-pub fn wait_frame(mut frame_waiter: ResMut<OxrFrameWaiter>, mut commands: Commands) {
-    let state = frame_waiter.wait().expect("Failed to wait frame");
+pub fn wait_frame(
+    mut frame_waiter: ResMut<OxrFrameWaiter>,
+    session: Res<OxrSession>,
+    secondary_view: Option<ResMut<OxrSecondaryView>>,
+    timing: Option<Res<OxrFrameTiming>>,
+    visibility: Option<Res<XrSessionVisibility>>,
+    skip_hidden: Res<OxrSkipRenderingWhenHidden>,
+    watchdog: Option<Res<OxrFrameWatchdog>>,
+    mut commands: Commands,
+) -> Result<(), OxrRenderError> {
+    let _guard = watchdog.map(|watchdog| watchdog.watch("wait_frame"));
+    let result = match secondary_view {
+        Some(mut secondary_view) => {
+            session.wait_frame_with_secondary_view(&mut frame_waiter, &mut secondary_view)
+        }
+        None => frame_waiter.wait(),
+    };
+    let mut state = match result {
+        Ok(state) => state,
+        // the runtime might not consider the session running yet right after it was begun
+        Err(openxr::sys::Result::ERROR_SESSION_NOT_RUNNING) => {
+            debug!("Session not running yet, skipping wait frame");
+            return Ok(());
+        }
+        Err(e) => return Err(OxrRenderError::WaitFrame(e)),
+    };
+    if skip_hidden.0
+        && visibility.is_some_and(|visibility| *visibility == XrSessionVisibility::Hidden)
+    {
+        state.should_render = false;
+    }
+    let delta = timing.map_or(Duration::ZERO, |timing| {
+        let nanos =
+            state.predicted_display_time.as_nanos() - timing.predicted_display_time.as_nanos();
+        Duration::from_nanos(nanos.max(0) as u64)
+    });
+    commands.insert_resource(OxrFrameTiming {
+        predicted_display_time: state.predicted_display_time,
+        predicted_display_period: state.predicted_display_period,
+        delta,
+        should_render: state.should_render,
+    });
     commands.insert_resource(OxrFrameState(state));
+    Ok(())
 }
 
-pub fn update_cameras(
-    frame_state: Res<OxrFrameState>,
-    mut cameras: Query<(&mut Camera, &XrCamera)>,
+/// Set in the render world when [`begin_frame`] failed,
+/// the remaining [`OxrRenderStep`]s are skipped for that frame.
+#[derive(Resource)]
+pub struct OxrFrameSkipped;
+
+/// Logs the errors of the frame loop instead of panicking, so a single failed frame doesn't kill the app.
+///
+/// Errors caused by the session not running yet are expected and only logged at debug level.
+/// Out of order calls happen every frame during session transitions, so they are only warned about once.
+/// If beginning the frame failed the rest of the frame is skipped, see [`OxrFrameSkipped`].
+pub fn handle_render_error(
+    In(result): In<Result<(), OxrRenderError>>,
+    watchdog: Option<Res<OxrFrameWatchdog>>,
+    mut commands: Commands,
 ) {
+    let Err(err) = result else {
+        return;
+    };
+    if let Some(watchdog) = watchdog {
+        watchdog.record_error(&err);
+    }
+    if matches!(err, OxrRenderError::BeginFrame(_)) {
+        commands.insert_resource(OxrFrameSkipped);
+    }
+    match err.raw() {
+        Some(openxr::sys::Result::ERROR_SESSION_NOT_RUNNING) => {
+            debug!("{err}, session not running")
+        }
+        Some(openxr::sys::Result::ERROR_CALL_ORDER_INVALID) => warn_once!("{err}, skipping"),
+        _ => error!("{err}"),
+    }
+}
+
+fn clear_frame_skipped(mut commands: Commands) {
+    commands.remove_resource::<OxrFrameSkipped>();
+}
+
+pub fn update_cameras(mut cameras: Query<(&mut Camera, &XrCamera)>) {
     for (mut camera, xr_camera) in &mut cameras {
         camera.target =
             RenderTarget::TextureView(ManualTextureViewHandle(XR_TEXTURE_INDEX + xr_camera.0));
     }
-    if frame_state.is_changed() {
-        for (mut camera, _) in &mut cameras {
-            camera.is_active = frame_state.should_render
+}
+
+/// Marks cameras whose clear color is managed by [`OxrClearMode::Auto`].
+#[derive(Component)]
+pub struct OxrAutoClearColor;
+
+/// Keeps the post processing of an [`XrCamera`] unchanged on additive displays,
+/// see [`OxrAdditiveDisplaySettings`].
+#[derive(Component)]
+pub struct OxrKeepPostProcessing;
+
+/// The post processing of a camera before it was adjusted for an additive display.
+#[derive(Component)]
+pub struct OxrAdditiveAdjusted {
+    tonemapping: Option<Tonemapping>,
+    deband_dither: Option<DebandDither>,
+    bloom: Option<Bloom>,
+}
+
+/// The [`Skybox`] of a camera while it is hidden by the [`OxrSkyboxPolicy`].
+#[derive(Component)]
+pub struct OxrHiddenSkybox(pub Skybox);
+
+/// Reverts [`OxrBlendMode`] to the blend mode the session was created with if the runtime doesn't support it.
+pub fn validate_blend_mode(
+    mut blend_mode: ResMut<OxrBlendMode>,
+    graphics_info: Res<OxrGraphicsInfo>,
+    instance: Res<OxrInstance>,
+    system_id: Res<OxrSystemId>,
+) {
+    match instance
+        .enumerate_environment_blend_modes(**system_id, graphics_info.view_configuration_type)
+    {
+        Ok(available) if available.contains(&blend_mode.0) => {}
+        Ok(_) => {
+            error!(
+                "Blend mode {:?} is not supported by the runtime, using {:?}",
+                blend_mode.0, graphics_info.blend_mode
+            );
+            blend_mode.0 = graphics_info.blend_mode;
         }
+        Err(e) => error!("Failed to enumerate blend modes: {e}"),
+    }
+}
+
+pub fn update_clear_color(
+    mode: Res<OxrClearMode>,
+    graphics_info: Res<OxrGraphicsInfo>,
+    blend_mode: Option<Res<OxrBlendMode>>,
+    clear_color: Res<ClearColor>,
+    passthrough: Option<Res<OxrPassthroughEnabled>>,
+    mut cameras: Query<(Entity, &mut Camera, Has<OxrAutoClearColor>), With<XrCamera>>,
+    mut commands: Commands,
+) {
+    if *mode == OxrClearMode::Manual {
+        for (entity, mut camera, auto) in &mut cameras {
+            if auto {
+                camera.clear_color = ClearColorConfig::Default;
+                commands.entity(entity).remove::<OxrAutoClearColor>();
+            }
+        }
+        return;
+    }
+    // the projection layer uses premultiplied alpha, so transparent pixels also have to be black
+    let color = if environment_visible(&graphics_info, blend_mode, passthrough) {
+        Color::NONE
+    } else {
+        clear_color.0.with_alpha(1.0)
+    };
+    for (entity, mut camera, auto) in &mut cameras {
+        if !auto && !matches!(camera.clear_color, ClearColorConfig::Default) {
+            continue;
+        }
+        if !matches!(camera.clear_color, ClearColorConfig::Custom(c) if c == color) {
+            camera.clear_color = ClearColorConfig::Custom(color);
+        }
+        if !auto {
+            commands.entity(entity).insert(OxrAutoClearColor);
+        }
+    }
+}
+
+/// Whether the real world shows through the transparent pixels of the views.
+fn environment_visible(
+    graphics_info: &OxrGraphicsInfo,
+    blend_mode: Option<Res<OxrBlendMode>>,
+    passthrough: Option<Res<OxrPassthroughEnabled>>,
+) -> bool {
+    let blend_mode = blend_mode.map_or(graphics_info.blend_mode, |mode| mode.0);
+    blend_mode != openxr::EnvironmentBlendMode::OPAQUE
+        || passthrough.is_some_and(|enabled| enabled.0)
+}
+
+/// Hides or shows the [`Skybox`] of the [`XrCamera`]s according to the [`OxrSkyboxPolicy`].
+///
+/// A hidden skybox is moved into [`OxrHiddenSkybox`] and put back once it is shown again,
+/// unless the camera got a new skybox in the meantime.
+pub fn update_skybox(
+    policy: Res<OxrSkyboxPolicy>,
+    graphics_info: Res<OxrGraphicsInfo>,
+    blend_mode: Option<Res<OxrBlendMode>>,
+    passthrough: Option<Res<OxrPassthroughEnabled>>,
+    cameras: Query<(Entity, Option<&Skybox>, Option<&OxrHiddenSkybox>), With<XrCamera>>,
+    mut commands: Commands,
+) {
+    let show = match *policy {
+        OxrSkyboxPolicy::Auto => !environment_visible(&graphics_info, blend_mode, passthrough),
+        OxrSkyboxPolicy::Show => true,
+        OxrSkyboxPolicy::Hide => false,
+    };
+    for (entity, skybox, hidden) in &cameras {
+        match (skybox, hidden) {
+            (Some(skybox), _) if !show => {
+                commands
+                    .entity(entity)
+                    .insert(OxrHiddenSkybox(skybox.clone()))
+                    .remove::<Skybox>();
+            }
+            (None, Some(hidden)) if show => {
+                commands
+                    .entity(entity)
+                    .insert(hidden.0.clone())
+                    .remove::<OxrHiddenSkybox>();
+            }
+            (Some(_), Some(_)) if show => {
+                commands.entity(entity).remove::<OxrHiddenSkybox>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Applies the [`OxrAdditiveDisplaySettings`] while the blend mode is additive, and restores the post processing
+/// of the cameras once it isn't anymore or the settings change.
+pub fn update_additive_post_processing(
+    graphics_info: Res<OxrGraphicsInfo>,
+    blend_mode: Option<Res<OxrBlendMode>>,
+    settings: Res<OxrAdditiveDisplaySettings>,
+    mut cameras: Query<
+        (
+            Entity,
+            Option<&mut Tonemapping>,
+            Option<&mut DebandDither>,
+            Option<&Bloom>,
+            Option<&OxrAdditiveAdjusted>,
+        ),
+        (With<XrCamera>, Without<OxrKeepPostProcessing>),
+    >,
+    mut commands: Commands,
+) {
+    let blend_mode = blend_mode.map_or(graphics_info.blend_mode, |mode| mode.0);
+    let additive = blend_mode == openxr::EnvironmentBlendMode::ADDITIVE;
+    for (entity, tonemapping, deband_dither, bloom, adjusted) in &mut cameras {
+        if let Some(adjusted) = adjusted {
+            if additive && !settings.is_changed() {
+                continue;
+            }
+            // restore this frame, the new settings are applied on the next one
+            if let (Some(mut tonemapping), Some(previous)) = (tonemapping, adjusted.tonemapping) {
+                *tonemapping = previous;
+            }
+            if let (Some(mut deband_dither), Some(previous)) =
+                (deband_dither, adjusted.deband_dither)
+            {
+                *deband_dither = previous;
+            }
+            let mut entity = commands.entity(entity);
+            if let Some(bloom) = adjusted.bloom.clone() {
+                entity.insert(bloom);
+            }
+            entity.remove::<OxrAdditiveAdjusted>();
+            continue;
+        }
+        if !additive {
+            continue;
+        }
+        let mut previous = OxrAdditiveAdjusted {
+            tonemapping: None,
+            deband_dither: None,
+            bloom: None,
+        };
+        if let Some(mut tonemapping) = tonemapping.filter(|_| settings.disable_tonemapping) {
+            previous.tonemapping = Some(*tonemapping);
+            *tonemapping = Tonemapping::None;
+        }
+        if let Some(mut deband_dither) = deband_dither.filter(|_| settings.disable_deband_dither) {
+            previous.deband_dither = Some(*deband_dither);
+            *deband_dither = DebandDither::Disabled;
+        }
+        let mut entity = commands.entity(entity);
+        if let Some(bloom) = bloom.filter(|_| settings.disable_bloom) {
+            previous.bloom = Some(bloom.clone());
+            entity.remove::<Bloom>();
+        }
+        entity.insert(previous);
+    }
+}
+
+/// Turns the [`XrCamera`]s off while the runtime doesn't want the views rendered, or while they are frozen
+/// by [`OxrFreezeFrame`], and back on afterwards.
+///
+/// [`Camera::is_active`] is only written when that state flips, or when an [`XrCamera`] is added,
+/// so an app can still turn single cameras off in between.
+pub fn update_camera_activity(
+    frame_state: Res<OxrFrameState>,
+    freeze: Res<OxrFreezeFrame>,
+    mut cameras: Query<(&mut Camera, Ref<XrCamera>)>,
+    mut last_active: Local<Option<bool>>,
+) {
+    let active = frame_state.should_render && freeze.should_render_views();
+    let flipped = *last_active != Some(active);
+    *last_active = Some(active);
+    for (mut camera, xr_camera) in &mut cameras {
+        if flipped || xr_camera.is_added() {
+            camera.is_active = active;
+        }
+    }
+}
+
+/// A [`Condition`](bevy::ecs::schedule::Condition) system that says if the XR views are rendered this frame, see [`OxrFreezeFrame`].
+pub fn should_render_views(freeze: Option<Res<OxrFreezeFrame>>) -> bool {
+    freeze.is_none_or(|freeze| freeze.should_render_views())
+}
+
+/// Clears [`OxrFreezeFrame`]'s dirty flag once the frame it was set for has been rendered.
+fn clear_freeze_frame_dirty(
+    frame_state: Option<Res<OxrFrameState>>,
+    mut freeze: ResMut<OxrFreezeFrame>,
+) {
+    if freeze.is_frozen()
+        && freeze.should_render_views()
+        && frame_state.is_some_and(|state| state.should_render)
+    {
+        freeze.clear_dirty();
     }
 }
 
@@ -199,10 +760,12 @@ pub fn locate_views(
     session: Res<OxrSession>,
     ref_space: Res<XrPrimaryReferenceSpace>,
     frame_state: Res<OxrFrameState>,
+    graphics_info: Res<OxrGraphicsInfo>,
     mut openxr_views: ResMut<OxrViews>,
     pipelined: Option<Res<Pipelined>>,
+    bias: Option<Res<OxrViewPredictionBias>>,
 ) {
-    let time = if pipelined.is_some() {
+    let mut time = if pipelined.is_some() {
         openxr::Time::from_nanos(
             frame_state.predicted_display_time.as_nanos()
                 + frame_state.predicted_display_period.as_nanos(),
@@ -210,13 +773,18 @@ pub fn locate_views(
     } else {
         frame_state.predicted_display_time
     };
-    let (flags, xr_views) = session
-        .locate_views(
-            openxr::ViewConfigurationType::PRIMARY_STEREO,
-            time,
-            &ref_space,
-        )
-        .expect("Failed to locate views");
+    if let Some(bias) = bias {
+        time = bias.apply(time);
+    }
+    let (flags, xr_views) =
+        match session.locate_views(graphics_info.view_configuration_type, time, &ref_space) {
+            Ok(v) => v,
+            Err(err) => {
+                // can happen while the session is transitioning, keep the last known views
+                debug!("Failed to locate views: {err}");
+                return;
+            }
+        };
 
     match (
         flags & ViewStateFlags::ORIENTATION_VALID == ViewStateFlags::ORIENTATION_VALID,
@@ -243,16 +811,75 @@ pub fn locate_views(
     }
 }
 
+/// The raw field of view and pose of the view an [`XrCamera`] renders, as located by the runtime.
+///
+/// Updated together with the camera's [`Transform`] and [`XrProjection`].
+#[derive(Clone, Copy, Debug, Component)]
+pub struct OxrViewInfo {
+    pub fov: openxr::Fovf,
+    /// Pose of the view relative to the [`XrPrimaryReferenceSpace`].
+    pub pose: openxr::Posef,
+}
+
+impl Default for OxrViewInfo {
+    fn default() -> Self {
+        Self {
+            fov: openxr::Fovf {
+                angle_left: 0.0,
+                angle_right: 0.0,
+                angle_up: 0.0,
+                angle_down: 0.0,
+            },
+            pose: openxr::Posef::IDENTITY,
+        }
+    }
+}
+
+impl OxrViewInfo {
+    /// The tangents of the field of view angles as `(left, right, up, down)`.
+    ///
+    /// Left and down are negative for a view centered on the eye.
+    pub fn tangents(&self) -> Vec4 {
+        Vec4::new(
+            self.fov.angle_left.tan(),
+            self.fov.angle_right.tan(),
+            self.fov.angle_up.tan(),
+            self.fov.angle_down.tan(),
+        )
+    }
+}
+
 pub fn update_views(
-    mut query: Query<(&mut Transform, &mut XrProjection, &XrCamera)>,
+    mut query: Query<(
+        &mut Transform,
+        &mut XrProjection,
+        &mut OxrViewInfo,
+        &XrCamera,
+    )>,
     views: ResMut<OxrViews>,
+    graphics_backend: Res<OxrGraphicsBackend>,
 ) {
-    for (mut transform, mut projection, camera) in query.iter_mut() {
+    for (mut transform, mut projection, mut view_info, camera) in query.iter_mut() {
         let Some(view) = views.get(camera.0 as usize) else {
+            warn_once!(
+                "XrCamera({}) has no located view, only {} views were located",
+                camera.0,
+                views.len()
+            );
             continue;
         };
+        *view_info = OxrViewInfo {
+            fov: view.fov,
+            pose: view.pose,
+        };
 
-        let projection_matrix = calculate_projection(projection.near, view.fov);
+        let projection_matrix = calculate_projection(
+            projection.near,
+            projection.far,
+            projection.reversed_z,
+            view.fov,
+            graphics_backend.api,
+        );
         projection.projection_matrix = projection_matrix;
 
         let openxr::Quaternionf { x, y, z, w } = view.pose.orientation;
@@ -264,6 +891,22 @@ pub fn update_views(
     }
 }
 
+/// Updates the [`XrHeadPose`] from the [`OxrViews::midpoint`] and the [`XrRootTransform`].
+pub fn update_head_pose(
+    views: Res<OxrViews>,
+    root: Res<XrRootTransform>,
+    mut head_pose: ResMut<XrHeadPose>,
+) {
+    let Some(midpoint) = views.midpoint() else {
+        return;
+    };
+    let local = Transform::from_isometry(midpoint);
+    head_pose.set_if_neq(XrHeadPose {
+        local,
+        global: root.0.mul_transform(local),
+    });
+}
+
 pub fn update_views_render_world(
     views: Res<OxrViews>,
     root: Res<XrRootTransform>,
@@ -284,15 +927,20 @@ pub fn update_views_render_world(
     }
 }
 
-fn calculate_projection(near_z: f32, fov: openxr::Fovf) -> Mat4 {
+pub(crate) fn calculate_projection(
+    near_z: f32,
+    far_z: Option<f32>,
+    reversed_z: bool,
+    fov: openxr::Fovf,
+    api: OxrGraphicsApi,
+) -> Mat4 {
     //  symmetric perspective for debugging
     // let x_fov = (self.fov.angle_left.abs() + self.fov.angle_right.abs());
     // let y_fov = (self.fov.angle_up.abs() + self.fov.angle_down.abs());
     // return Mat4::perspective_infinite_reverse_rh(y_fov, x_fov / y_fov, self.near);
 
-    let is_vulkan_api = false; // FIXME wgpu probably abstracts this
-    let far_z = -1.; //   use infinite proj
-                     // let far_z = self.far;
+    // a far plane at or in front of the near plane means an infinite projection
+    let far_z = far_z.unwrap_or(-1.);
 
     let tan_angle_left = fov.angle_left.tan();
     let tan_angle_right = fov.angle_right.tan();
@@ -307,7 +955,7 @@ fn calculate_projection(near_z: f32, fov: openxr::Fovf) -> Mat4 {
     // positive Y up (OpenGL / D3D / Metal).
     // const float tanAngleHeight =
     //     graphicsApi == GRAPHICS_VULKAN ? (tanAngleDown - tanAngleUp) : (tanAngleUp - tanAngleDown);
-    let tan_angle_height = if is_vulkan_api {
+    let tan_angle_height = if api.clip_space_y_down() {
         tan_angle_down - tan_angle_up
     } else {
         tan_angle_up - tan_angle_down
@@ -317,8 +965,11 @@ fn calculate_projection(near_z: f32, fov: openxr::Fovf) -> Mat4 {
     // Set to zero for a [0,1] Z clip space (Vulkan / D3D / Metal).
     // const float offsetZ =
     //     (graphicsApi == GRAPHICS_OPENGL || graphicsApi == GRAPHICS_OPENGL_ES) ? nearZ : 0;
-    // FIXME handle enum of graphics apis
-    let offset_z = 0.;
+    let offset_z = if api.clip_space_z_negative_one_to_one() {
+        near_z
+    } else {
+        0.
+    };
 
     let mut cols: [f32; 16] = [0.0; 16];
 
@@ -343,17 +994,6 @@ fn calculate_projection(near_z: f32, fov: openxr::Fovf) -> Mat4 {
         cols[7] = 0.;
         cols[11] = -1.;
         cols[15] = 0.;
-
-        //  bevy uses the _reverse_ infinite projection
-        //  https://dev.theomader.com/depth-precision/
-        let z_reversal = Mat4::from_cols_array_2d(&[
-            [1f32, 0., 0., 0.],
-            [0., 1., 0., 0.],
-            [0., 0., -1., 0.],
-            [0., 0., 1., 1.],
-        ]);
-
-        return z_reversal * Mat4::from_cols_array(&cols);
     } else {
         // normal projection
         cols[0] = 2. / tan_angle_width;
@@ -377,30 +1017,209 @@ fn calculate_projection(near_z: f32, fov: openxr::Fovf) -> Mat4 {
         cols[15] = 0.;
     }
 
+    if reversed_z {
+        //  bevy uses the _reverse_ projection, this maps depth d to 1 - d
+        //  https://dev.theomader.com/depth-precision/
+        let z_reversal = Mat4::from_cols_array_2d(&[
+            [1f32, 0., 0., 0.],
+            [0., 1., 0., 0.],
+            [0., 0., -1., 0.],
+            [0., 0., 1., 1.],
+        ]);
+        return z_reversal * Mat4::from_cols_array(&cols);
+    }
+
     Mat4::from_cols_array(&cols)
 }
 
 /// # Safety
-/// Images inserted into texture views here should not be written to until [`wait_image`] is ran
+/// Images inserted into texture views here should not be written to until [`wait_image`] is ran.
+/// Use [`OxrAcquiredSwapchainImage`] to access the acquired image from the render graph.
 pub fn insert_texture_views(
     swapchain_images: Res<OxrSwapchainImages>,
     mut swapchain: ResMut<OxrSwapchain>,
     mut manual_texture_views: ResMut<ManualTextureViews>,
     graphics_info: Res<OxrGraphicsInfo>,
-) {
-    let index = swapchain.acquire_image().expect("Failed to acquire image");
-    let image = &swapchain_images[index as usize];
+    waiter: Option<Res<OxrImageWaiter>>,
+    mut commands: Commands,
+) -> Result<(), OxrRenderError> {
+    if let Some(waiter) = waiter
+        .as_ref()
+        .filter(|_| swapchain.image_state() == OxrSwapchainImageState::Waiting)
+    {
+        if let Err(e) = swapchain.finish_wait_image(waiter) {
+            warn!("Failed to finish waiting for pending image: {e}");
+        }
+    }
+    if swapchain.image_state() != OxrSwapchainImageState::Released {
+        warn!("Swapchain image of a skipped frame is still acquired, releasing it");
+        if let Err(e) = swapchain.release_pending_image() {
+            warn!("Failed to release pending image: {e}");
+        }
+    }
+    let index = swapchain
+        .acquire_image()
+        .map_err(OxrRenderError::AcquireImage)?;
+    let image = &swapchain_images.0[index as usize];
 
-    for i in 0..2 {
+    for i in 0..graphics_info.view_count {
         let _span = debug_span!("xr_insert_texture_view").entered();
         add_texture_view(&mut manual_texture_views, image, &graphics_info, i);
     }
+    commands.insert_resource(OxrAcquiredSwapchainImage {
+        index,
+        texture: image,
+    });
+    if let Some(waiter) = waiter {
+        swapchain
+            .start_wait_image(&waiter)
+            .map_err(OxrRenderError::WaitImage)?;
+    }
+    Ok(())
+}
+
+pub fn wait_image(
+    mut swapchain: ResMut<OxrSwapchain>,
+    watchdog: Option<Res<OxrFrameWatchdog>>,
+) -> Result<(), OxrRenderError> {
+    let _guard = watchdog.map(|watchdog| watchdog.watch("wait_image"));
+    if swapchain.image_state() == OxrSwapchainImageState::Waiting {
+        // finished by `finish_wait_image` right before rendering
+        return Ok(());
+    }
+    swapchain
+        .wait_image(openxr::Duration::INFINITE)
+        .map_err(OxrRenderError::WaitImage)
 }
 
-pub fn wait_image(mut swapchain: ResMut<OxrSwapchain>) {
+/// Blocks until the wait started by [`insert_texture_views`] is done, see [`OxrRenderPlugin::wait_image_off_thread`].
+pub fn finish_wait_image(
+    mut swapchain: ResMut<OxrSwapchain>,
+    waiter: Res<OxrImageWaiter>,
+) -> Result<(), OxrRenderError> {
+    if swapchain.image_state() != OxrSwapchainImageState::Waiting {
+        return Ok(());
+    }
     swapchain
+        .finish_wait_image(&waiter)
+        .map_err(OxrRenderError::WaitImage)
+}
+
+pub fn acquire_depth_image(mut depth_swapchain: ResMut<OxrDepthSwapchain>, mut commands: Commands) {
+    if depth_swapchain.swapchain.image_state() != OxrSwapchainImageState::Released {
+        if let Err(e) = depth_swapchain.swapchain.release_pending_image() {
+            warn!("Failed to release pending depth image: {e}");
+        }
+    }
+    let index = match depth_swapchain.swapchain.acquire_image() {
+        Ok(index) => index,
+        Err(OxrError::OpenXrError(openxr::sys::Result::ERROR_SESSION_NOT_RUNNING)) => {
+            debug!("Session not running yet, skipping acquire depth image");
+            return;
+        }
+        Err(e) => {
+            error!("Failed to acquire depth image: {e}");
+            return;
+        }
+    };
+    commands.insert_resource(OxrAcquiredDepthImage {
+        index,
+        texture: &depth_swapchain.images.0[index as usize],
+    });
+}
+
+pub fn wait_depth_image(mut depth_swapchain: ResMut<OxrDepthSwapchain>) {
+    match depth_swapchain
+        .swapchain
         .wait_image(openxr::Duration::INFINITE)
-        .expect("Failed to wait image");
+    {
+        Ok(()) => {}
+        Err(OxrError::OpenXrError(openxr::sys::Result::ERROR_SESSION_NOT_RUNNING)) => {
+            debug!("Session not running yet, skipping wait depth image");
+        }
+        Err(e) => error!("Failed to wait depth image: {e}"),
+    }
+}
+
+/// Copies the depth textures of the [`XrCamera`]s into the acquired depth image.
+///
+/// Depth is only submitted if it could be copied for every view.
+pub fn copy_view_depth(
+    mut depth_swapchain: ResMut<OxrDepthSwapchain>,
+    image: Res<OxrAcquiredDepthImage>,
+    views: Query<(&ViewDepthTexture, &XrCamera, &XrProjection)>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    let mut encoder = render_device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("xr_depth_copy_encoder"),
+    });
+    let mut depth_ranges = vec![None; image.texture.depth_or_array_layers() as usize];
+    for (depth, camera, projection) in &views {
+        let Some(depth_range) = depth_ranges.get_mut(camera.0 as usize) else {
+            continue;
+        };
+        if depth.texture.sample_count() != 1
+            || depth.texture.format() != depth_swapchain.format
+            || !depth
+                .texture
+                .usage()
+                .contains(wgpu::TextureUsages::COPY_SRC)
+            || depth.texture.width() != image.texture.width()
+            || depth.texture.height() != image.texture.height()
+        {
+            warn_once!(
+                "Can't submit the depth of XrCamera {}, it needs a DepthPrepass and Msaa::Off",
+                camera.0
+            );
+            continue;
+        }
+        encoder.copy_texture_to_texture(
+            depth.texture.as_image_copy(),
+            wgpu::ImageCopyTexture {
+                texture: image.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: 0,
+                    y: 0,
+                    z: camera.0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::Extent3d {
+                width: image.texture.width(),
+                height: image.texture.height(),
+                depth_or_array_layers: 1,
+            },
+        );
+        let (near, far) = projection.depth_range();
+        // the runtime wants the distances at depth 0.0 and 1.0, which are swapped for reversed depth
+        *depth_range = Some(if projection.reversed_z {
+            (far, near)
+        } else {
+            (near, far)
+        });
+    }
+    render_queue.submit([encoder.finish()]);
+    depth_swapchain.depth_ranges = depth_ranges
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default();
+}
+
+pub fn release_depth_image(mut depth_swapchain: ResMut<OxrDepthSwapchain>, mut commands: Commands) {
+    commands.remove_resource::<OxrAcquiredDepthImage>();
+    match depth_swapchain.swapchain.release_image() {
+        Ok(()) => {}
+        Err(OxrError::OpenXrError(openxr::sys::Result::ERROR_SESSION_NOT_RUNNING)) => {
+            debug!("Session not running yet, skipping release depth image");
+        }
+        Err(e) => {
+            error!("Failed to release depth image: {e}");
+            // the depth in the swapchain can't be trusted anymore
+            depth_swapchain.depth_ranges.clear();
+        }
+    }
 }
 
 pub fn add_texture_view(
@@ -425,11 +1244,18 @@ pub fn add_texture_view(
     handle
 }
 
-pub fn begin_frame(mut frame_stream: ResMut<OxrFrameStream>) {
-    frame_stream.begin().expect("Failed to begin frame");
+pub fn begin_frame(
+    mut frame_stream: ResMut<OxrFrameStream>,
+    watchdog: Option<Res<OxrFrameWatchdog>>,
+) -> Result<(), OxrRenderError> {
+    let _guard = watchdog.map(|watchdog| watchdog.watch("begin_frame"));
+    frame_stream.begin().map_err(OxrRenderError::BeginFrame)
 }
 
-pub fn release_image(mut swapchain: ResMut<OxrSwapchain>) {
+pub fn release_image(
+    mut swapchain: ResMut<OxrSwapchain>,
+    mut commands: Commands,
+) -> Result<(), OxrRenderError> {
     #[cfg(target_os = "android")]
     {
         let ctx = ndk_context::android_context();
@@ -437,36 +1263,160 @@ pub fn release_image(mut swapchain: ResMut<OxrSwapchain>) {
         let env = vm.attach_current_thread_as_daemon();
     }
     let _span = debug_span!("xr_release_image").entered();
-    swapchain.release_image().unwrap();
+    commands.remove_resource::<OxrAcquiredSwapchainImage>();
+    swapchain
+        .release_image()
+        .map_err(OxrRenderError::ReleaseImage)
+}
+
+pub fn store_rendered_views(views: Res<OxrViews>, mut commands: Commands) {
+    commands.insert_resource(OxrRenderedViews(views.0.clone()));
+}
+
+/// Drops the layers past `max_layers`, the runtime rejects the whole frame if too many layers are submitted.
+fn clamp_layer_count<T>(layers: &mut Vec<T>, max_layers: usize) {
+    if layers.len() > max_layers {
+        warn_once!(
+            "Tried to submit {} composition layers, but the runtime only supports {}. Dropping the last {} layers.",
+            layers.len(),
+            max_layers,
+            layers.len() - max_layers
+        );
+        layers.truncate(max_layers);
+    }
 }
 
-pub fn end_frame(world: &mut World) {
+pub fn end_frame(world: &mut World) -> Result<(), OxrRenderError> {
+    let _guard = world
+        .get_resource::<OxrFrameWatchdog>()
+        .map(|watchdog| watchdog.watch("end_frame"));
     #[cfg(target_os = "android")]
     {
         let ctx = ndk_context::android_context();
         let vm = unsafe { jni::JavaVM::from_raw(ctx.vm().cast()) }.unwrap();
         let env = vm.attach_current_thread_as_daemon();
     }
-    world.resource_scope::<OxrFrameStream, ()>(|world, mut frame_stream| {
+    world.resource_scope::<OxrFrameStream, _>(|world, mut frame_stream| {
         let mut layers = vec![];
         let frame_state = world.resource::<OxrFrameState>();
         let _span = debug_span!("get layers").entered();
         if frame_state.should_render {
+            let support = world.get_resource::<OxrLayerSupport>();
             for layer in world.resource::<OxrRenderLayers>().iter() {
-                if let Some(layer) = layer.get(world) {
-                    layers.push(layer);
+                if let Some(Err(e)) = support.map(|support| support.check(layer.as_ref())) {
+                    warn_once!("Skipping composition layer: {e}");
+                    continue;
                 }
+                layers.extend(layer.layers(world));
             }
         }
         drop(_span);
+        if let Some(max_layers) = world
+            .get_resource::<OxrSystemProperties>()
+            .map(|props| props.graphics_properties.max_layer_count as usize)
+        {
+            clamp_layer_count(&mut layers, max_layers);
+        }
         let layers: Vec<_> = layers.iter().map(Box::as_ref).collect();
+        let blend_mode = world
+            .get_resource::<OxrBlendMode>()
+            .map_or(world.resource::<OxrGraphicsInfo>().blend_mode, |mode| {
+                mode.0
+            });
+        let secondary_view = world
+            .get_resource::<OxrSecondaryView>()
+            .filter(|secondary_view| secondary_view.active && frame_state.should_render)
+            .zip(secondary_view_layer(world));
         let _span = debug_span!("xr_end_frame").entered();
-        if let Err(e) = frame_stream.end(
-            frame_state.predicted_display_time,
-            world.resource::<OxrGraphicsInfo>().blend_mode,
-            &layers,
-        ) {
-            error!("Failed to end frame stream: {e}");
+        match &secondary_view {
+            Some((secondary_view, secondary_layer)) => frame_stream.end_with_secondary_view(
+                world.resource::<OxrSession>(),
+                frame_state.predicted_display_time,
+                blend_mode,
+                &layers,
+                secondary_view,
+                &[secondary_layer as &dyn CompositionLayer],
+            ),
+            None => frame_stream.end(frame_state.predicted_display_time, blend_mode, &layers),
         }
-    });
+        .map_err(OxrRenderError::EndFrame)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layers_over_the_limit_are_dropped_from_the_end() {
+        let mut layers = vec![0, 1, 2, 3];
+        clamp_layer_count(&mut layers, 2);
+        assert_eq!(layers, [0, 1]);
+    }
+
+    #[test]
+    fn layers_within_the_limit_are_kept() {
+        let mut layers = vec![0, 1];
+        clamp_layer_count(&mut layers, 2);
+        assert_eq!(layers, [0, 1]);
+        clamp_layer_count(&mut layers, 16);
+        assert_eq!(layers, [0, 1]);
+    }
+
+    const FOV: openxr::Fovf = openxr::Fovf {
+        angle_left: -std::f32::consts::FRAC_PI_4,
+        angle_right: std::f32::consts::FRAC_PI_4,
+        angle_up: std::f32::consts::FRAC_PI_4,
+        angle_down: -std::f32::consts::FRAC_PI_4,
+    };
+
+    fn depth(projection: Mat4, distance: f32) -> f32 {
+        projection.project_point3(Vec3::new(0., 0., -distance)).z
+    }
+
+    #[test]
+    fn projection_maps_the_fov_edges_to_the_clip_edges() {
+        let projection = calculate_projection(0.1, None, true, FOV, OxrGraphicsApi::Vulkan);
+        let right_top = projection.project_point3(Vec3::new(2., 2., -2.));
+        assert!(right_top.truncate().abs_diff_eq(Vec2::ONE, 1e-5));
+        let left_bottom = projection.project_point3(Vec3::new(-2., -2., -2.));
+        assert!(left_bottom.truncate().abs_diff_eq(Vec2::NEG_ONE, 1e-5));
+    }
+
+    #[test]
+    fn infinite_reversed_projection() {
+        let projection = calculate_projection(0.1, None, true, FOV, OxrGraphicsApi::Vulkan);
+        assert!((depth(projection, 0.1) - 1.).abs() < 1e-5);
+        assert!(depth(projection, 1e6) < 1e-5);
+        assert!(depth(projection, 1.) > depth(projection, 10.));
+    }
+
+    #[test]
+    fn infinite_projection() {
+        let projection = calculate_projection(0.1, None, false, FOV, OxrGraphicsApi::Vulkan);
+        assert!(depth(projection, 0.1).abs() < 1e-5);
+        assert!((depth(projection, 1e6) - 1.).abs() < 1e-5);
+        assert!(depth(projection, 1.) < depth(projection, 10.));
+    }
+
+    #[test]
+    fn finite_projection() {
+        let projection = calculate_projection(0.1, Some(100.), false, FOV, OxrGraphicsApi::Vulkan);
+        assert!(depth(projection, 0.1).abs() < 1e-5);
+        assert!((depth(projection, 100.) - 1.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn finite_reversed_projection() {
+        let projection = calculate_projection(0.1, Some(100.), true, FOV, OxrGraphicsApi::Vulkan);
+        assert!((depth(projection, 0.1) - 1.).abs() < 1e-5);
+        assert!(depth(projection, 100.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn far_plane_in_front_of_near_plane_is_infinite() {
+        let infinite = calculate_projection(0.1, None, true, FOV, OxrGraphicsApi::Vulkan);
+        let projection = calculate_projection(0.1, Some(0.05), true, FOV, OxrGraphicsApi::Vulkan);
+        assert_eq!(projection, infinite);
+    }
 }
@@ -1,9 +1,12 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 use bevy::render::extract_resource::ExtractResource;
 
 use crate::error::OxrError;
 use crate::graphics::*;
-use crate::layer_builder::{CompositionLayer, LayerProvider};
+use crate::helper_traits::{ToQuat, ToVec3};
+use crate::layer_builder::{CompositionLayer, LayerProvider, OxrLayerType};
 use crate::session::{OxrSession, OxrSessionCreateNextChain};
 use crate::types::*;
 
@@ -14,6 +17,19 @@ use crate::types::*;
 pub struct OxrEntry(pub openxr::Entry);
 
 impl OxrEntry {
+    /// Loads the OpenXR loader, linked statically on windows and dynamically everywhere else.
+    pub fn load() -> Result<OxrEntry> {
+        #[cfg(windows)]
+        let entry = OxrEntry(openxr::Entry::linked());
+        #[cfg(not(windows))]
+        let entry = OxrEntry(unsafe { openxr::Entry::load()? });
+
+        #[cfg(target_os = "android")]
+        entry.initialize_android_loader()?;
+
+        Ok(entry)
+    }
+
     /// Enumerate available extensions for this OpenXR runtime.
     pub fn enumerate_extensions(&self) -> Result<OxrExtensions> {
         Ok(self.0.enumerate_extensions().map(Into::into)?)
@@ -101,15 +117,38 @@ impl OxrInstance {
         &self.2
     }
 
+    /// Returns the name and version of the OpenXR runtime.
+    ///
+    /// Calls [`properties`](openxr::Instance::properties) internally.
+    pub fn runtime_properties(&self) -> Result<(String, openxr::Version)> {
+        let properties = self.0.properties()?;
+        Ok((properties.runtime_name, properties.runtime_version))
+    }
+
+    /// Queries the properties of `system_id`, e.g. its tracking capabilities, graphics limits and vendor ID.
+    ///
+    /// Shadows [`openxr::Instance::system_properties`], see [`OxrSystemProperties::new`].
+    pub fn system_properties(&self, system_id: openxr::SystemId) -> Result<OxrSystemProperties> {
+        OxrSystemProperties::new(self, system_id)
+    }
+
+    /// Enumerates the extensions supported by the runtime, including ones that weren't enabled for this instance.
+    pub fn available_extensions(&self) -> Result<OxrExtensions> {
+        Ok(self.0.entry().enumerate_extensions().map(Into::into)?)
+    }
+
     /// Initialize graphics. This is used to create [WgpuGraphics] for the bevy app and to get the [SessionCreateInfo] needed to make an XR session.
+    ///
+    /// `device_descriptor` can change the features and limits the render device is created with.
     pub fn init_graphics(
         &self,
         system_id: openxr::SystemId,
+        device_descriptor: Option<&OxrDeviceDescriptorFn>,
     ) -> Result<(WgpuGraphics, SessionCreateInfo)> {
         graphics_match!(
             self.1;
             _ => {
-                let (graphics, session_info) = Api::init_graphics(&self.2, self, system_id)?;
+                let (graphics, session_info) = Api::init_graphics(&self.2, self, system_id, device_descriptor)?;
 
                 Ok((graphics, SessionCreateInfo(Api::wrap(session_info))))
             }
@@ -216,8 +255,27 @@ impl OxrFrameStream {
 pub struct OxrFrameWaiter(pub openxr::FrameWaiter);
 
 /// Graphics agnostic wrapper around [openxr::Swapchain]
+///
+/// Keeps track of the [`OxrSwapchainImageState`], so calls in the wrong order,
+/// e.g. acquiring an image twice after a skipped frame, fail with `ERROR_CALL_ORDER_INVALID`
+/// instead of reaching the runtime.
 #[derive(Resource)]
-pub struct OxrSwapchain(pub GraphicsWrap<Self>);
+pub struct OxrSwapchain(pub GraphicsWrap<Self>, pub(crate) OxrSwapchainImageState);
+
+/// Where an [`OxrSwapchain`] is in the acquire, wait and release cycle of its image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OxrSwapchainImageState {
+    /// No image is acquired, the next call has to be [`acquire_image`](OxrSwapchain::acquire_image).
+    #[default]
+    Released,
+    /// An image is acquired, the next call has to be [`wait_image`](OxrSwapchain::wait_image).
+    Acquired,
+    /// The acquired image is being waited for on the thread of an [`OxrImageWaiter`],
+    /// the next call has to be [`finish_wait_image`](OxrSwapchain::finish_wait_image).
+    Waiting,
+    /// The acquired image can be written to, the next call has to be [`release_image`](OxrSwapchain::release_image).
+    Waited,
+}
 
 impl GraphicsType for OxrSwapchain {
     type Inner<G: GraphicsExt> = openxr::Swapchain<G>;
@@ -227,47 +285,115 @@ impl OxrSwapchain {
     /// Creates a new [`OxrSwapchain`] from an [`openxr::Swapchain`].
     /// In the majority of cases, you should use [`create_swapchain`](OxrSession::create_swapchain) instead.
     pub fn from_inner<G: GraphicsExt>(swapchain: openxr::Swapchain<G>) -> Self {
-        Self(G::wrap(swapchain))
+        Self(G::wrap(swapchain), default())
+    }
+
+    pub fn image_state(&self) -> OxrSwapchainImageState {
+        self.1
+    }
+
+    pub fn as_raw(&self) -> openxr::sys::Swapchain {
+        graphics_match!(
+            &self.0;
+            swap => swap.as_raw()
+        )
     }
 
     /// Determine the index of the next image to render to in the swapchain image array.
     ///
     /// Calls [`acquire_image`](openxr::Swapchain::acquire_image) internally.
     pub fn acquire_image(&mut self) -> Result<u32> {
-        graphics_match!(
+        self.check_image_state(OxrSwapchainImageState::Released)?;
+        let index = graphics_match!(
             &mut self.0;
-            swap => Ok(swap.acquire_image()?)
-        )
+            swap => swap.acquire_image()?
+        );
+        self.1 = OxrSwapchainImageState::Acquired;
+        Ok(index)
     }
 
     /// Wait for the compositor to finish reading from the oldest unwaited acquired image.
     ///
     /// Calls [`wait_image`](openxr::Swapchain::wait_image) internally.
     pub fn wait_image(&mut self, timeout: openxr::Duration) -> Result<()> {
+        self.check_image_state(OxrSwapchainImageState::Acquired)?;
         graphics_match!(
             &mut self.0;
-            swap => Ok(swap.wait_image(timeout)?)
-        )
+            swap => swap.wait_image(timeout)?
+        );
+        self.1 = OxrSwapchainImageState::Waited;
+        Ok(())
+    }
+
+    /// Starts waiting for the oldest unwaited acquired image on the thread of `waiter`.
+    ///
+    /// The swapchain can't be used until [`finish_wait_image`](Self::finish_wait_image) was called.
+    pub fn start_wait_image(&mut self, waiter: &OxrImageWaiter) -> Result<()> {
+        self.check_image_state(OxrSwapchainImageState::Acquired)?;
+        waiter.start(self.as_raw())?;
+        self.1 = OxrSwapchainImageState::Waiting;
+        Ok(())
+    }
+
+    /// Blocks until the wait started with [`start_wait_image`](Self::start_wait_image) is done.
+    pub fn finish_wait_image(&mut self, waiter: &OxrImageWaiter) -> Result<()> {
+        self.check_image_state(OxrSwapchainImageState::Waiting)?;
+        let result = waiter.finish();
+        if result.into_raw() < 0 {
+            self.1 = OxrSwapchainImageState::Acquired;
+            return Err(result.into());
+        }
+        self.1 = OxrSwapchainImageState::Waited;
+        Ok(())
     }
 
     /// Release the oldest acquired image.
     ///
     /// Calls [`release_image`](openxr::Swapchain::release_image) internally.
     pub fn release_image(&mut self) -> Result<()> {
+        self.check_image_state(OxrSwapchainImageState::Waited)?;
         graphics_match!(
             &mut self.0;
-            swap => Ok(swap.release_image()?)
-        )
+            swap => swap.release_image()?
+        );
+        self.1 = OxrSwapchainImageState::Released;
+        Ok(())
+    }
+
+    /// Waits for and releases an image that is still held from a frame that was skipped midway,
+    /// so the next [`acquire_image`](Self::acquire_image) doesn't fail.
+    pub fn release_pending_image(&mut self) -> Result<()> {
+        if self.1 == OxrSwapchainImageState::Acquired {
+            self.wait_image(openxr::Duration::INFINITE)?;
+        }
+        if self.1 == OxrSwapchainImageState::Waited {
+            self.release_image()?;
+        }
+        Ok(())
+    }
+
+    fn check_image_state(&self, expected: OxrSwapchainImageState) -> Result<()> {
+        if self.1 != expected {
+            return Err(OxrError::OpenXrError(
+                openxr::sys::Result::ERROR_CALL_ORDER_INVALID,
+            ));
+        }
+        Ok(())
     }
 
     /// Enumerates swapchain images and converts them to wgpu [`Texture`](wgpu::Texture)s.
     ///
+    /// `format`, `resolution` and `array_size` have to match the values the swapchain was created with,
+    /// and its usage flags have to include the [`swapchain_usage_flags`] for `usage`.
+    ///
     /// Calls [`enumerate_images`](openxr::Swapchain::enumerate_images) internally.
     pub fn enumerate_images(
         &self,
         device: &wgpu::Device,
         format: wgpu::TextureFormat,
         resolution: UVec2,
+        array_size: u32,
+        usage: wgpu::TextureUsages,
     ) -> Result<OxrSwapchainImages> {
         graphics_match!(
             &self.0;
@@ -275,7 +401,7 @@ impl OxrSwapchain {
                 let mut images = vec![];
                 for image in swap.enumerate_images()? {
                     unsafe {
-                        images.push(Api::to_wgpu_img(image, device, format, resolution)?);
+                        images.push(Api::to_wgpu_img(image, device, format, resolution, array_size, usage)?);
                     }
                 }
                 Ok(OxrSwapchainImages(images.leak()))
@@ -288,6 +414,51 @@ impl OxrSwapchain {
 #[derive(Debug, Deref, Resource, Clone, Copy, ExtractResource)]
 pub struct OxrSwapchainImages(pub &'static [wgpu::Texture]);
 
+/// The swapchain image acquired for the current frame. Only exists in the render world.
+///
+/// This is inserted in [`OxrRenderStep::AcquireImage`](crate::render::OxrRenderStep::AcquireImage) as soon as the image is acquired,
+/// which is before it has been waited on, and removed in [`OxrRenderStep::ReleaseImage`](crate::render::OxrRenderStep::ReleaseImage).
+/// The texture can only be written to once the wait finished: after [`OxrRenderStep::WaitImage`](crate::render::OxrRenderStep::WaitImage),
+/// or with [`OxrRenderPlugin::wait_image_off_thread`](crate::render::OxrRenderPlugin::wait_image_off_thread) only right before the render graph runs.
+/// Render graph nodes are always safe to write to it.
+/// Writing to it outside of that window is undefined behavior as far as the runtime is concerned.
+///
+/// The texture is an array texture with one layer per view.
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct OxrAcquiredSwapchainImage {
+    /// Index of the image in [`OxrSwapchainImages`].
+    pub index: u32,
+    pub texture: &'static wgpu::Texture,
+}
+
+/// The depth swapchain submitted with the projection layer through `XR_KHR_composition_layer_depth`,
+/// which lets the runtime use the scene depth for reprojection. Only exists in the render world.
+///
+/// This is only created if the extension is enabled and the runtime supports the depth format bevy renders with.
+/// The depth of every [`XrCamera`](bevy_mod_xr::camera::XrCamera) is copied into it after rendering,
+/// which requires the camera's depth texture to be copyable and not multisampled,
+/// e.g. by adding a [`DepthPrepass`](bevy::core_pipeline::prepass::DepthPrepass) and setting
+/// [`OxrInitPlugin::msaa`](crate::init::OxrInitPlugin::msaa) to [`OxrMsaa::Off`].
+#[derive(Resource)]
+pub struct OxrDepthSwapchain {
+    pub swapchain: OxrSwapchain,
+    pub images: OxrSwapchainImages,
+    pub format: wgpu::TextureFormat,
+    /// The distances depth 0.0 and 1.0 map to for every view of the last released image.
+    /// Empty if no depth was written, in which case no depth is submitted.
+    pub(crate) depth_ranges: Vec<(f32, f32)>,
+}
+
+/// The depth swapchain image acquired for the current frame. Only exists in the render world.
+///
+/// Like [`OxrAcquiredSwapchainImage`], but for the [`OxrDepthSwapchain`].
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct OxrAcquiredDepthImage {
+    /// Index of the image in the [`OxrDepthSwapchain`]'s images.
+    pub index: u32,
+    pub texture: &'static wgpu::Texture,
+}
+
 /// Thread safe wrapper around [openxr::Space] representing the stage.
 // #[derive(Deref, Clone, Resource)]
 // pub struct OxrStage(pub Arc<openxr::Space>);
@@ -296,10 +467,317 @@ pub struct OxrSwapchainImages(pub &'static [wgpu::Texture]);
 #[derive(Clone, Resource, ExtractResource, Deref, DerefMut, Default)]
 pub struct OxrViews(pub Vec<openxr::View>);
 
+impl OxrViews {
+    /// The interpupillary distance in meters, the distance between the positions of the first two views.
+    ///
+    /// Returns 0.0 for a single view, e.g. on handheld devices.
+    pub fn ipd(&self) -> f32 {
+        match self.0.as_slice() {
+            [left, right, ..] => left
+                .pose
+                .position
+                .to_vec3()
+                .distance(right.pose.position.to_vec3()),
+            _ => 0.0,
+        }
+    }
+
+    /// The pose halfway between the first two views, or the pose of the only view.
+    ///
+    /// Like the views themselves, the pose is relative to the [`XrPrimaryReferenceSpace`](bevy_mod_xr::spaces::XrPrimaryReferenceSpace).
+    pub fn midpoint(&self) -> Option<Isometry3d> {
+        match self.0.as_slice() {
+            [left, right, ..] => Some(Isometry3d::new(
+                left.pose
+                    .position
+                    .to_vec3()
+                    .lerp(right.pose.position.to_vec3(), 0.5),
+                left.pose
+                    .orientation
+                    .to_quat()
+                    .slerp(right.pose.orientation.to_quat(), 0.5),
+            )),
+            [view] => Some(Isometry3d::new(
+                view.pose.position.to_vec3(),
+                view.pose.orientation.to_quat(),
+            )),
+            [] => None,
+        }
+    }
+}
+
+/// Time added to the predicted display time the views are located at, in both the main and the render world.
+///
+/// Can be negative to predict less far ahead. Tune this against the latency of a specific runtime,
+/// the default of zero uses the runtime's prediction as is.
+/// The resulting time is clamped to at least 1ns, because OpenXR rejects times of zero or less with `XR_ERROR_TIME_INVALID`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Resource, ExtractResource)]
+pub struct OxrViewPredictionBias(pub openxr::Duration);
+
+impl Default for OxrViewPredictionBias {
+    fn default() -> Self {
+        Self(openxr::Duration::from_nanos(0))
+    }
+}
+
+impl OxrViewPredictionBias {
+    /// Adds the bias to `time`, clamped to a positive time.
+    pub fn apply(&self, time: openxr::Time) -> openxr::Time {
+        openxr::Time::from_nanos(time.as_nanos().saturating_add(self.0.as_nanos()).max(1))
+    }
+}
+
+/// The views the last rendered frame was rendered with. Only exists in the render world.
+///
+/// While [`OxrFreezeFrame`] skips rendering, the projection layer is submitted with these instead of [`OxrViews`],
+/// so the runtime can reproject the last rendered image to the current head pose.
+#[derive(Clone, Resource, Deref, DerefMut, Default)]
+pub struct OxrRenderedViews(pub Vec<openxr::View>);
+
+/// Renders the XR views only when requested, for content that doesn't change, like a 360 photo viewer.
+///
+/// While frozen, the XR cameras are deactivated and no swapchain image is acquired.
+/// The image rendered last is submitted every frame instead and the runtime reprojects it for head motion.
+/// Call [`mark_dirty`](Self::mark_dirty) whenever the content changed to render a single new frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Resource, ExtractResource)]
+pub struct OxrFreezeFrame {
+    enabled: bool,
+    dirty: bool,
+}
+
+impl OxrFreezeFrame {
+    /// Renders one more frame and then stops rendering the views.
+    pub fn freeze(&mut self) {
+        self.enabled = true;
+        self.dirty = true;
+    }
+
+    /// Goes back to rendering the views every frame.
+    pub fn unfreeze(&mut self) {
+        self.enabled = false;
+    }
+
+    /// Renders a single new frame while frozen.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns true if the views should be rendered this frame.
+    pub fn should_render_views(&self) -> bool {
+        !self.enabled || self.dirty
+    }
+
+    pub(crate) fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+}
+
+/// Calls `xrWaitSwapchainImage` on a dedicated thread, so the render world can keep preparing the frame
+/// while the compositor still reads from the acquired image.
+///
+/// Inserted into the render world when [`OxrRenderPlugin::wait_image_off_thread`](crate::render::OxrRenderPlugin::wait_image_off_thread)
+/// is set. Only one wait can be in flight at a time.
+#[derive(Resource)]
+pub struct OxrImageWaiter {
+    requests: std::sync::mpsc::Sender<openxr::sys::Swapchain>,
+    results: std::sync::Mutex<std::sync::mpsc::Receiver<openxr::sys::Result>>,
+}
+
+impl OxrImageWaiter {
+    /// Spawns the thread waiting for the images.
+    pub fn new(instance: OxrInstance) -> std::io::Result<Self> {
+        let (requests, request_receiver) = std::sync::mpsc::channel::<openxr::sys::Swapchain>();
+        let (result_sender, results) = std::sync::mpsc::channel();
+        std::thread::Builder::new()
+            .name("xr_image_waiter".into())
+            .spawn(move || {
+                for swapchain in request_receiver {
+                    let info = openxr::sys::SwapchainImageWaitInfo {
+                        ty: openxr::sys::SwapchainImageWaitInfo::TYPE,
+                        next: std::ptr::null(),
+                        timeout: openxr::Duration::INFINITE,
+                    };
+                    let result = unsafe { (instance.fp().wait_swapchain_image)(swapchain, &info) };
+                    if result_sender.send(result).is_err() {
+                        break;
+                    }
+                }
+            })?;
+        Ok(Self {
+            requests,
+            results: std::sync::Mutex::new(results),
+        })
+    }
+
+    fn start(&self, swapchain: openxr::sys::Swapchain) -> Result<()> {
+        self.requests
+            .send(swapchain)
+            .map_err(|_| OxrError::OpenXrError(openxr::sys::Result::ERROR_RUNTIME_FAILURE))
+    }
+
+    fn finish(&self) -> openxr::sys::Result {
+        let _span = debug_span!("xr_finish_wait_image").entered();
+        self.results
+            .lock()
+            .ok()
+            .and_then(|results| results.recv().ok())
+            .unwrap_or(openxr::sys::Result::ERROR_RUNTIME_FAILURE)
+    }
+}
+
 /// Wrapper around [openxr::SystemId] to allow it to be stored as a resource.
 #[derive(Debug, Copy, Clone, Deref, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Resource)]
 pub struct OxrSystemId(pub openxr::SystemId);
 
+/// The graphics API wgpu renders through.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum OxrGraphicsApi {
+    Vulkan,
+    D3D12,
+    Gl,
+    Metal,
+}
+
+impl OxrGraphicsApi {
+    /// Returns [`None`] for backends that can't be used with OpenXR.
+    pub fn from_wgpu(backend: wgpu::Backend) -> Option<Self> {
+        match backend {
+            wgpu::Backend::Vulkan => Some(Self::Vulkan),
+            wgpu::Backend::Dx12 => Some(Self::D3D12),
+            wgpu::Backend::Gl => Some(Self::Gl),
+            wgpu::Backend::Metal => Some(Self::Metal),
+            _ => None,
+        }
+    }
+
+    /// Whether positive Y points down in the clip space shaders write to.
+    ///
+    /// wgpu exposes a Y up clip space on every API and flips it for Vulkan when translating shaders.
+    pub fn clip_space_y_down(self) -> bool {
+        match self {
+            Self::Vulkan | Self::D3D12 | Self::Gl | Self::Metal => false,
+        }
+    }
+
+    /// Whether the clip space depth range is `[-1, 1]` rather than `[0, 1]`.
+    ///
+    /// wgpu exposes a `[0, 1]` depth range on every API and remaps it for OpenGL when translating shaders.
+    pub fn clip_space_z_negative_one_to_one(self) -> bool {
+        match self {
+            Self::Vulkan | Self::D3D12 | Self::Gl | Self::Metal => false,
+        }
+    }
+}
+
+/// The graphics API and adapter negotiated with the runtime during initialization.
+///
+/// Inserted into both the main and render world.
+#[derive(Debug, Clone, Resource)]
+pub struct OxrGraphicsBackend {
+    pub api: OxrGraphicsApi,
+    /// The OpenXR graphics binding the session is created with.
+    pub backend: GraphicsBackend,
+    pub adapter_info: wgpu::AdapterInfo,
+}
+
+/// Wrapper around [`openxr::SystemProperties`] to allow it to be stored as a resource.
+///
+/// Contains the properties of the system the current [`OxrInstance`] was created for, e.g. the maximum number of composition layers the runtime supports.
+/// Useful for per device workarounds and telemetry.
+#[derive(Debug, Clone, Deref, Resource)]
+pub struct OxrSystemProperties {
+    #[deref]
+    pub properties: openxr::SystemProperties,
+    /// Unique identifier of the headset model.
+    ///
+    /// Requires [`XR_META_headset_id`](https://registry.khronos.org/OpenXR/specs/1.0/html/xrspec.html#XR_META_headset_id), [`None`] otherwise.
+    pub headset_id: Option<[u8; 16]>,
+}
+
+impl OxrSystemProperties {
+    /// Queries the properties of `system_id`.
+    ///
+    /// Calls [`system_properties`](openxr::Instance::system_properties) internally.
+    pub fn new(instance: &OxrInstance, system_id: openxr::SystemId) -> Result<Self> {
+        let properties = instance.0.system_properties(system_id)?;
+        let headset_id = if instance.exts().meta_headset_id.is_some() {
+            unsafe {
+                let mut headset_id = openxr::sys::SystemHeadsetIdPropertiesMETA {
+                    ty: openxr::sys::SystemHeadsetIdPropertiesMETA::TYPE,
+                    ..std::mem::zeroed()
+                };
+                let mut props = openxr::sys::SystemProperties::out(&mut headset_id as *mut _ as _);
+                let result = (instance.fp().get_system_properties)(
+                    instance.as_raw(),
+                    system_id,
+                    props.as_mut_ptr(),
+                );
+                if result.into_raw() < 0 {
+                    return Err(result.into());
+                }
+                Some(headset_id.id.data)
+            }
+        } else {
+            None
+        };
+        Ok(Self {
+            properties,
+            headset_id,
+        })
+    }
+
+    /// Name of the system, e.g. the headset model. Can be empty.
+    pub fn system_name(&self) -> &str {
+        &self.properties.system_name
+    }
+
+    /// Vendor ID of the system, usually the PCI vendor ID of the headset manufacturer.
+    pub fn vendor_id(&self) -> u32 {
+        self.properties.vendor_id
+    }
+
+    /// Whether the system can track the orientation of the headset.
+    pub fn orientation_tracking(&self) -> bool {
+        self.properties.tracking_properties.orientation_tracking
+    }
+
+    /// Whether the system can track the position of the headset.
+    pub fn position_tracking(&self) -> bool {
+        self.properties.tracking_properties.position_tracking
+    }
+
+    /// Largest swapchain image the runtime can create.
+    pub fn max_swapchain_image_size(&self) -> UVec2 {
+        let graphics = &self.properties.graphics_properties;
+        UVec2::new(
+            graphics.max_swapchain_image_width,
+            graphics.max_swapchain_image_height,
+        )
+    }
+
+    /// Maximum number of composition layers the runtime accepts per frame.
+    pub fn max_layer_count(&self) -> u32 {
+        self.properties.graphics_properties.max_layer_count
+    }
+}
+
+/// The OpenXR runtime and the system it runs on, queried once at startup.
+///
+/// Meant for bug reports and per runtime workarounds, e.g. `info.runtime_name.starts_with("SteamVR")`.
+/// Exists in both the main and render world.
+#[derive(Debug, Clone, Resource)]
+pub struct OxrSystemInfo {
+    /// Name of the runtime, as reported by `xrGetInstanceProperties`.
+    pub runtime_name: String,
+    pub runtime_version: openxr::Version,
+    /// Also available as its own resource.
+    pub system: OxrSystemProperties,
+}
+
 /// Wrapper around [`openxr::Passthrough`].
 ///
 /// Used to [`start`](openxr::Passthrough::start) or [`pause`](openxr::Passthrough::pause) passthrough on the physical device.
@@ -320,6 +798,112 @@ impl OxrPassthrough {
     }
 }
 
+/// How the [`XrCamera`](bevy_mod_xr::camera::XrCamera)s clear the swapchain images before rendering.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Resource)]
+pub enum OxrClearMode {
+    /// Clear to transparent black if the environment shows through, i.e. with an alpha blend or additive
+    /// [`EnvironmentBlendMode`] or while [`OxrPassthroughEnabled`] is set, otherwise clear to the [`ClearColor`] made opaque.
+    /// Cameras with their own [`ClearColorConfig`] are left alone.
+    #[default]
+    Auto,
+    /// Cameras use their [`ClearColorConfig`] as is.
+    Manual,
+}
+
+/// Whether the [`Skybox`](bevy::core_pipeline::Skybox) of the [`XrCamera`](bevy_mod_xr::camera::XrCamera)s is rendered.
+///
+/// A skybox covers the transparent background, so it would hide the real world in mixed reality.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Resource)]
+pub enum OxrSkyboxPolicy {
+    /// Hide the skybox while the environment shows through, i.e. with an alpha blend or additive
+    /// [`EnvironmentBlendMode`] or while [`OxrPassthroughEnabled`] is set, and show it otherwise.
+    #[default]
+    Auto,
+    /// Always render the skybox, e.g. for a virtual window into another world.
+    Show,
+    /// Never render the skybox.
+    Hide,
+}
+
+/// How the [`XrCamera`](bevy_mod_xr::camera::XrCamera)s are adjusted while the [`OxrBlendMode`] is additive,
+/// e.g. on HoloLens or Magic Leap, where black is transparent and every other color is added to the real world.
+///
+/// Anything that lifts black pixels shows up as a haze over the environment, so those post processing effects
+/// are turned off and restored once the blend mode isn't additive anymore.
+/// Cameras with [`OxrKeepPostProcessing`](crate::render::OxrKeepPostProcessing) are left alone.
+/// The clear color is handled by [`OxrClearMode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Resource)]
+pub struct OxrAdditiveDisplaySettings {
+    /// Sets the [`Tonemapping`](bevy::core_pipeline::tonemapping::Tonemapping) to `None`.
+    pub disable_tonemapping: bool,
+    /// Sets the [`DebandDither`](bevy::core_pipeline::tonemapping::DebandDither) to `Disabled`,
+    /// dithering adds noise to black pixels.
+    pub disable_deband_dither: bool,
+    /// Removes the [`Bloom`](bevy::core_pipeline::bloom::Bloom), which lifts the blacks around bright pixels.
+    pub disable_bloom: bool,
+}
+
+impl Default for OxrAdditiveDisplaySettings {
+    fn default() -> Self {
+        Self {
+            disable_tonemapping: true,
+            disable_deband_dither: true,
+            disable_bloom: true,
+        }
+    }
+}
+
+/// Whether passthrough should be shown behind the app, toggle it to start or pause passthrough at runtime.
+///
+/// The camera feed only shows through where the app renders transparent pixels,
+/// so the [`ClearColor`] of the [`XrCamera`](bevy_mod_xr::camera::XrCamera)s needs an alpha of 0.
+#[derive(Clone, Copy, Debug, Deref, DerefMut, Resource, ExtractResource)]
+pub struct OxrPassthroughEnabled(pub bool);
+
+impl Default for OxrPassthroughEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// The look of the passthrough layer, applied whenever it changes.
+///
+/// Set it back to its default to reset the style, which shows the unmodified camera feed.
+#[derive(Clone, Debug, PartialEq, Resource, ExtractResource)]
+pub struct OxrPassthroughStyle {
+    /// Opacity of the camera feed, from 0.0 to 1.0.
+    pub texture_opacity_factor: f32,
+    /// Color edges detected in the camera feed are highlighted with, fully transparent to disable edge rendering.
+    pub edge_color: Color,
+    pub color_map: Option<OxrPassthroughColorMap>,
+}
+
+impl Default for OxrPassthroughStyle {
+    fn default() -> Self {
+        Self {
+            texture_opacity_factor: 1.0,
+            edge_color: Color::NONE,
+            color_map: None,
+        }
+    }
+}
+
+/// A color lookup table applied to the passthrough camera feed, see [`OxrPassthroughStyle`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum OxrPassthroughColorMap {
+    /// Maps each of the 256 brightness levels of the camera feed to a color.
+    MonoToRgba(Box<[Color; 256]>),
+    /// Maps each of the 256 brightness levels of the camera feed to another brightness level.
+    MonoToMono(Box<[u8; 256]>),
+    /// Adjusts the camera feed. `brightness` ranges from -100.0 to 100.0 with 0.0 as neutral,
+    /// `contrast` and `saturation` are factors with 1.0 as neutral.
+    BrightnessContrastSaturation {
+        brightness: f32,
+        contrast: f32,
+        saturation: f32,
+    },
+}
+
 /// Wrapper around [`openxr::Passthrough`].
 ///
 /// Used to create a [`CompositionLayerPassthrough`](crate::layer_builder::CompositionLayerPassthrough), and to [`pause`](openxr::PassthroughLayer::pause) or [`resume`](openxr::PassthroughLayer::resume) rendering of the passthrough layer.
@@ -328,15 +912,95 @@ impl OxrPassthrough {
 #[derive(Resource, Deref, DerefMut)]
 pub struct OxrPassthroughLayer(pub openxr::PassthroughLayer);
 
+/// The composition layers submitted every frame in [`end_frame`](crate::render::end_frame).
+///
+/// Layers are composited in order, so the first layer is the furthest back.
+/// If there are more layers than the runtime supports (see [`OxrSystemProperties`]),
+/// the layers at the end of this list are dropped first, so put the layers that are most important to keep at the front.
 #[derive(Resource, Deref, DerefMut, Default)]
 pub struct OxrRenderLayers(pub Vec<Box<dyn LayerProvider + Send + Sync>>);
 
+impl OxrRenderLayers {
+    /// Adds a layer to the end of the list if its [`layer_type`](LayerProvider::layer_type) is supported.
+    pub fn try_push(
+        &mut self,
+        layer: Box<dyn LayerProvider + Send + Sync>,
+        support: &OxrLayerSupport,
+    ) -> Result<()> {
+        support.check(layer.as_ref())?;
+        self.0.push(layer);
+        Ok(())
+    }
+
+    /// Inserts a layer at `index` if its [`layer_type`](LayerProvider::layer_type) is supported.
+    pub fn try_insert(
+        &mut self,
+        index: usize,
+        layer: Box<dyn LayerProvider + Send + Sync>,
+        support: &OxrLayerSupport,
+    ) -> Result<()> {
+        support.check(layer.as_ref())?;
+        self.0.insert(index, layer);
+        Ok(())
+    }
+}
+
+/// The composition layer types that can be used with the current runtime and enabled extensions.
+///
+/// Use this to pick fallbacks up front, e.g. a quad layer when cylinder layers aren't supported.
+#[derive(Resource, Clone, Debug)]
+pub struct OxrLayerSupport(Vec<OxrLayerType>);
+
+impl OxrLayerSupport {
+    pub fn new(enabled_exts: &OxrExtensions) -> Self {
+        Self(
+            OxrLayerType::ALL
+                .into_iter()
+                .filter(|ty| ty.is_enabled(enabled_exts))
+                .collect(),
+        )
+    }
+
+    pub fn is_supported(&self, ty: OxrLayerType) -> bool {
+        self.0.contains(&ty)
+    }
+
+    /// Returns all supported layer types.
+    pub fn supported(&self) -> &[OxrLayerType] {
+        &self.0
+    }
+
+    /// Returns an error if the layer type of `layer` isn't supported.
+    pub fn check(&self, layer: &dyn LayerProvider) -> Result<()> {
+        match layer.layer_type() {
+            Some(ty) if !self.is_supported(ty) => Err(OxrError::UnsupportedLayerType(ty)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The environment blend mode frames are submitted with, e.g. to switch between VR and MR at runtime.
+///
+/// Inserted with the blend mode picked from [`OxrInitPlugin::blend_modes`](crate::init::OxrInitPlugin::blend_modes)
+/// when the session is created. Changing it to a blend mode the runtime doesn't support is reverted with an error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deref, DerefMut, Resource, ExtractResource)]
+pub struct OxrBlendMode(pub EnvironmentBlendMode);
+
 /// Resource storing graphics info for the currently running session.
 #[derive(Clone, Copy, Resource, ExtractResource)]
 pub struct OxrGraphicsInfo {
     pub blend_mode: EnvironmentBlendMode,
     pub resolution: UVec2,
     pub format: wgpu::TextureFormat,
+    /// The view configuration the session was started with.
+    pub view_configuration_type: openxr::ViewConfigurationType,
+    /// The number of views in [`view_configuration_type`](Self::view_configuration_type), one [`XrCamera`](bevy_mod_xr::camera::XrCamera) is spawned per view.
+    pub view_count: u32,
+    /// The multisampling the spawned [`XrCamera`](bevy_mod_xr::camera::XrCamera)s use,
+    /// [`OxrMsaa::Off`] if the requested one isn't supported by the runtime.
+    pub msaa: OxrMsaa,
+    /// Usages of the swapchain textures, see [`OxrInitPlugin::swapchain_usage`](crate::init::OxrInitPlugin::swapchain_usage).
+    pub swapchain_usage: wgpu::TextureUsages,
 }
 
 #[derive(Clone)]
@@ -344,10 +1008,16 @@ pub struct OxrGraphicsInfo {
 pub struct SessionConfigInfo {
     /// List of blend modes the openxr session can use. If [None], pick the first available blend mode.
     pub blend_modes: Option<Vec<EnvironmentBlendMode>>,
-    /// List of formats the openxr session can use. If [None], pick the first available format
+    /// List of view configurations the openxr session can use. If [None], pick the first available view configuration.
+    pub view_configuration_types: Option<Vec<openxr::ViewConfigurationType>>,
+    /// Swapchain formats the openxr session can use, in order of preference. If [None], pick the runtime's preferred format.
     pub formats: Option<Vec<wgpu::TextureFormat>>,
     /// List of resolutions that the openxr swapchain can use. If [None] pick the first available resolution.
     pub resolutions: Option<Vec<UVec2>>,
+    /// The multisampling the XR cameras should use.
+    pub msaa: OxrMsaa,
+    /// Usages of the swapchain textures the XR cameras render to.
+    pub swapchain_usage: wgpu::TextureUsages,
     /// Graphics info used to create a session.
     pub graphics_info: SessionCreateInfo,
 }
@@ -356,9 +1026,44 @@ pub struct SessionConfigInfo {
 pub struct OxrSessionStarted(pub bool);
 
 /// The frame state returned from [FrameWaiter::wait_frame](openxr::FrameWaiter::wait)
+///
+/// `should_render` is also false while the session isn't visible, see [`OxrSkipRenderingWhenHidden`].
 #[derive(Clone, Deref, DerefMut, Resource, ExtractResource)]
 pub struct OxrFrameState(pub openxr::FrameState);
 
+/// Whether rendering is skipped while the [`XrSessionVisibility`](bevy_mod_xr::session::XrSessionVisibility)
+/// is `Hidden`, e.g. while the session is synchronized but not shown yet, or the headset was taken off.
+///
+/// The frame loop keeps waiting, beginning and ending frames, but the [`XrCamera`](bevy_mod_xr::camera::XrCamera)s
+/// are deactivated, no swapchain image is acquired and no layers are submitted, which saves battery and heat
+/// on standalone headsets. Enabled by default, set it to false to render whenever the runtime asks for it.
+#[derive(Clone, Copy, Debug, Deref, DerefMut, Resource)]
+pub struct OxrSkipRenderingWhenHidden(pub bool);
+
+impl Default for OxrSkipRenderingWhenHidden {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Timing of the frame returned by the latest [`wait_frame`](crate::render::wait_frame), for timing animations
+/// to when the frame will actually be shown.
+///
+/// When [`Pipelined`] rendering is enabled the frame is rendered while the next one is already being simulated,
+/// so the simulation runs one `predicted_display_period` ahead of `predicted_display_time`.
+/// The tracking systems in this crate account for that, app code using these values should do the same.
+#[derive(Clone, Copy, Debug, Resource)]
+pub struct OxrFrameTiming {
+    pub predicted_display_time: openxr::Time,
+    pub predicted_display_period: openxr::Duration,
+    /// Time between the predicted display times of the previous and the current frame.
+    /// [`Duration::ZERO`] for the first frame of a session.
+    pub delta: Duration,
+    /// If the runtime shows this frame to the user. When it doesn't, e.g. because the app is occluded,
+    /// the cameras aren't rendered and the frame is ended without composition layers.
+    pub should_render: bool,
+}
+
 /// Instructs systems to add display period
 #[derive(Clone, Copy, Default, Resource)]
 pub struct Pipelined;
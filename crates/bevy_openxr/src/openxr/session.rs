@@ -1,12 +1,16 @@
 use std::ffi::c_void;
+use std::ptr;
 
 use crate::next_chain::{OxrNextChain, OxrNextChainStructBase, OxrNextChainStructProvider};
-use crate::resources::{OxrPassthrough, OxrPassthroughLayer, OxrSwapchain};
-use crate::types::{Result, SwapchainCreateInfo};
+use crate::resources::{
+    OxrPassthrough, OxrPassthroughColorMap, OxrPassthroughLayer, OxrPassthroughStyle, OxrSwapchain,
+};
+use crate::types::{swapchain_usage_flags, Result, SwapchainCreateInfo};
 use bevy::prelude::*;
 use openxr::AnyGraphics;
 
 use crate::graphics::{graphics_match, GraphicsExt, GraphicsType, GraphicsWrap};
+use crate::helper_traits::ToDuration;
 
 /// Graphics agnostic wrapper around [openxr::Session].
 ///
@@ -48,11 +52,19 @@ impl OxrSession {
 
     /// Enumerates all available swapchain formats and converts them to wgpu's [`TextureFormat`](wgpu::TextureFormat).
     ///
+    /// Formats that don't convert to a wgpu format and back are left out, since swapchains can't be created or imported with them.
+    /// The runtime's order of preference is kept.
+    ///
     /// Calls [`enumerate_swapchain_formats`](openxr::Session::enumerate_swapchain_formats) internally.
     pub fn enumerate_swapchain_formats(&self) -> Result<Vec<wgpu::TextureFormat>> {
         graphics_match!(
             &self.1;
-            session => Ok(session.enumerate_swapchain_formats()?.into_iter().filter_map(Api::into_wgpu_format).collect())
+            session => Ok(session
+                .enumerate_swapchain_formats()?
+                .into_iter()
+                .filter_map(Api::into_wgpu_format)
+                .filter(|format| Api::from_wgpu_format(*format).is_some())
+                .collect())
         )
     }
 
@@ -60,10 +72,36 @@ impl OxrSession {
     ///
     /// Calls [`create_swapchain`](openxr::Session::create_swapchain) internally.
     pub fn create_swapchain(&self, info: SwapchainCreateInfo) -> Result<OxrSwapchain> {
-        Ok(OxrSwapchain(graphics_match!(
-            &self.1;
-            session => session.create_swapchain(&info.try_into()?)? => OxrSwapchain
-        )))
+        Ok(OxrSwapchain(
+            graphics_match!(
+                &self.1;
+                session => session.create_swapchain(&info.try_into()?)? => OxrSwapchain
+            ),
+            default(),
+        ))
+    }
+
+    /// Creates a single sample [`OxrSwapchain`] with one array layer and mip level, e.g. for a quad layer.
+    ///
+    /// `usage` is converted with [`swapchain_usage_flags`],
+    /// pass the same `usage` to [`enumerate_images`](OxrSwapchain::enumerate_images).
+    pub fn create_simple_swapchain(
+        &self,
+        resolution: UVec2,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+    ) -> Result<OxrSwapchain> {
+        self.create_swapchain(SwapchainCreateInfo {
+            create_flags: openxr::SwapchainCreateFlags::EMPTY,
+            usage_flags: swapchain_usage_flags(usage, format),
+            format,
+            sample_count: 1,
+            width: resolution.x,
+            height: resolution.y,
+            face_count: 1,
+            array_size: 1,
+            mip_count: 1,
+        })
     }
 
     /// Creates a passthrough.
@@ -96,6 +134,116 @@ impl OxrSession {
             session => session.create_passthrough_layer(&passthrough.0, passthrough.1, purpose)?
         }))
     }
+
+    /// Sets the opacity, edge color and color map of a passthrough layer.
+    ///
+    /// Requires [`XR_FB_passthrough`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_FB_passthrough).
+    pub fn set_passthrough_style(
+        &self,
+        layer: &OxrPassthroughLayer,
+        style: &OxrPassthroughStyle,
+    ) -> Result<()> {
+        let fp = self
+            .instance()
+            .exts()
+            .fb_passthrough
+            .as_ref()
+            .ok_or(openxr::sys::Result::ERROR_EXTENSION_NOT_PRESENT)?;
+        let to_color4f = |color: Color| {
+            let color = color.to_linear();
+            openxr::sys::Color4f {
+                r: color.red,
+                g: color.green,
+                b: color.blue,
+                a: color.alpha,
+            }
+        };
+        // only one of these is chained, depending on the color map
+        let mono_to_rgba;
+        let mono_to_mono;
+        let brightness_contrast_saturation;
+        let next: *const c_void = match &style.color_map {
+            None => ptr::null(),
+            Some(OxrPassthroughColorMap::MonoToRgba(colors)) => {
+                mono_to_rgba = openxr::sys::PassthroughColorMapMonoToRgbaFB {
+                    ty: openxr::sys::PassthroughColorMapMonoToRgbaFB::TYPE,
+                    next: ptr::null(),
+                    texture_color_map: colors.map(to_color4f),
+                };
+                &mono_to_rgba as *const _ as _
+            }
+            Some(OxrPassthroughColorMap::MonoToMono(values)) => {
+                mono_to_mono = openxr::sys::PassthroughColorMapMonoToMonoFB {
+                    ty: openxr::sys::PassthroughColorMapMonoToMonoFB::TYPE,
+                    next: ptr::null(),
+                    texture_color_map: **values,
+                };
+                &mono_to_mono as *const _ as _
+            }
+            Some(OxrPassthroughColorMap::BrightnessContrastSaturation {
+                brightness,
+                contrast,
+                saturation,
+            }) => {
+                brightness_contrast_saturation =
+                    openxr::sys::PassthroughBrightnessContrastSaturationFB {
+                        ty: openxr::sys::PassthroughBrightnessContrastSaturationFB::TYPE,
+                        next: ptr::null(),
+                        brightness: *brightness,
+                        contrast: *contrast,
+                        saturation: *saturation,
+                    };
+                &brightness_contrast_saturation as *const _ as _
+            }
+        };
+        let info = openxr::sys::PassthroughStyleFB {
+            ty: openxr::sys::PassthroughStyleFB::TYPE,
+            next,
+            texture_opacity_factor: style.texture_opacity_factor,
+            edge_color: to_color4f(style.edge_color),
+        };
+        let result = unsafe { (fp.passthrough_layer_set_style)(layer.0.as_raw(), &info) };
+        if result.into_raw() < 0 {
+            return Err(result.into());
+        }
+        Ok(())
+    }
+
+    /// Vibrates the device `action` is bound to.
+    ///
+    /// `amplitude` ranges from 0.0 to 1.0. Pass [`openxr::Duration::MIN_HAPTIC`] as `duration` for the shortest pulse
+    /// the device supports and [`openxr::FREQUENCY_UNSPECIFIED`] as `frequency` to let the runtime pick the frequency.
+    /// Use [`openxr::Path::NULL`] as `subaction_path` to apply the feedback to all devices the action is bound to.
+    ///
+    /// Calls [`apply_feedback`](openxr::Action::apply_feedback) internally.
+    pub fn apply_haptic_feedback(
+        &self,
+        action: &openxr::Action<openxr::Haptic>,
+        subaction_path: openxr::Path,
+        amplitude: f32,
+        duration: impl ToDuration,
+        frequency: f32,
+    ) -> Result<()> {
+        Ok(action.apply_feedback(
+            &self.0,
+            subaction_path,
+            &openxr::HapticVibration::new()
+                .amplitude(amplitude)
+                .duration(duration.to_duration())
+                .frequency(frequency),
+        )?)
+    }
+
+    /// Stops any haptic feedback running on the device `action` is bound to.
+    ///
+    /// Calls [`stop_feedback`](openxr::Action::stop_feedback) internally.
+    pub fn stop_haptic_feedback(
+        &self,
+        action: &openxr::Action<openxr::Haptic>,
+        subaction_path: openxr::Path,
+    ) -> Result<()> {
+        Ok(action.stop_feedback(&self.0, subaction_path)?)
+    }
 }
 
 pub trait OxrSessionCreateNextProvider: OxrNextChainStructProvider {}
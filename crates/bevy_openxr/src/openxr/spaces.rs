@@ -5,7 +5,7 @@ use bevy_mod_xr::{
     session::{XrFirst, XrHandleEvents},
     spaces::{
         XrDestroySpace, XrPrimaryReferenceSpace, XrReferenceSpace, XrSpace, XrSpaceLocationFlags,
-        XrSpaceVelocityFlags, XrVelocity,
+        XrSpaceVelocityFlags, XrSpatialAnchor, XrTrackVelocity, XrVelocity,
     },
 };
 use openxr::{
@@ -14,16 +14,21 @@ use openxr::{
 };
 
 use crate::{
-    helper_traits::{ToPosef, ToQuat, ToVec3},
+    helper_traits::{OxrPose, ToQuat, ToVec3},
     openxr_session_available, openxr_session_running,
     resources::{OxrFrameState, OxrInstance, Pipelined},
     session::OxrSession,
+    OxrPreUpdateSet,
 };
 
 #[derive(SystemSet, Hash, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct OxrSpaceSyncSet;
 
 /// VERY IMPORTANT!! only disable when you know what you are doing
+///
+/// Patches `xrDestroySpace` of the instance so that dropping an [`openxr::Space`] created from an [`XrSpace`]
+/// doesn't destroy the space a second time. Without it, spaces have to be destroyed only once through
+/// [`XrDestroySpace`] or [`OxrSession::destroy_space`] and any [`openxr::Space`] referencing them must not be dropped.
 pub struct OxrSpacePatchingPlugin;
 impl Plugin for OxrSpacePatchingPlugin {
     fn build(&self, app: &mut App) {
@@ -44,6 +49,10 @@ impl Plugin for OxrSpatialPlugin {
                     .before(XrHandleEvents::Poll)
                     .run_if(openxr_session_available),
             )
+            .configure_sets(
+                PreUpdate,
+                OxrSpaceSyncSet.in_set(OxrPreUpdateSet::UpdateCriticalComponents),
+            )
             .add_systems(
                 PreUpdate,
                 update_space_transforms
@@ -68,37 +77,35 @@ pub static OXR_DO_NOT_CALL_DESTOY_SPACE_FOR_SPACES: Mutex<Option<HashSet<u64>>>
 pub static OXR_ORIGINAL_DESTOY_SPACE: Mutex<Option<openxr::sys::pfn::DestroySpace>> =
     Mutex::new(None);
 
-fn patch_destroy_space(instance: ResMut<OxrInstance>) {
-    OXR_DO_NOT_CALL_DESTOY_SPACE_FOR_SPACES
+/// Runs `f` with the set of spaces that [`patched_destroy_space`] must not destroy.
+fn with_protected_spaces<R>(f: impl FnOnce(&mut HashSet<u64>) -> R) -> R {
+    f(OXR_DO_NOT_CALL_DESTOY_SPACE_FOR_SPACES
         .lock()
         .unwrap()
-        .replace(HashSet::new());
+        .get_or_insert_with(HashSet::new))
+}
+
+fn patch_destroy_space(instance: ResMut<OxrInstance>) {
     let raw_instance_ptr = instance.fp() as *const _ as *mut openxr::raw::Instance;
     unsafe {
-        OXR_ORIGINAL_DESTOY_SPACE
-            .lock()
-            .unwrap()
-            .replace((*raw_instance_ptr).destroy_space);
+        let current = (*raw_instance_ptr).destroy_space;
+        // patching twice would store the patched function as the original one, which then calls itself
+        if current as usize == patched_destroy_space as openxr::sys::pfn::DestroySpace as usize {
+            return;
+        }
+        OXR_ORIGINAL_DESTOY_SPACE.lock().unwrap().replace(current);
 
         (*raw_instance_ptr).destroy_space = patched_destroy_space;
     }
 }
 unsafe extern "system" fn patched_destroy_space(space: openxr::sys::Space) -> openxr::sys::Result {
-    if !OXR_DO_NOT_CALL_DESTOY_SPACE_FOR_SPACES
-        .lock()
-        .unwrap()
-        .as_ref()
-        .unwrap()
-        .contains(&space.into_raw())
-    {
-        OXR_ORIGINAL_DESTOY_SPACE
-            .lock()
-            .unwrap()
-            .expect("has to be initialized")(space)
-    } else {
+    if with_protected_spaces(|spaces| spaces.contains(&space.into_raw())) {
         info!("Inject Worked, not destroying space");
-        openxr::sys::Result::SUCCESS
+        return openxr::sys::Result::SUCCESS;
     }
+    // copied out so the lock isn't held while calling into the runtime
+    let original = *OXR_ORIGINAL_DESTOY_SPACE.lock().unwrap();
+    original.expect("has to be initialized")(space)
 }
 
 #[derive(Clone, Copy, Component, Default)]
@@ -117,6 +124,28 @@ impl OxrSpaceLocationFlags {
         self.0.contains(SpaceLocationFlags::ORIENTATION_TRACKED)
     }
 }
+impl From<OxrSpaceLocationFlags> for XrSpaceLocationFlags {
+    fn from(flags: OxrSpaceLocationFlags) -> Self {
+        XrSpaceLocationFlags {
+            position_valid: flags.pos_valid(),
+            orientation_valid: flags.rot_valid(),
+            position_tracked: flags.pos_valid() && flags.pos_tracked(),
+            orientation_tracked: flags.rot_valid() && flags.rot_tracked(),
+        }
+    }
+}
+
+/// The result of locating a space, like [`openxr::SpaceLocation`] but with the flags also available as named booleans.
+///
+/// Returned by the `locate_space` functions of [`OxrSession`] and [`OxrInstance`] in place of [`openxr::SpaceLocation`].
+/// Code matching on the old return type can keep using `pose` and `location_flags`, which have the same names and types.
+#[derive(Clone, Copy, Debug)]
+pub struct OxrSpaceLocation {
+    pub pose: openxr::Posef,
+    pub location_flags: openxr::SpaceLocationFlags,
+    pub flags: XrSpaceLocationFlags,
+}
+
 #[derive(Clone, Copy, Component, Default)]
 pub struct OxrSpaceVelocityFlags(pub openxr::SpaceVelocityFlags);
 impl OxrSpaceVelocityFlags {
@@ -138,6 +167,7 @@ fn update_space_transforms(
         &mut Transform,
         &XrSpace,
         Option<&mut XrVelocity>,
+        Has<XrTrackVelocity>,
         Option<&XrReferenceSpace>,
         &mut OxrSpaceLocationFlags,
         &mut XrSpaceLocationFlags,
@@ -149,6 +179,7 @@ fn update_space_transforms(
         mut transform,
         space,
         velocity,
+        track_velocity,
         ref_space,
         mut oxr_space_location_flags,
         mut xr_space_location_flags,
@@ -165,23 +196,23 @@ fn update_space_transforms(
         } else {
             frame_state.predicted_display_time
         };
-        let space_location = if let Some(mut velocity) = velocity {
+        let space_location = if let Some(mut velocity) = velocity.filter(|_| track_velocity) {
             match session.locate_space_with_velocity(space, ref_space, time) {
                 Ok((location, space_velocity)) => {
                     let flags = OxrSpaceVelocityFlags(space_velocity.velocity_flags);
                     if flags.linear_valid() {
                         velocity.linear = space_velocity.linear_velocity.to_vec3();
                     }
-                    if flags.linear_valid() {
-                        velocity.linear = space_velocity.linear_velocity.to_vec3();
+                    if flags.angular_valid() {
+                        velocity.angular = space_velocity.angular_velocity.to_vec3();
                     }
                     let Some(mut vel_flags) = oxr_space_velocity_flags else {
                         error!("XrVelocity without OxrSpaceVelocityFlags");
-                        return;
+                        continue;
                     };
                     let Some(mut xr_vel_flags) = xr_space_velocity_flags else {
                         error!("XrVelocity without XrSpaceVelocityFlags");
-                        return;
+                        continue;
                     };
                     *vel_flags = flags;
                     xr_vel_flags.linear_valid = vel_flags.linear_valid();
@@ -202,25 +233,27 @@ fn update_space_transforms(
                 transform.rotation = space_location.pose.orientation.to_quat();
             }
             *oxr_space_location_flags = flags;
-            xr_space_location_flags.position_tracked = flags.pos_valid() && flags.pos_tracked();
-            xr_space_location_flags.rotation_tracked = flags.rot_valid() && flags.rot_tracked();
+            *xr_space_location_flags = flags.into();
         }
     }
 }
 
 impl OxrSession {
+    /// Creates an [`XrSpace`] for a pose action.
+    ///
+    /// `pose_in_space` can be anything convertible into an [`OxrPose`], e.g. [`Isometry3d`], [`Transform`], a `(Vec3, Quat)` tuple or [`openxr::Posef`].
     pub fn create_action_space<T: openxr::ActionTy>(
         &self,
         action: &openxr::Action<T>,
         subaction_path: openxr::Path,
-        pose_in_space: Isometry3d,
+        pose_in_space: impl Into<OxrPose>,
     ) -> openxr::Result<XrSpace> {
         let info = sys::ActionSpaceCreateInfo {
             ty: sys::ActionSpaceCreateInfo::TYPE,
             next: ptr::null(),
             action: action.as_raw(),
             subaction_path,
-            pose_in_action_space: pose_in_space.to_posef(),
+            pose_in_action_space: pose_in_space.into().into(),
         };
         let mut out = sys::Space::NULL;
         unsafe {
@@ -232,16 +265,19 @@ impl OxrSession {
             Ok(XrSpace::from_raw(out.into_raw()))
         }
     }
+    /// Creates an [`XrReferenceSpace`] of the given type.
+    ///
+    /// `pose_in_ref_space` can be anything convertible into an [`OxrPose`], e.g. [`Isometry3d`], [`Transform`], a `(Vec3, Quat)` tuple or [`openxr::Posef`].
     pub fn create_reference_space(
         &self,
         ref_space_type: ReferenceSpaceType,
-        pose_in_ref_space: Transform,
+        pose_in_ref_space: impl Into<OxrPose>,
     ) -> openxr::Result<XrReferenceSpace> {
         let info = sys::ReferenceSpaceCreateInfo {
             ty: sys::ReferenceSpaceCreateInfo::TYPE,
             next: ptr::null(),
             reference_space_type: ref_space_type,
-            pose_in_reference_space: pose_in_ref_space.to_posef(),
+            pose_in_reference_space: pose_in_ref_space.into().into(),
         };
         let mut out = sys::Space::NULL;
         unsafe {
@@ -253,13 +289,99 @@ impl OxrSession {
             Ok(XrReferenceSpace(XrSpace::from_raw(out.into_raw())))
         }
     }
+    /// Creates an [`XrReferenceSpace`] anchored to an [`XrSpatialAnchor`].
+    ///
+    /// Spatial entities are spaces themselves, so the returned reference space shares the handle of the anchor.
+    /// It is only valid as long as the anchor is and must not be destroyed separately.
+    /// The anchor has to be locatable (e.g. `XR_SPACE_COMPONENT_TYPE_LOCATABLE_FB` enabled) for spaces to be located relative to it.
+    pub fn create_anchor_space(&self, anchor: &XrSpatialAnchor) -> XrReferenceSpace {
+        XrReferenceSpace(anchor.0)
+    }
+    /// Creates a local-floor reference space: gravity aligned with its origin on the floor below the `LOCAL` origin.
+    ///
+    /// Uses `XR_EXT_local_floor` if it's enabled, otherwise this is emulated by moving a `LOCAL` space
+    /// down to the floor of the `STAGE` space located at `time`. If the runtime has no stage or it can't be located,
+    /// the floor is assumed to be [`ESTIMATED_LOCAL_FLOOR_HEIGHT`] below the `LOCAL` origin.
+    ///
+    /// Unlike a native local-floor space, the emulated one doesn't follow changes of the stage after creation.
+    pub fn create_local_floor_space(&self, time: openxr::Time) -> openxr::Result<XrReferenceSpace> {
+        if self.instance().exts().ext_local_floor {
+            return self
+                .create_reference_space(ReferenceSpaceType::LOCAL_FLOOR_EXT, Transform::IDENTITY);
+        }
+        let height = match self.local_origin_in_stage(time) {
+            Ok(Some(origin)) => origin.translation.y,
+            _ => ESTIMATED_LOCAL_FLOOR_HEIGHT,
+        };
+        self.create_reference_space(
+            ReferenceSpaceType::LOCAL,
+            Transform::from_xyz(0.0, -height, 0.0),
+        )
+    }
+    /// Returns the size of the rectangle inside the bounds of a reference space, in meters along the x and z axes.
+    /// For the `STAGE` space this is the play area, centered on its origin.
+    ///
+    /// Returns [`None`] if the bounds are unavailable, e.g. because the user hasn't set up a play area.
+    ///
+    /// Calls `xrGetReferenceSpaceBoundsRect` internally.
+    pub fn get_reference_space_bounds_rect(
+        &self,
+        ref_space_type: ReferenceSpaceType,
+    ) -> openxr::Result<Option<Vec2>> {
+        Ok(self
+            .reference_space_bounds_rect(ref_space_type)?
+            .map(|bounds| Vec2::new(bounds.width, bounds.height)))
+    }
+    /// Returns the pose of the `LOCAL` origin in the `STAGE` space at `time`,
+    /// its translation's y is the height of the `LOCAL` origin above the floor.
+    ///
+    /// Returns [`None`] if the runtime has no `STAGE` space or the position can't be located.
+    pub fn local_origin_in_stage(&self, time: openxr::Time) -> openxr::Result<Option<Isometry3d>> {
+        if !self
+            .enumerate_reference_spaces()?
+            .contains(&ReferenceSpaceType::STAGE)
+        {
+            return Ok(None);
+        }
+        let local = self.create_reference_space(ReferenceSpaceType::LOCAL, Transform::IDENTITY)?;
+        let stage =
+            match self.create_reference_space(ReferenceSpaceType::STAGE, Transform::IDENTITY) {
+                Ok(stage) => stage,
+                Err(err) => {
+                    _ = self.destroy_space(local.0);
+                    return Err(err);
+                }
+            };
+        let location = self.locate_space(&local, &stage, time);
+        // destroy both spaces before returning any error, so neither of them leaks
+        let destroy_local = self.destroy_space(local.0);
+        let destroy_stage = self.destroy_space(stage.0);
+        let location = location?;
+        destroy_local?;
+        destroy_stage?;
+        if !location.flags.position_valid {
+            return Ok(None);
+        }
+        Ok(Some(Isometry3d::new(
+            location.pose.position.to_vec3(),
+            if location.flags.orientation_valid {
+                location.pose.orientation.to_quat()
+            } else {
+                Quat::IDENTITY
+            },
+        )))
+    }
 }
+
+/// The height of the `LOCAL` origin above the floor used by [`OxrSession::create_local_floor_space`]
+/// when the floor can't be determined, a typical standing eye height in meters.
+pub const ESTIMATED_LOCAL_FLOOR_HEIGHT: f32 = 1.5;
 fn locate_space(
     instance: &openxr::Instance,
     space: &XrSpace,
     base: &XrSpace,
     time: openxr::Time,
-) -> openxr::Result<openxr::SpaceLocation> {
+) -> openxr::Result<OxrSpaceLocation> {
     unsafe {
         let mut x = sys::SpaceLocation::out(ptr::null_mut());
         cvt((instance.fp().locate_space)(
@@ -276,7 +398,7 @@ fn locate_space_with_velocity(
     space: &XrSpace,
     base: &XrSpace,
     time: openxr::Time,
-) -> openxr::Result<(openxr::SpaceLocation, openxr::SpaceVelocity)> {
+) -> openxr::Result<(OxrSpaceLocation, openxr::SpaceVelocity)> {
     unsafe {
         let mut velocity = sys::SpaceVelocity::out(ptr::null_mut());
         let mut location = sys::SpaceLocation::out(&mut velocity as *mut _ as _);
@@ -380,23 +502,13 @@ pub fn destroy_space(
     instance: &openxr::Instance,
     space: sys::Space,
 ) -> openxr::Result<sys::Result> {
-    OXR_DO_NOT_CALL_DESTOY_SPACE_FOR_SPACES
-        .lock()
-        .unwrap()
-        .as_mut()
-        .unwrap()
-        .remove(&space.into_raw());
+    with_protected_spaces(|spaces| spaces.remove(&space.into_raw()));
     let result = unsafe { (instance.fp().destroy_space)(space) };
     cvt(result)
 }
 impl OxrSession {
     pub fn allow_auto_destruct_of_openxr_space(&self, space: &openxr::Space) {
-        OXR_DO_NOT_CALL_DESTOY_SPACE_FOR_SPACES
-            .lock()
-            .unwrap()
-            .as_mut()
-            .unwrap()
-            .remove(&space.as_raw().into_raw());
+        with_protected_spaces(|spaces| spaces.remove(&space.as_raw().into_raw()));
     }
     pub fn destroy_space(&self, space: XrSpace) -> openxr::Result<sys::Result> {
         destroy_space(self.instance(), space.as_raw_openxr_space())
@@ -438,20 +550,24 @@ impl OxrSession {
                 .collect(),
         ))
     }
+    /// Locates `space` relative to `base` at `time`.
+    ///
+    /// Returns an [`OxrSpaceLocation`] rather than an [`openxr::SpaceLocation`], see its docs for migrating.
     pub fn locate_space(
         &self,
         space: &XrSpace,
         base: &XrSpace,
         time: openxr::Time,
-    ) -> openxr::Result<openxr::SpaceLocation> {
+    ) -> openxr::Result<OxrSpaceLocation> {
         locate_space(self.instance(), space, base, time)
     }
+    /// Like [`locate_space`](Self::locate_space), but also returns the velocity of `space`.
     pub fn locate_space_with_velocity(
         &self,
         space: &XrSpace,
         base: &XrSpace,
         time: openxr::Time,
-    ) -> openxr::Result<(openxr::SpaceLocation, openxr::SpaceVelocity)> {
+    ) -> openxr::Result<(OxrSpaceLocation, openxr::SpaceVelocity)> {
         locate_space_with_velocity(self.instance(), space, base, time)
     }
     pub fn locate_hand_joints(
@@ -473,12 +589,7 @@ impl OxrSession {
 }
 impl OxrInstance {
     pub fn allow_auto_destruct_of_openxr_space(&self, space: &openxr::Space) {
-        OXR_DO_NOT_CALL_DESTOY_SPACE_FOR_SPACES
-            .lock()
-            .unwrap()
-            .as_mut()
-            .unwrap()
-            .remove(&space.as_raw().into_raw());
+        with_protected_spaces(|spaces| spaces.remove(&space.as_raw().into_raw()));
     }
     pub fn destroy_space(&self, space: XrSpace) -> openxr::Result<sys::Result> {
         destroy_space(self, space.as_raw_openxr_space())
@@ -486,20 +597,24 @@ impl OxrInstance {
     pub fn destroy_openxr_space(&self, space: openxr::Space) -> openxr::Result<sys::Result> {
         destroy_space(self, space.as_raw())
     }
+    /// Locates `space` relative to `base` at `time`.
+    ///
+    /// Returns an [`OxrSpaceLocation`] rather than an [`openxr::SpaceLocation`], see its docs for migrating.
     pub fn locate_space(
         &self,
         space: &XrSpace,
         base: &XrSpace,
         time: openxr::Time,
-    ) -> openxr::Result<openxr::SpaceLocation> {
+    ) -> openxr::Result<OxrSpaceLocation> {
         locate_space(self, space, base, time)
     }
+    /// Like [`locate_space`](Self::locate_space), but also returns the velocity of `space`.
     pub fn locate_space_with_velocity(
         &self,
         space: &XrSpace,
         base: &XrSpace,
         time: openxr::Time,
-    ) -> openxr::Result<(openxr::SpaceLocation, openxr::SpaceVelocity)> {
+    ) -> openxr::Result<(OxrSpaceLocation, openxr::SpaceVelocity)> {
         locate_space_with_velocity(self, space, base, time)
     }
     pub fn locate_hand_joints(
@@ -549,12 +664,7 @@ unsafe impl OxrSpaceExt for XrSpace {
 
     fn from_raw_openxr_space(space: sys::Space) -> Self {
         let raw = space.into_raw();
-        OXR_DO_NOT_CALL_DESTOY_SPACE_FOR_SPACES
-            .lock()
-            .unwrap()
-            .as_mut()
-            .unwrap()
-            .insert(raw);
+        with_protected_spaces(|spaces| spaces.insert(raw));
         unsafe { XrSpace::from_raw(raw) }
     }
 
@@ -566,12 +676,7 @@ unsafe impl OxrSpaceExt for XrSpace {
         unsafe { openxr::Space::reference_from_raw(session.clone(), self.as_raw_openxr_space()) }
     }
     unsafe fn into_openxr_space<T>(self, session: &openxr::Session<T>) -> openxr::Space {
-        OXR_DO_NOT_CALL_DESTOY_SPACE_FOR_SPACES
-            .lock()
-            .unwrap()
-            .as_mut()
-            .unwrap()
-            .remove(&self.as_raw());
+        with_protected_spaces(|spaces| spaces.remove(&self.as_raw()));
         unsafe { openxr::Space::reference_from_raw(session.clone(), self.as_raw_openxr_space()) }
     }
 }
@@ -600,12 +705,13 @@ unsafe fn create_view(flags: openxr::ViewStateFlags, raw: &MaybeUninit<sys::View
         fov: *ptr::addr_of!((*ptr).fov),
     }
 }
-unsafe fn create_space_location(raw: &MaybeUninit<sys::SpaceLocation>) -> openxr::SpaceLocation {
+unsafe fn create_space_location(raw: &MaybeUninit<sys::SpaceLocation>) -> OxrSpaceLocation {
     // Applications *must* not read invalid parts of a pose, i.e. they may be uninitialized
     let ptr = raw.as_ptr();
     let flags = *ptr::addr_of!((*ptr).location_flags);
-    openxr::SpaceLocation {
+    OxrSpaceLocation {
         location_flags: flags,
+        flags: OxrSpaceLocationFlags(flags).into(),
         pose: openxr::Posef {
             orientation: flags
                 .contains(sys::SpaceLocationFlags::ORIENTATION_VALID)
@@ -634,7 +740,7 @@ unsafe fn create_space_velocity(raw: &MaybeUninit<sys::SpaceVelocity>) -> openxr
             .unwrap_or_default(),
     }
 }
-fn get_arr_init<T: Copy>(
+pub(crate) fn get_arr_init<T: Copy>(
     init: T,
     mut getter: impl FnMut(u32, &mut u32, *mut T) -> sys::Result,
 ) -> openxr::Result<Vec<T>> {
@@ -0,0 +1,309 @@
+//! Reads the common controller inputs into [`XrStandardInput`] without branching on the interaction profile.
+//!
+//! [`OxrStandardInputPlugin`] creates a `standard_input` action set with bindings for the simple, Touch,
+//! Index, Vive and Windows Mixed Reality controllers, and updates [`XrStandardInput`] and
+//! [`ButtonInput<XrStandardButton>`] after the actions are synced.
+use bevy::prelude::*;
+use bevy_mod_xr::input::{XrStandardButton, XrStandardInput};
+use bevy_mod_xr::session::{XrPreDestroySession, XrSessionCreated};
+use openxr::{ActionInput, Vector2f};
+
+use crate::action_binding::{OxrSendActionBindings, OxrSuggestActionBinding};
+use crate::action_set_attaching::OxrAttachActionSet;
+use crate::action_set_syncing::{OxrActionSetSyncSet, OxrActionSets};
+use crate::resources::OxrInstance;
+use crate::session::OxrSession;
+use crate::{openxr_session_available, openxr_session_running};
+
+const SIMPLE: &str = "/interaction_profiles/khr/simple_controller";
+const TOUCH: &str = "/interaction_profiles/oculus/touch_controller";
+const INDEX: &str = "/interaction_profiles/valve/index_controller";
+const VIVE: &str = "/interaction_profiles/htc/vive_controller";
+const WMR: &str = "/interaction_profiles/microsoft/motion_controller";
+
+pub struct OxrStandardInputPlugin;
+
+impl Plugin for OxrStandardInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<XrStandardInput>()
+            .init_resource::<ButtonInput<XrStandardButton>>()
+            .add_systems(
+                Startup,
+                create_standard_actions.run_if(openxr_session_available),
+            )
+            .add_systems(OxrSendActionBindings, suggest_bindings)
+            .add_systems(XrSessionCreated, attach_set)
+            .add_systems(XrPreDestroySession, reset_input)
+            .add_systems(
+                PreUpdate,
+                update_standard_input
+                    .after(OxrActionSetSyncSet)
+                    .run_if(openxr_session_running),
+            );
+    }
+}
+
+/// The actions [`XrStandardInput`] is read from.
+///
+/// The per hand actions have the `/user/hand/left` and `/user/hand/right` subaction paths.
+#[derive(Resource)]
+pub struct OxrStandardActions {
+    pub set: openxr::ActionSet,
+    pub trigger: openxr::Action<f32>,
+    pub grip: openxr::Action<f32>,
+    pub stick: openxr::Action<Vector2f>,
+    pub stick_click: openxr::Action<bool>,
+    pub stick_touch: openxr::Action<bool>,
+    pub a: openxr::Action<bool>,
+    pub b: openxr::Action<bool>,
+    pub x: openxr::Action<bool>,
+    pub y: openxr::Action<bool>,
+    pub menu: openxr::Action<bool>,
+    pub left: openxr::Path,
+    pub right: openxr::Path,
+}
+
+impl OxrStandardActions {
+    /// Creates the actions in the given action set.
+    pub fn new(instance: &OxrInstance, set: &openxr::ActionSet) -> openxr::Result<Self> {
+        let left = instance.string_to_path("/user/hand/left")?;
+        let right = instance.string_to_path("/user/hand/right")?;
+        let hands = [left, right];
+        Ok(Self {
+            set: set.clone(),
+            trigger: set.create_action("trigger", "Trigger", &hands)?,
+            grip: set.create_action("grip", "Grip", &hands)?,
+            stick: set.create_action("stick", "Thumbstick", &hands)?,
+            stick_click: set.create_action("stick_click", "Thumbstick Click", &hands)?,
+            stick_touch: set.create_action("stick_touch", "Thumbstick Touch", &hands)?,
+            a: set.create_action("a", "A Button", &[])?,
+            b: set.create_action("b", "B Button", &[])?,
+            x: set.create_action("x", "X Button", &[])?,
+            y: set.create_action("y", "Y Button", &[])?,
+            menu: set.create_action("menu", "Menu", &[])?,
+            left,
+            right,
+        })
+    }
+
+    /// Suggests the bindings of the simple, Touch, Index, Vive and Windows Mixed Reality controllers.
+    ///
+    /// Has to run in [`OxrSendActionBindings`].
+    pub fn suggest_bindings(&self, bindings: &mut EventWriter<OxrSuggestActionBinding>) {
+        let mut suggest =
+            |action: openxr::sys::Action, profile: &'static str, paths: Vec<String>| {
+                bindings.send(OxrSuggestActionBinding {
+                    action,
+                    interaction_profile: profile.into(),
+                    bindings: paths.into_iter().map(Into::into).collect(),
+                });
+            };
+        let both = |component: &str| {
+            vec![
+                format!("/user/hand/left/input/{component}"),
+                format!("/user/hand/right/input/{component}"),
+            ]
+        };
+        let one = |path: &str| vec![path.to_string()];
+
+        let trigger = self.trigger.as_raw();
+        let grip = self.grip.as_raw();
+        let stick = self.stick.as_raw();
+        let stick_click = self.stick_click.as_raw();
+        for (profile, trigger_path, grip_path, stick_path) in [
+            (SIMPLE, "select/click", None, None),
+            (
+                TOUCH,
+                "trigger/value",
+                Some("squeeze/value"),
+                Some("thumbstick"),
+            ),
+            (
+                INDEX,
+                "trigger/value",
+                Some("squeeze/value"),
+                Some("thumbstick"),
+            ),
+            (
+                VIVE,
+                "trigger/value",
+                Some("squeeze/click"),
+                Some("trackpad"),
+            ),
+            (
+                WMR,
+                "trigger/value",
+                Some("squeeze/click"),
+                Some("thumbstick"),
+            ),
+        ] {
+            suggest(trigger, profile, both(trigger_path));
+            if let Some(grip_path) = grip_path {
+                suggest(grip, profile, both(grip_path));
+            }
+            if let Some(stick_path) = stick_path {
+                suggest(stick, profile, both(stick_path));
+                suggest(stick_click, profile, both(&format!("{stick_path}/click")));
+            }
+        }
+
+        // the simple and Windows Mixed Reality thumbsticks can't detect touch
+        for (profile, touch_path) in [
+            (TOUCH, "thumbstick/touch"),
+            (INDEX, "thumbstick/touch"),
+            (VIVE, "trackpad/touch"),
+        ] {
+            suggest(self.stick_touch.as_raw(), profile, both(touch_path));
+        }
+
+        suggest(
+            self.a.as_raw(),
+            TOUCH,
+            one("/user/hand/right/input/a/click"),
+        );
+        suggest(
+            self.b.as_raw(),
+            TOUCH,
+            one("/user/hand/right/input/b/click"),
+        );
+        suggest(self.x.as_raw(), TOUCH, one("/user/hand/left/input/x/click"));
+        suggest(self.y.as_raw(), TOUCH, one("/user/hand/left/input/y/click"));
+        suggest(
+            self.menu.as_raw(),
+            TOUCH,
+            one("/user/hand/left/input/menu/click"),
+        );
+
+        // the index controllers have a and b buttons on both hands
+        suggest(
+            self.a.as_raw(),
+            INDEX,
+            one("/user/hand/right/input/a/click"),
+        );
+        suggest(
+            self.b.as_raw(),
+            INDEX,
+            one("/user/hand/right/input/b/click"),
+        );
+        suggest(self.x.as_raw(), INDEX, one("/user/hand/left/input/a/click"));
+        suggest(self.y.as_raw(), INDEX, one("/user/hand/left/input/b/click"));
+
+        for profile in [SIMPLE, VIVE, WMR] {
+            suggest(self.menu.as_raw(), profile, both("menu/click"));
+        }
+    }
+
+    /// Reads the current state of all actions.
+    pub fn read(&self, session: &OxrSession) -> XrStandardInput {
+        let [left_trigger, right_trigger] = self.hands(session, &self.trigger);
+        let [left_grip, right_grip] = self.hands(session, &self.grip);
+        let [left_stick, right_stick] = self.hands(session, &self.stick);
+        let [left_stick_click, right_stick_click] = self.hands(session, &self.stick_click);
+        let [left_stick_touch, right_stick_touch] = self.hands(session, &self.stick_touch);
+        XrStandardInput {
+            left_trigger,
+            right_trigger,
+            left_grip,
+            right_grip,
+            left_stick: Vec2::new(left_stick.x, left_stick.y),
+            right_stick: Vec2::new(right_stick.x, right_stick.y),
+            left_stick_click,
+            right_stick_click,
+            left_stick_touch,
+            right_stick_touch,
+            a_button: read_state(session, &self.a, openxr::Path::NULL),
+            b_button: read_state(session, &self.b, openxr::Path::NULL),
+            x_button: read_state(session, &self.x, openxr::Path::NULL),
+            y_button: read_state(session, &self.y, openxr::Path::NULL),
+            menu: read_state(session, &self.menu, openxr::Path::NULL),
+        }
+    }
+
+    fn hands<T: ActionInput + Default>(
+        &self,
+        session: &OxrSession,
+        action: &openxr::Action<T>,
+    ) -> [T; 2] {
+        [
+            read_state(session, action, self.left),
+            read_state(session, action, self.right),
+        ]
+    }
+}
+
+/// Returns the current state of `action`, or the default value while it isn't bound.
+fn read_state<T: ActionInput + Default>(
+    session: &OxrSession,
+    action: &openxr::Action<T>,
+    subaction_path: openxr::Path,
+) -> T {
+    action
+        .state(session, subaction_path)
+        .ok()
+        .filter(|state| state.is_active)
+        .map(|state| state.current_state)
+        .unwrap_or_default()
+}
+
+fn create_standard_actions(
+    instance: Res<OxrInstance>,
+    mut action_sets: ResMut<OxrActionSets>,
+    mut cmds: Commands,
+) {
+    let actions = instance
+        .create_action_set("standard_input", "Standard Input", 0)
+        .and_then(|set| OxrStandardActions::new(&instance, &set));
+    match actions {
+        Ok(actions) => {
+            action_sets.insert("standard_input", actions.set.clone());
+            cmds.insert_resource(actions);
+        }
+        Err(err) => error!("Failed to create standard input actions: {err}"),
+    }
+}
+
+fn suggest_bindings(
+    actions: Option<Res<OxrStandardActions>>,
+    mut bindings: EventWriter<OxrSuggestActionBinding>,
+) {
+    if let Some(actions) = actions {
+        actions.suggest_bindings(&mut bindings);
+    }
+}
+
+fn attach_set(
+    actions: Option<Res<OxrStandardActions>>,
+    mut attach: EventWriter<OxrAttachActionSet>,
+) {
+    if let Some(actions) = actions {
+        attach.send(OxrAttachActionSet(actions.set.clone()));
+    }
+}
+
+fn update_standard_input(
+    actions: Option<Res<OxrStandardActions>>,
+    session: Res<OxrSession>,
+    mut input: ResMut<XrStandardInput>,
+    mut buttons: ResMut<ButtonInput<XrStandardButton>>,
+) {
+    let Some(actions) = actions else {
+        return;
+    };
+    *input = actions.read(&session);
+    buttons.clear();
+    for button in XrStandardButton::ALL {
+        if input.pressed(button) {
+            buttons.press(button);
+        } else {
+            buttons.release(button);
+        }
+    }
+}
+
+fn reset_input(
+    mut input: ResMut<XrStandardInput>,
+    mut buttons: ResMut<ButtonInput<XrStandardButton>>,
+) {
+    *input = default();
+    buttons.release_all();
+}
@@ -0,0 +1,106 @@
+//! Conversions between [`openxr::Time`] and the clocks of the operating system.
+//!
+//! OpenXR times are nanoseconds on a runtime defined clock, which is unrelated to the clock behind
+//! bevy's [`Time`](bevy::time::Time). To correlate XR poses with timestamps from other sources,
+//! e.g. external sensors, the timestamps have to be converted with the functions below.
+//! [`ToStdDuration`](crate::helper_traits::ToStdDuration) and [`ToTime`](crate::helper_traits::ToTime)
+//! convert between [`openxr::Time`] and [`Duration`](std::time::Duration) without changing the clock.
+use std::mem;
+
+use openxr::sys;
+
+use crate::session::OxrSession;
+
+impl OxrSession {
+    /// Converts a `CLOCK_MONOTONIC` timespec into an [`openxr::Time`].
+    ///
+    /// Requires [`XR_KHR_convert_timespec_time`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_KHR_convert_timespec_time).
+    pub fn convert_timespec_to_time(
+        &self,
+        timespec: &sys::timespec,
+    ) -> openxr::Result<openxr::Time> {
+        let fp = self
+            .instance()
+            .exts()
+            .khr_convert_timespec_time
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)?;
+        let mut time = openxr::Time::from_nanos(0);
+        cvt(unsafe {
+            (fp.convert_timespec_time_to_time)(self.instance().as_raw(), timespec, &mut time)
+        })?;
+        Ok(time)
+    }
+
+    /// Converts an [`openxr::Time`] into a `CLOCK_MONOTONIC` timespec.
+    ///
+    /// Requires [`XR_KHR_convert_timespec_time`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_KHR_convert_timespec_time).
+    pub fn convert_time_to_timespec(&self, time: openxr::Time) -> openxr::Result<sys::timespec> {
+        let fp = self
+            .instance()
+            .exts()
+            .khr_convert_timespec_time
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)?;
+        let mut timespec: sys::timespec = unsafe { mem::zeroed() };
+        cvt(unsafe {
+            (fp.convert_time_to_timespec_time)(self.instance().as_raw(), time, &mut timespec)
+        })?;
+        Ok(timespec)
+    }
+
+    /// Converts a value of `QueryPerformanceCounter` into an [`openxr::Time`].
+    ///
+    /// Requires [`XR_KHR_win32_convert_performance_counter_time`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_KHR_win32_convert_performance_counter_time).
+    #[cfg(windows)]
+    pub fn convert_win32_to_time(
+        &self,
+        performance_counter: &sys::LARGE_INTEGER,
+    ) -> openxr::Result<openxr::Time> {
+        let fp = self
+            .instance()
+            .exts()
+            .khr_win32_convert_performance_counter_time
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)?;
+        let mut time = openxr::Time::from_nanos(0);
+        cvt(unsafe {
+            (fp.convert_win32_performance_counter_to_time)(
+                self.instance().as_raw(),
+                performance_counter,
+                &mut time,
+            )
+        })?;
+        Ok(time)
+    }
+
+    /// Converts an [`openxr::Time`] into a value of `QueryPerformanceCounter`.
+    ///
+    /// Requires [`XR_KHR_win32_convert_performance_counter_time`](https://www.khronos.org/registry/OpenXR/specs/1.0/html/xrspec.html#XR_KHR_win32_convert_performance_counter_time).
+    #[cfg(windows)]
+    pub fn convert_time_to_win32(&self, time: openxr::Time) -> openxr::Result<sys::LARGE_INTEGER> {
+        let fp = self
+            .instance()
+            .exts()
+            .khr_win32_convert_performance_counter_time
+            .as_ref()
+            .ok_or(sys::Result::ERROR_EXTENSION_NOT_PRESENT)?;
+        let mut performance_counter: sys::LARGE_INTEGER = unsafe { mem::zeroed() };
+        cvt(unsafe {
+            (fp.convert_time_to_win32_performance_counter)(
+                self.instance().as_raw(),
+                time,
+                &mut performance_counter,
+            )
+        })?;
+        Ok(performance_counter)
+    }
+}
+
+fn cvt(x: sys::Result) -> openxr::Result<sys::Result> {
+    if x.into_raw() >= 0 {
+        Ok(x)
+    } else {
+        Err(x)
+    }
+}
@@ -9,6 +9,9 @@ pub use openxr::{EnvironmentBlendMode, SwapchainCreateFlags, SwapchainUsageFlags
 
 pub type Result<T> = std::result::Result<T, OxrError>;
 
+/// Modifies the [`wgpu::DeviceDescriptor`] the render device is created with, see [`OxrInitPlugin::device_descriptor`](crate::init::OxrInitPlugin::device_descriptor).
+pub type OxrDeviceDescriptorFn = Box<dyn Fn(&mut wgpu::DeviceDescriptor<'static>) + Send + Sync>;
+
 /// A container for all required graphics objects needed for a bevy app.
 pub struct WgpuGraphics(
     pub wgpu::Device,
@@ -54,6 +57,77 @@ impl Default for AppInfo {
     }
 }
 
+/// Multisample anti-aliasing of the [`XrCamera`](bevy_mod_xr::camera::XrCamera)s.
+///
+/// The cameras render into multisampled textures which are resolved into the swapchain images.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OxrMsaa {
+    Off,
+    X2,
+    #[default]
+    X4,
+    X8,
+}
+
+impl OxrMsaa {
+    pub fn samples(self) -> u32 {
+        match self {
+            OxrMsaa::Off => 1,
+            OxrMsaa::X2 => 2,
+            OxrMsaa::X4 => 4,
+            OxrMsaa::X8 => 8,
+        }
+    }
+
+    /// Returns the highest setting up to `self` that uses at most `max_samples` samples.
+    pub fn clamp_to(self, max_samples: u32) -> Self {
+        [OxrMsaa::X8, OxrMsaa::X4, OxrMsaa::X2]
+            .into_iter()
+            .find(|msaa| msaa.samples() <= self.samples() && msaa.samples() <= max_samples)
+            .unwrap_or(OxrMsaa::Off)
+    }
+}
+
+impl From<OxrMsaa> for bevy::render::view::Msaa {
+    fn from(msaa: OxrMsaa) -> Self {
+        match msaa {
+            OxrMsaa::Off => Self::Off,
+            OxrMsaa::X2 => Self::Sample2,
+            OxrMsaa::X4 => Self::Sample4,
+            OxrMsaa::X8 => Self::Sample8,
+        }
+    }
+}
+
+/// Returns the [`SwapchainUsageFlags`] a swapchain needs to be created with
+/// for its images to be used as wgpu textures with `usage`.
+pub fn swapchain_usage_flags(
+    usage: wgpu::TextureUsages,
+    format: wgpu::TextureFormat,
+) -> SwapchainUsageFlags {
+    let mut flags = SwapchainUsageFlags::EMPTY;
+    if usage.contains(wgpu::TextureUsages::RENDER_ATTACHMENT) {
+        flags |= if format.is_depth_stencil_format() {
+            SwapchainUsageFlags::DEPTH_STENCIL_ATTACHMENT
+        } else {
+            SwapchainUsageFlags::COLOR_ATTACHMENT
+        };
+    }
+    if usage.contains(wgpu::TextureUsages::TEXTURE_BINDING) {
+        flags |= SwapchainUsageFlags::SAMPLED;
+    }
+    if usage.contains(wgpu::TextureUsages::STORAGE_BINDING) {
+        flags |= SwapchainUsageFlags::UNORDERED_ACCESS;
+    }
+    if usage.contains(wgpu::TextureUsages::COPY_SRC) {
+        flags |= SwapchainUsageFlags::TRANSFER_SRC;
+    }
+    if usage.contains(wgpu::TextureUsages::COPY_DST) {
+        flags |= SwapchainUsageFlags::TRANSFER_DST;
+    }
+    flags
+}
+
 /// Info needed to create a swapchain.
 /// This is an API agnostic version of [openxr::SwapchainCreateInfo] used for some of this library's functions
 #[derive(Debug, Copy, Clone)]
@@ -96,3 +170,24 @@ pub struct SessionCreateInfo(pub GraphicsWrap<Self>);
 impl GraphicsType for SessionCreateInfo {
     type Inner<G: GraphicsExt> = G::SessionCreateInfo;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn msaa_steps_down_to_the_highest_supported_count() {
+        assert_eq!(OxrMsaa::X8.clamp_to(4), OxrMsaa::X4);
+        assert_eq!(OxrMsaa::X8.clamp_to(7), OxrMsaa::X4);
+        assert_eq!(OxrMsaa::X4.clamp_to(2), OxrMsaa::X2);
+        assert_eq!(OxrMsaa::X4.clamp_to(1), OxrMsaa::Off);
+        assert_eq!(OxrMsaa::X4.clamp_to(0), OxrMsaa::Off);
+    }
+
+    #[test]
+    fn msaa_is_never_raised() {
+        assert_eq!(OxrMsaa::X4.clamp_to(8), OxrMsaa::X4);
+        assert_eq!(OxrMsaa::X2.clamp_to(16), OxrMsaa::X2);
+        assert_eq!(OxrMsaa::Off.clamp_to(8), OxrMsaa::Off);
+    }
+}
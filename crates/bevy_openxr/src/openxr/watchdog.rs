@@ -0,0 +1,108 @@
+//! Opt-in diagnostics for the frame loop.
+//!
+//! Add [`OxrFrameWatchdogPlugin`] to log a warning whenever `wait_frame`, `begin_frame`, `wait_image` or `end_frame`
+//! take longer than a threshold, and to log which of them was running when a panic unwinds through it,
+//! together with the last error the frame loop got from the runtime. The behavior of the frame loop is unchanged.
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy::render::RenderApp;
+
+use crate::error::OxrRenderError;
+
+/// Watches the frame loop systems in the main and render world, see the [module docs](self).
+pub struct OxrFrameWatchdogPlugin {
+    /// A frame loop system running longer than this logs a warning.
+    pub threshold: Duration,
+}
+
+impl Default for OxrFrameWatchdogPlugin {
+    fn default() -> Self {
+        Self {
+            threshold: Duration::from_millis(50),
+        }
+    }
+}
+
+impl Plugin for OxrFrameWatchdogPlugin {
+    fn build(&self, app: &mut App) {
+        let watchdog = OxrFrameWatchdog::new(self.threshold);
+        app.insert_resource(watchdog.clone());
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.insert_resource(watchdog);
+        }
+    }
+}
+
+/// Shared between the main and render world, so the last error is known to both.
+#[derive(Resource, Clone)]
+pub struct OxrFrameWatchdog(Arc<WatchdogInner>);
+
+struct WatchdogInner {
+    threshold: Duration,
+    last_error: Mutex<Option<String>>,
+}
+
+impl OxrFrameWatchdog {
+    pub fn new(threshold: Duration) -> Self {
+        Self(Arc::new(WatchdogInner {
+            threshold,
+            last_error: Mutex::new(None),
+        }))
+    }
+
+    pub fn threshold(&self) -> Duration {
+        self.0.threshold
+    }
+
+    /// Starts watching `stage` until the returned guard is dropped.
+    pub fn watch(&self, stage: &'static str) -> OxrWatchdogGuard {
+        OxrWatchdogGuard {
+            watchdog: self.clone(),
+            stage,
+            started: Instant::now(),
+        }
+    }
+
+    /// Remembers `error` as the last error of the frame loop.
+    pub fn record_error(&self, error: &OxrRenderError) {
+        if let Ok(mut last_error) = self.0.last_error.lock() {
+            *last_error = Some(error.to_string());
+        }
+    }
+
+    /// The last error of the frame loop, if there was one.
+    pub fn last_error(&self) -> Option<String> {
+        self.0.last_error.lock().ok()?.clone()
+    }
+}
+
+/// Returned by [`OxrFrameWatchdog::watch`], checks the duration of the stage when dropped.
+pub struct OxrWatchdogGuard {
+    watchdog: OxrFrameWatchdog,
+    stage: &'static str,
+    started: Instant,
+}
+
+impl Drop for OxrWatchdogGuard {
+    fn drop(&mut self) {
+        let elapsed = self.started.elapsed();
+        let last_error = self
+            .watchdog
+            .last_error()
+            .unwrap_or_else(|| "none".to_string());
+        if std::thread::panicking() {
+            error!(
+                "Panicked in frame loop stage `{}` after {elapsed:?}, last frame loop error: {last_error}",
+                self.stage
+            );
+        } else if elapsed > self.watchdog.threshold() {
+            warn!(
+                "Frame loop stage `{}` took {elapsed:?}, longer than the threshold of {:?}",
+                self.stage,
+                self.watchdog.threshold()
+            );
+        }
+    }
+}
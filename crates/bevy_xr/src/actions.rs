@@ -1,6 +1,7 @@
 use std::{any::TypeId, marker::PhantomData};
 
 use bevy::app::{App, Plugin};
+use bevy::ecs::event::Event;
 use bevy::ecs::system::Resource;
 use bevy::math::Vec2;
 
@@ -105,3 +106,14 @@ impl<A: Action<ActionType = bool>> ActionState<A> {
         self.current_state = true
     }
 }
+
+/// Sent when the interaction profile of a top level user path changed,
+/// e.g. when the user put down the controllers to use hand tracking.
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct XrInteractionProfileChanged {
+    /// The top level user path, e.g. `/user/hand/left`.
+    pub user_path: String,
+    /// The active interaction profile, e.g. `/interaction_profiles/oculus/touch_controller`.
+    /// [`None`] if there is no active interaction profile for the user path.
+    pub profile: Option<String>,
+}
@@ -0,0 +1,23 @@
+use bevy::ecs::system::Resource;
+
+use crate::hands::HandSide;
+
+/// The charge of the XR devices from 0.0 to 1.0, for showing battery indicators.
+///
+/// Polled by the backend at a low frequency, a level is [`None`] while the runtime or platform
+/// doesn't report it for that device.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Resource)]
+pub struct XrBatteryStatus {
+    pub hmd: Option<f32>,
+    pub left_controller: Option<f32>,
+    pub right_controller: Option<f32>,
+}
+
+impl XrBatteryStatus {
+    pub fn controller(&self, hand: HandSide) -> Option<f32> {
+        match hand {
+            HandSide::Left => self.left_controller,
+            HandSide::Right => self.right_controller,
+        }
+    }
+}
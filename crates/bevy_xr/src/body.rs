@@ -0,0 +1,36 @@
+use bevy::{
+    ecs::component::Component,
+    prelude::{Deref, DerefMut, GlobalTransform},
+};
+
+use crate::spaces::XrSpaceLocationFlags;
+
+/// The tracking state of a single body joint, see [`XrBodyJoints`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct XrBodyJoint {
+    /// World space transform of the joint.
+    /// Keeps the last valid position and rotation while they aren't valid.
+    pub transform: GlobalTransform,
+    pub location_flags: XrSpaceLocationFlags,
+}
+
+impl XrBodyJoint {
+    /// Returns true if both the position and rotation of the joint are valid.
+    pub fn is_valid(&self) -> bool {
+        self.location_flags.position_valid && self.location_flags.orientation_valid
+    }
+}
+
+/// All joints of a tracked body, updated every frame by the backend.
+///
+/// The order and number of joints depends on the joint set of the backend,
+/// e.g. the 70 joints of `XrBodyJointFB` for `XR_FB_body_tracking`.
+#[derive(Clone, Component, Debug, Default, Deref, DerefMut)]
+pub struct XrBodyJoints {
+    #[deref]
+    pub joints: Vec<XrBodyJoint>,
+    /// Whether the body is currently tracked. While this is `false` the joints keep their last known transforms.
+    pub tracked: bool,
+    /// Confidence of the tracking from 0.0 to 1.0.
+    pub confidence: f32,
+}
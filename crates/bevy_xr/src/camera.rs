@@ -5,6 +5,7 @@ use bevy::core_pipeline::core_3d::Camera3d;
 use bevy::ecs::component::{Component, StorageType};
 use bevy::ecs::reflect::ReflectComponent;
 use bevy::ecs::schedule::IntoSystemConfigs;
+use bevy::ecs::system::Resource;
 use bevy::math::{Mat4, Vec3A};
 use bevy::pbr::{PbrPlugin, PbrProjectionPlugin};
 use bevy::prelude::{Projection, SystemSet};
@@ -13,6 +14,7 @@ use bevy::reflect::Reflect;
 use bevy::render::camera::{CameraProjection, CameraProjectionPlugin};
 use bevy::render::extract_component::{ExtractComponent, ExtractComponentPlugin};
 use bevy::render::view::{update_frusta, VisibilitySystems};
+use bevy::transform::components::{GlobalTransform, Transform};
 use bevy::transform::TransformSystem;
 
 use crate::session::XrTracker;
@@ -41,11 +43,23 @@ impl Plugin for XrCameraPlugin {
 #[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Hash, SystemSet)]
 pub struct XrViewInit;
 
+/// The projection of an [`XrCamera`]. The backend rebuilds `projection_matrix` from the view's fov every frame,
+/// using `near`, `far` and `reversed_z`.
+///
+/// By default this is an infinite reversed-Z projection, which is what bevy's core pipeline expects:
+/// depth is 1.0 at the near plane and goes towards 0.0 with distance. Reversed-Z spreads float precision
+/// much more evenly over the depth range, while a standard projection loses most of it far from the camera
+/// and z-fights sooner. Only disable `reversed_z` when your own render pipeline or depth consumer needs it,
+/// bevy's built-in passes will not render correctly with a standard projection.
 #[derive(Debug, Clone, Reflect, ExtractComponent)]
 #[reflect(Component, Default)]
 pub struct XrProjection {
     pub projection_matrix: Mat4,
     pub near: f32,
+    /// Distance of the far plane. [`None`] places the far plane at infinity.
+    pub far: Option<f32>,
+    /// Whether depth is reversed, mapping the near plane to 1.0 and the far plane to 0.0.
+    pub reversed_z: bool,
 }
 impl Component for XrProjection {
     const STORAGE_TYPE: StorageType = StorageType::Table;
@@ -61,44 +75,67 @@ impl Default for XrProjection {
     fn default() -> Self {
         Self {
             near: 0.1,
+            far: None,
+            reversed_z: true,
             projection_matrix: Mat4::IDENTITY,
         }
     }
 }
 
+impl XrProjection {
+    /// Returns the near and far plane distances, the far plane is [`f32::INFINITY`] for infinite projections.
+    pub fn depth_range(&self) -> (f32, f32) {
+        (self.near, self.far.unwrap_or(f32::INFINITY))
+    }
+}
+
 /// Marker component for an XR view. It is the backends responsibility to update this.
 #[derive(Clone, Copy, Component, ExtractComponent, Debug, Default)]
 #[require(Camera3d, XrProjection, XrTracker)]
 pub struct XrCamera(pub u32);
 
+/// The pose of the head, halfway between the [`XrCamera`]s, for gameplay code that needs it without
+/// querying the cameras, e.g. for spatial audio.
+///
+/// Updated by the backend every frame after [`TransformSystem::TransformPropagate`].
+/// While the views aren't located it keeps the last known pose.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Resource)]
+pub struct XrHeadPose {
+    /// Relative to the [`XrTrackingRoot`](crate::session::XrTrackingRoot).
+    pub local: Transform,
+    /// In world space.
+    pub global: GlobalTransform,
+}
+
 impl CameraProjection for XrProjection {
     fn update(&mut self, _width: f32, _height: f32) {}
 
     fn far(&self) -> f32 {
-        self.projection_matrix.to_cols_array()[14]
-            / (self.projection_matrix.to_cols_array()[10] + 1.0)
+        self.far.unwrap_or_else(|| {
+            self.projection_matrix.to_cols_array()[14]
+                / (self.projection_matrix.to_cols_array()[10] + 1.0)
+        })
     }
 
-    // TODO calculate this properly
-    fn get_frustum_corners(&self, _z_near: f32, _z_far: f32) -> [Vec3A; 8] {
-        let ndc_corners = [
-            Vec3A::new(1.0, -1.0, 1.0),   // Bottom-right far
-            Vec3A::new(1.0, 1.0, 1.0),    // Top-right far
-            Vec3A::new(-1.0, 1.0, 1.0),   // Top-left far
-            Vec3A::new(-1.0, -1.0, 1.0),  // Bottom-left far
-            Vec3A::new(1.0, -1.0, -1.0),  // Bottom-right near
-            Vec3A::new(1.0, 1.0, -1.0),   // Top-right near
-            Vec3A::new(-1.0, 1.0, -1.0),  // Top-left near
-            Vec3A::new(-1.0, -1.0, -1.0), // Bottom-left near
-        ];
-
-        let mut view_space_corners = [Vec3A::ZERO; 8];
+    fn get_frustum_corners(&self, z_near: f32, z_far: f32) -> [Vec3A; 8] {
+        // XR fovs are asymmetric, so the corners are found by unprojecting the corners of the screen
+        // and scaling the resulting rays to the requested depths. This works for finite and infinite projections.
         let inverse_matrix = self.projection_matrix.inverse();
-        for (i, corner) in ndc_corners.into_iter().enumerate() {
-            view_space_corners[i] = inverse_matrix.transform_point3a(corner);
-        }
-
-        view_space_corners
+        let corner = |x: f32, y: f32, z: f32| {
+            let point = inverse_matrix.project_point3a(Vec3A::new(x, y, 0.5));
+            point * (z / point.z)
+        };
+        // same order as the corners of a `PerspectiveProjection`, which cascaded shadows rely on
+        [
+            corner(1.0, -1.0, z_near),  // bottom right
+            corner(1.0, 1.0, z_near),   // top right
+            corner(-1.0, 1.0, z_near),  // top left
+            corner(-1.0, -1.0, z_near), // bottom left
+            corner(1.0, -1.0, z_far),   // bottom right
+            corner(1.0, 1.0, z_far),    // top right
+            corner(-1.0, 1.0, z_far),   // top left
+            corner(-1.0, -1.0, z_far),  // bottom left
+        ]
     }
 
     fn get_clip_from_view(&self) -> Mat4 {
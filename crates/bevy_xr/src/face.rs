@@ -0,0 +1,30 @@
+use bevy::{
+    ecs::component::Component,
+    prelude::{Deref, DerefMut},
+};
+
+/// Blendshape weights of a tracked face, updated every frame by the backend.
+///
+/// The order and number of weights depends on the expression set of the backend,
+/// e.g. the 70 expressions of `XrFaceExpression2FB` for `XR_FB_face_tracking2`.
+#[derive(Clone, Component, Debug, Default, Deref, DerefMut)]
+pub struct XrFaceWeights {
+    /// Weight of each expression from 0.0 to 1.0.
+    #[deref]
+    pub weights: Vec<f32>,
+    /// Confidence of regions of the face from 0.0 to 1.0, e.g. the upper and lower face.
+    pub confidences: Vec<f32>,
+    /// Whether the weights are from the latest frame.
+    /// While this is `false` the weights keep their last known values.
+    pub valid: bool,
+    /// Whether the weights of the eye looking expressions are valid.
+    /// Some runtimes only drive these when eye tracking is available.
+    pub eye_following_valid: bool,
+}
+
+impl XrFaceWeights {
+    /// Returns true if the weights are stale, see [`valid`](Self::valid).
+    pub fn is_stale(&self) -> bool {
+        !self.valid
+    }
+}
@@ -1,11 +1,14 @@
 use bevy::{
     ecs::{component::Component, entity::Entity, world::Command},
     log::warn,
-    math::bool,
+    math::{bool, Vec3},
     prelude::{Bundle, Commands, Deref, DerefMut, Resource, Transform, Visibility, World},
 };
 
-use crate::{session::XrTracker, spaces::XrSpaceLocationFlags};
+use crate::{
+    session::XrTracker,
+    spaces::{XrSpaceLocationFlags, XrSpaceVelocityFlags},
+};
 pub const HAND_JOINT_COUNT: usize = 26;
 
 pub fn spawn_hand_bones<T: Bundle>(
@@ -19,7 +22,7 @@ pub fn spawn_hand_bones<T: Bundle>(
     bones
 }
 
-#[derive(Clone, Copy, Component, Debug)]
+#[derive(Clone, Copy, Component, Debug, PartialEq, Eq)]
 pub enum HandSide {
     Left,
     Right,
@@ -31,6 +34,14 @@ pub struct LeftHand;
 #[derive(Clone, Copy, Component, Debug)]
 pub struct RightHand;
 
+/// Marks an entity tracking the aim pose of a controller, pointing forward from the controller.
+#[derive(Clone, Copy, Component, Debug)]
+pub struct AimPose;
+
+/// Marks an entity tracking the grip pose of a controller, centered in the closed hand holding it.
+#[derive(Clone, Copy, Component, Debug)]
+pub struct GripPose;
+
 /// Hand Joint Entities orderd
 #[derive(Deref, DerefMut, Component, Clone, Copy)]
 pub struct XrHandBoneEntities(pub [Entity; HAND_JOINT_COUNT]);
@@ -39,6 +50,97 @@ pub struct XrHandBoneEntities(pub [Entity; HAND_JOINT_COUNT]);
 #[derive(Clone, Copy, Component, Debug, DerefMut, Deref, Default)]
 pub struct XrHandBoneRadius(pub f32);
 
+/// The tracking state of a single hand joint, see [`XrHandJoints`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct XrHandJoint {
+    /// Pose of the joint relative to the hand tracker's reference space.
+    /// Keeps the last valid position and rotation while they aren't valid.
+    pub transform: Transform,
+    /// Radius of the joint in meters.
+    pub radius: f32,
+    pub linear_velocity: Vec3,
+    /// Angular velocity in radians per second, the direction is the axis of rotation.
+    pub angular_velocity: Vec3,
+    pub location_flags: XrSpaceLocationFlags,
+    pub velocity_flags: XrSpaceVelocityFlags,
+}
+
+impl XrHandJoint {
+    /// Returns true if both the position and rotation of the joint are valid.
+    pub fn is_valid(&self) -> bool {
+        self.location_flags.position_valid && self.location_flags.orientation_valid
+    }
+}
+
+/// All joints of a hand, indexed by [`HandBone`].
+///
+/// Add this to a hand tracker entity and the backend updates it every frame,
+/// which is easier to work with than the individual [`XrHandBoneEntities`] for gesture detection and similar.
+/// Where the joints come from is reported by the [`XrHandDataSource`] on the same entity.
+#[derive(Clone, Copy, Component, Debug, Deref, DerefMut)]
+#[require(XrHandDataSource)]
+pub struct XrHandJoints(pub [XrHandJoint; HAND_JOINT_COUNT]);
+
+impl XrHandJoints {
+    pub fn get(&self, bone: HandBone) -> &XrHandJoint {
+        &self.0[bone as usize]
+    }
+}
+
+impl Default for XrHandJoints {
+    fn default() -> Self {
+        Self([XrHandJoint::default(); HAND_JOINT_COUNT])
+    }
+}
+
+/// Where the joints of a hand tracker currently come from, updated with its [`XrHandJoints`].
+#[derive(Clone, Copy, Component, Debug, Default, PartialEq, Eq)]
+pub enum XrHandDataSource {
+    /// The backend can't tell, e.g. because the runtime doesn't report it.
+    #[default]
+    Unknown,
+    /// The joints are tracked from the unobstructed hand, i.e. real hand tracking.
+    Unobstructed,
+    /// The joints are synthesized from a controller the hand is holding.
+    Controller,
+}
+
+/// Makes the entity follow a single joint of a tracked hand, e.g. to attach a tool to the palm.
+///
+/// The entity is placed under the [`XrTrackingRoot`](crate::session::XrTrackingRoot) and the backend copies the pose
+/// of the joint from the hand tracker of `hand` into its [`Transform`] every frame.
+/// While the joint isn't tracked the entity keeps its last pose, and its [`XrSpaceLocationFlags`] are cleared.
+#[derive(Clone, Copy, Component, Debug)]
+#[require(Transform, Visibility, XrTracker, XrSpaceLocationFlags)]
+pub struct XrFollowHandJoint {
+    pub hand: HandSide,
+    pub joint: HandBone,
+}
+
+impl XrFollowHandJoint {
+    pub fn new(hand: HandSide, joint: HandBone) -> Self {
+        Self { hand, joint }
+    }
+
+    /// Follows the center of the palm of `hand`.
+    pub fn palm(hand: HandSide) -> Self {
+        Self::new(hand, HandBone::Palm)
+    }
+}
+
+/// Resource used to pause and resume hand tracking without destroying the hand trackers.
+///
+/// While this is `false`, backends skip locating hand joints and the hand bones are marked as not tracked.
+/// Useful to save power while the app doesn't need hands, e.g. in controller only sections.
+#[derive(Clone, Copy, Resource, Debug, DerefMut, Deref, PartialEq, Eq)]
+pub struct XrHandTrackingActive(pub bool);
+
+impl Default for XrHandTrackingActive {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
 #[repr(u8)]
 #[derive(Clone, Copy, Component, Debug)]
 #[require(
@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use bevy::ecs::event::Event;
+
+use crate::hands::HandSide;
+
+/// A single vibration of an [`XrHapticPattern`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct XrHapticStep {
+    /// Strength of the vibration from 0.0 to 1.0, 0.0 pauses for the duration of the step.
+    pub amplitude: f32,
+    /// Frequency of the vibration in Hz, [`None`] lets the backend pick the frequency.
+    pub frequency: Option<f32>,
+    pub duration: Duration,
+}
+
+/// A timed sequence of vibrations, played on a controller by sending an [`XrPlayHapticEvent`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct XrHapticPattern {
+    pub steps: Vec<XrHapticStep>,
+}
+
+impl XrHapticPattern {
+    /// Creates an empty pattern, playing it stops the current vibration of the controller.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a vibration at the frequency picked by the backend.
+    pub fn pulse(self, amplitude: f32, duration: Duration) -> Self {
+        self.step(XrHapticStep {
+            amplitude,
+            frequency: None,
+            duration,
+        })
+    }
+
+    /// Appends a vibration at `frequency` Hz.
+    pub fn pulse_with_frequency(self, amplitude: f32, frequency: f32, duration: Duration) -> Self {
+        self.step(XrHapticStep {
+            amplitude,
+            frequency: Some(frequency),
+            duration,
+        })
+    }
+
+    /// Appends a pause without vibration.
+    pub fn pause(self, duration: Duration) -> Self {
+        self.pulse(0.0, duration)
+    }
+
+    /// Appends `step`.
+    pub fn step(mut self, step: XrHapticStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Appends a ramp from amplitude `from` to `to` over `duration`, split into `steps` vibrations.
+    pub fn ramp(mut self, from: f32, to: f32, duration: Duration, steps: u32) -> Self {
+        let steps = steps.max(1);
+        let step_duration = duration / steps;
+        for i in 0..steps {
+            let t = if steps == 1 {
+                1.0
+            } else {
+                i as f32 / (steps - 1) as f32
+            };
+            self = self.pulse(from + (to - from) * t, step_duration);
+        }
+        self
+    }
+
+    /// Appends the steps of `self` `times` times in total.
+    pub fn repeat(mut self, times: u32) -> Self {
+        let steps = self.steps.clone();
+        for _ in 1..times {
+            self.steps.extend_from_slice(&steps);
+        }
+        self
+    }
+
+    /// A short, strong pulse.
+    pub fn click() -> Self {
+        Self::new().pulse(0.8, Duration::from_millis(15))
+    }
+
+    /// Two [`click`](Self::click)s in quick succession.
+    pub fn double_click() -> Self {
+        Self::click()
+            .pause(Duration::from_millis(80))
+            .pulse(0.8, Duration::from_millis(15))
+    }
+
+    /// The total duration of all steps.
+    pub fn duration(&self) -> Duration {
+        self.steps.iter().map(|step| step.duration).sum()
+    }
+}
+
+/// Plays `pattern` on the controller held in `hand`, replacing the pattern currently playing on it.
+#[derive(Clone, Debug, Event)]
+pub struct XrPlayHapticEvent {
+    pub hand: HandSide,
+    pub pattern: XrHapticPattern,
+}
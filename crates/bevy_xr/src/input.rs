@@ -0,0 +1,130 @@
+use bevy::ecs::system::Resource;
+use bevy::math::Vec2;
+
+use crate::hands::HandSide;
+
+/// The state of the common inputs of both controllers, independent of the interaction profile.
+///
+/// Updated every frame by the backend. Inputs the current controllers don't have stay at their default value,
+/// e.g. the face buttons on controllers without them.
+/// The buttons are also available as [`ButtonInput<XrStandardButton>`](bevy::input::ButtonInput).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Resource)]
+pub struct XrStandardInput {
+    /// How far the left trigger is pulled, from 0.0 to 1.0.
+    pub left_trigger: f32,
+    pub right_trigger: f32,
+    /// How far the left grip is squeezed, from 0.0 to 1.0.
+    /// Controllers with a grip button report 0.0 or 1.0.
+    pub left_grip: f32,
+    pub right_grip: f32,
+    /// Position of the left thumbstick, or trackpad on controllers without a thumbstick,
+    /// from -1.0 to 1.0 on both axes with positive Y pointing forward.
+    pub left_stick: Vec2,
+    pub right_stick: Vec2,
+    pub left_stick_click: bool,
+    pub right_stick_click: bool,
+    /// Whether the thumb rests on the left thumbstick or trackpad.
+    /// Stays `false` on controllers that can't detect it, e.g. the Windows Mixed Reality thumbsticks.
+    pub left_stick_touch: bool,
+    pub right_stick_touch: bool,
+    /// The lower face button of the right controller.
+    pub a_button: bool,
+    /// The upper face button of the right controller.
+    pub b_button: bool,
+    /// The lower face button of the left controller.
+    pub x_button: bool,
+    /// The upper face button of the left controller.
+    pub y_button: bool,
+    pub menu: bool,
+}
+
+impl XrStandardInput {
+    pub fn trigger(&self, hand: HandSide) -> f32 {
+        match hand {
+            HandSide::Left => self.left_trigger,
+            HandSide::Right => self.right_trigger,
+        }
+    }
+
+    pub fn grip(&self, hand: HandSide) -> f32 {
+        match hand {
+            HandSide::Left => self.left_grip,
+            HandSide::Right => self.right_grip,
+        }
+    }
+
+    pub fn stick(&self, hand: HandSide) -> Vec2 {
+        match hand {
+            HandSide::Left => self.left_stick,
+            HandSide::Right => self.right_stick,
+        }
+    }
+
+    pub fn stick_clicked(&self, hand: HandSide) -> bool {
+        match hand {
+            HandSide::Left => self.left_stick_click,
+            HandSide::Right => self.right_stick_click,
+        }
+    }
+
+    pub fn stick_touched(&self, hand: HandSide) -> bool {
+        match hand {
+            HandSide::Left => self.left_stick_touch,
+            HandSide::Right => self.right_stick_touch,
+        }
+    }
+
+    /// Returns whether `button` is pressed.
+    ///
+    /// Triggers and grips count as pressed past [`XrStandardInput::PRESS_THRESHOLD`].
+    pub fn pressed(&self, button: XrStandardButton) -> bool {
+        match button {
+            XrStandardButton::LeftTrigger => self.left_trigger > Self::PRESS_THRESHOLD,
+            XrStandardButton::RightTrigger => self.right_trigger > Self::PRESS_THRESHOLD,
+            XrStandardButton::LeftGrip => self.left_grip > Self::PRESS_THRESHOLD,
+            XrStandardButton::RightGrip => self.right_grip > Self::PRESS_THRESHOLD,
+            XrStandardButton::LeftStick => self.left_stick_click,
+            XrStandardButton::RightStick => self.right_stick_click,
+            XrStandardButton::A => self.a_button,
+            XrStandardButton::B => self.b_button,
+            XrStandardButton::X => self.x_button,
+            XrStandardButton::Y => self.y_button,
+            XrStandardButton::Menu => self.menu,
+        }
+    }
+
+    /// Value above which analog triggers and grips count as pressed.
+    pub const PRESS_THRESHOLD: f32 = 0.5;
+}
+
+/// The buttons of [`XrStandardInput`], for use with [`ButtonInput`](bevy::input::ButtonInput).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum XrStandardButton {
+    LeftTrigger,
+    RightTrigger,
+    LeftGrip,
+    RightGrip,
+    LeftStick,
+    RightStick,
+    A,
+    B,
+    X,
+    Y,
+    Menu,
+}
+
+impl XrStandardButton {
+    pub const ALL: [Self; 11] = [
+        Self::LeftTrigger,
+        Self::RightTrigger,
+        Self::LeftGrip,
+        Self::RightGrip,
+        Self::LeftStick,
+        Self::RightStick,
+        Self::A,
+        Self::B,
+        Self::X,
+        Self::Y,
+        Self::Menu,
+    ];
+}
@@ -1,6 +1,11 @@
 pub mod actions;
+pub mod battery;
+pub mod body;
 pub mod camera;
+pub mod face;
 pub mod hands;
+pub mod haptics;
+pub mod input;
 pub mod session;
 pub mod types;
 pub mod spaces;
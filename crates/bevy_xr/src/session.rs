@@ -89,12 +89,21 @@ pub enum XrRenderSet {
 pub struct XrRootTransform(pub GlobalTransform);
 
 /// Component used to specify the entity we should use as the tracking root.
+///
+/// Tracked entities are located relative to it, so moving or rotating its [`Transform`] moves the user
+/// through the world, e.g. for locomotion. The [`XrRootTransform`] used for rendering follows it every frame.
 #[derive(Component)]
 #[require(Transform, Visibility)]
 pub struct XrTrackingRoot;
 #[derive(Resource)]
 struct TrackingRootRes(Entity);
 
+/// Send this to move and rotate the [`XrTrackingRoot`] so the headset is above the world origin and looking along -Z.
+///
+/// The root is only rotated around the Y axis and keeps its height, so the floor stays in place.
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct XrRecenterEvent;
+
 /// Makes the entity a child of the XrTrackingRoot if the entity has no parent
 #[derive(Clone, Copy, Hash, PartialEq, Eq, Reflect, Debug, Default)]
 pub struct XrTracker;
@@ -124,7 +133,8 @@ pub struct XrSessionPlugin {
 
 impl Plugin for XrSessionPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<XrDestroySessionRender>();
+        app.init_resource::<XrDestroySessionRender>()
+            .init_resource::<XrSessionVisibility>();
         let mut xr_first = Schedule::new(XrFirst);
         xr_first.set_executor_kind(bevy::ecs::schedule::ExecutorKind::Simple);
         app.add_event::<XrCreateSessionEvent>()
@@ -133,6 +143,8 @@ impl Plugin for XrSessionPlugin {
             .add_event::<XrEndSessionEvent>()
             .add_event::<XrRequestExitEvent>()
             .add_event::<XrStateChanged>()
+            .add_event::<XrInstanceLost>()
+            .add_event::<XrRecenterEvent>()
             .add_event::<XrSessionCreatedEvent>()
             .add_event::<XrSessionDestroyedEvent>()
             .init_schedule(XrSessionCreated)
@@ -223,6 +235,12 @@ fn exits_session_on_app_exit(mut request_exit: EventWriter<XrRequestExitEvent>)
     request_exit.send_default();
 }
 
+/// Event sent by backends when the connection to the XR runtime was lost, together with an [`XrStateChanged`] to [`XrState::Lost`].
+///
+/// XR can't be used for the rest of the app's lifetime, apps can continue without XR or exit.
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct XrInstanceLost;
+
 /// Event sent by backends whenever [`XrState`] is changed.
 #[derive(Event, Clone, Copy, Deref)]
 pub struct XrStateChanged(pub XrState);
@@ -248,6 +266,24 @@ pub enum XrState {
         /// Whether we should automatically restart the session
         should_restart: bool,
     },
+    /// The connection to the XR runtime was lost, e.g. because the runtime was shut down or the headset was disconnected.
+    /// The session has been destroyed and no new one can be created, see [`XrInstanceLost`].
+    Lost,
+}
+
+/// A resource in the main world describing how a running session is presented to the user.
+///
+/// Only meaningful while the [`XrState`] is [`Running`](XrState::Running), otherwise this is [`Hidden`](Self::Hidden).
+#[derive(Clone, Copy, Debug, Default, Resource, PartialEq, Eq, PartialOrd, Ord)]
+pub enum XrSessionVisibility {
+    /// The session is running, but the frames aren't shown to the user.
+    #[default]
+    Hidden,
+    /// The frames are shown to the user, but the session doesn't receive input,
+    /// e.g. because a system menu is open on top of the app.
+    Visible,
+    /// The frames are shown to the user and the session receives input.
+    Focused,
 }
 
 pub fn auto_handle_session(
@@ -300,16 +336,16 @@ pub fn status_changed_to(
     }
 }
 
-/// A [`Condition`](bevy::ecs::schedule::Condition) system that says if the XR session is available. Returns true as long as [`XrState`] exists and isn't [`Unavailable`](XrStatus::Unavailable).
+/// A [`Condition`](bevy::ecs::schedule::Condition) system that says if the XR session is available. Returns true as long as [`XrState`] exists and isn't [`Unavailable`](XrStatus::Unavailable) or [`Lost`](XrState::Lost).
 /// When using backend specific resources use the backend specific condition
 pub fn session_available(status: Option<Res<XrState>>) -> bool {
-    status.is_some_and(|s| *s != XrState::Unavailable)
+    status.is_some_and(|s| !matches!(*s, XrState::Unavailable | XrState::Lost))
 }
 
 pub fn session_created(status: Option<Res<XrState>>) -> bool {
     !matches!(
         status.as_deref(),
-        Some(XrState::Unavailable | XrState::Available) | None
+        Some(XrState::Unavailable | XrState::Available | XrState::Lost) | None
     )
 }
 
@@ -324,6 +360,29 @@ pub fn session_running(status: Option<Res<XrState>>) -> bool {
     matches!(status.as_deref(), Some(XrState::Running))
 }
 
+/// A [`Condition`](bevy::ecs::schedule::Condition) system that says if the frames of the XR session are shown to the user.
+pub fn session_visible(
+    status: Option<Res<XrState>>,
+    visibility: Option<Res<XrSessionVisibility>>,
+) -> bool {
+    session_running(status) && visibility.is_some_and(|v| *v >= XrSessionVisibility::Visible)
+}
+
+/// A [`Condition`](bevy::ecs::schedule::Condition) system that says if the XR session receives input.
+/// Use this for systems that should only process input while the user interacts with the app.
+pub fn session_focused(
+    status: Option<Res<XrState>>,
+    visibility: Option<Res<XrSessionVisibility>>,
+) -> bool {
+    session_running(status) && visibility.is_some_and(|v| *v == XrSessionVisibility::Focused)
+}
+
+/// A [`Condition`](bevy::ecs::schedule::Condition) system that says if the XR session is stopping,
+/// e.g. to save state before the session is ended.
+pub fn session_stopping(status: Option<Res<XrState>>) -> bool {
+    matches!(status.as_deref(), Some(XrState::Stopping))
+}
+
 /// A function that returns a [`Condition`](bevy::ecs::schedule::Condition) system that says if the [`XrState`] is in a specific state
 pub fn state_equals(status: XrState) -> impl FnMut(Option<Res<XrState>>) -> bool {
     move |state: Option<Res<XrState>>| state.is_some_and(|s| *s == status)
@@ -11,6 +11,17 @@ use crate::session::XrTracker;
 #[require(XrSpaceLocationFlags, Transform, Visibility, XrTracker)]
 pub struct XrSpace(u64);
 
+/// Add this to an entity with an [`XrSpace`] to also have its velocity located every frame and stored in its [`XrVelocity`].
+///
+/// Locating the velocity costs an extra call per space, so it's only done for spaces with this marker.
+#[derive(Component, Clone, Copy, Reflect, Debug, ExtractComponent, Default)]
+#[require(XrVelocity)]
+pub struct XrTrackVelocity;
+
+/// The velocity of a space, located every frame for spaces with [`XrTrackVelocity`].
+///
+/// Only the parts marked valid in the [`XrSpaceVelocityFlags`] are updated, the others keep their last value.
+/// Adding only this component doesn't locate the velocity of an [`XrSpace`], hand joints have it filled in by hand tracking.
 #[derive(Component, Clone, Copy, Reflect, Debug, ExtractComponent, Default)]
 #[require(XrSpaceVelocityFlags)]
 pub struct XrVelocity {
@@ -34,6 +45,16 @@ impl XrVelocity {
 #[derive(Event, Clone, Copy, Deref, DerefMut)]
 pub struct XrDestroySpace(pub XrSpace);
 
+/// Sent when the origin of the [`XrPrimaryReferenceSpace`] changed, e.g. because the user recentered.
+///
+/// Tracked transforms jump by the inverse of `pose_in_previous_space` when this happens,
+/// apps can use this to smooth the transition or to move content along.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct XrReferenceSpaceChanged {
+    /// The pose of the new origin in the previous reference space, [`None`] if the runtime doesn't know it.
+    pub pose_in_previous_space: Option<Isometry3d>,
+}
+
 #[repr(transparent)]
 #[derive(
     Clone, Copy, Hash, PartialEq, Eq, Reflect, Debug, Component, Deref, DerefMut, ExtractComponent,
@@ -46,12 +67,35 @@ pub struct XrReferenceSpace(pub XrSpace);
 )]
 pub struct XrPrimaryReferenceSpace(pub XrReferenceSpace);
 
+/// A spatial anchor, a space fixed to a point in the real world that the runtime keeps tracking.
+///
+/// Anchors can be persisted by the runtime and loaded again in later sessions.
+#[repr(transparent)]
+#[derive(
+    Clone, Copy, Hash, PartialEq, Eq, Reflect, Debug, Component, Deref, DerefMut, ExtractComponent,
+)]
+pub struct XrSpatialAnchor(pub XrSpace);
+
 #[derive(
     Clone, Copy, Hash, PartialEq, Eq, Reflect, Debug, Component, ExtractComponent, Default,
 )]
 pub struct XrSpaceLocationFlags {
+    /// The position is valid, but might be inferred or last known rather than actively tracked.
+    pub position_valid: bool,
+    /// The orientation is valid, but might be inferred or last known rather than actively tracked.
+    pub orientation_valid: bool,
     pub position_tracked: bool,
-    pub rotation_tracked: bool,
+    pub orientation_tracked: bool,
+}
+
+impl XrSpaceLocationFlags {
+    /// Returns true if both position and orientation are valid and actively tracked.
+    pub fn fully_tracked(&self) -> bool {
+        self.position_valid
+            && self.orientation_valid
+            && self.position_tracked
+            && self.orientation_tracked
+    }
 }
 
 #[derive(
@@ -21,7 +21,7 @@ fn draw_hand_gizmos(
     )>,
 ) {
     for (transform, bone, radius, flags) in &query {
-        if (!flags.position_tracked) || (!flags.rotation_tracked) {
+        if (!flags.position_tracked) || (!flags.orientation_tracked) {
             continue;
         }
         let pose = transform.compute_transform();
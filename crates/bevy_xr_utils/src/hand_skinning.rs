@@ -0,0 +1,169 @@
+//! Poses the skeleton of a rigged hand model, e.g. a glTF scene, with the joints of a hand tracker.
+//!
+//! Spawn the model as a child of the [`XrTrackingRoot`](bevy_mod_xr::session::XrTrackingRoot) and add an
+//! [`XrHandSkin`] pointing at the entity with the [`XrHandJoints`]. The bones of the model are found by their
+//! [`Name`] once the scene is spawned, using the [`XrHandBoneNames`] of the skin.
+use bevy::math::Affine3A;
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use bevy_mod_xr::hands::{HandBone, XrHandJoints, HAND_JOINT_COUNT};
+
+pub struct HandSkinningPlugin;
+
+impl Plugin for HandSkinningPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            (find_skin_bones, update_skin_bones)
+                .chain()
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// The names of the bones of a hand model, indexed by [`HandBone`].
+///
+/// By default every bone is named like its [`HandBone`] variant, e.g. `IndexProximal`.
+/// Bones without a name aren't posed, which is common for the palm and the finger tips.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XrHandBoneNames(pub [Option<String>; HAND_JOINT_COUNT]);
+
+impl XrHandBoneNames {
+    /// Returns names without any bones, to be filled with [`with_name`](Self::with_name).
+    pub fn empty() -> Self {
+        Self(std::array::from_fn(|_| None))
+    }
+
+    pub fn with_name(mut self, bone: HandBone, name: impl Into<String>) -> Self {
+        self.0[bone as usize] = Some(name.into());
+        self
+    }
+
+    pub fn without(mut self, bone: HandBone) -> Self {
+        self.0[bone as usize] = None;
+        self
+    }
+
+    pub fn get(&self, bone: HandBone) -> Option<&str> {
+        self.0[bone as usize].as_deref()
+    }
+}
+
+impl Default for XrHandBoneNames {
+    fn default() -> Self {
+        Self(HandBone::get_all_bones().map(|bone| Some(format!("{bone:?}"))))
+    }
+}
+
+/// Poses the bones of the hand model on this entity with the [`XrHandJoints`] of `tracker`.
+#[derive(Component, Clone, Debug)]
+#[require(Transform)]
+pub struct XrHandSkin {
+    /// The entity with the [`XrHandJoints`], e.g. a hand tracker.
+    pub tracker: Entity,
+    pub bone_names: XrHandBoneNames,
+    /// Applied to the rotation of every joint, to match the bone orientation of the model.
+    /// OpenXR joints point along -Z with the back of the hand towards +Y.
+    pub rotation_offset: Quat,
+    /// The bone entities found in the model, empty until the model is spawned.
+    bones: Vec<(HandBone, Entity)>,
+}
+
+impl XrHandSkin {
+    pub fn new(tracker: Entity) -> Self {
+        Self {
+            tracker,
+            bone_names: default(),
+            rotation_offset: Quat::IDENTITY,
+            bones: Vec::new(),
+        }
+    }
+
+    pub fn with_bone_names(mut self, bone_names: XrHandBoneNames) -> Self {
+        self.bone_names = bone_names;
+        self.bones.clear();
+        self
+    }
+
+    pub fn with_rotation_offset(mut self, rotation_offset: Quat) -> Self {
+        self.rotation_offset = rotation_offset;
+        self
+    }
+
+    /// The bone entities found in the model, in [`HandBone`] order.
+    pub fn bones(&self) -> &[(HandBone, Entity)] {
+        &self.bones
+    }
+}
+
+fn find_skin_bones(
+    mut skins: Query<(Entity, &mut XrHandSkin)>,
+    children: Query<&Children>,
+    names: Query<&Name>,
+) {
+    for (entity, mut skin) in &mut skins {
+        if !skin.bones.is_empty() {
+            continue;
+        }
+        let mut bones = Vec::new();
+        for descendant in children.iter_descendants(entity) {
+            let Ok(name) = names.get(descendant) else {
+                continue;
+            };
+            if let Some(bone) = HandBone::get_all_bones()
+                .into_iter()
+                .find(|bone| skin.bone_names.get(*bone) == Some(name.as_str()))
+            {
+                bones.push((bone, descendant));
+            }
+        }
+        // parents have to be posed before their children, which the bone order guarantees
+        bones.sort_by_key(|(bone, _)| *bone as u8);
+        if !bones.is_empty() {
+            skin.bones = bones;
+        }
+    }
+}
+
+fn update_skin_bones(
+    skins: Query<(Entity, &XrHandSkin, &Transform)>,
+    joints_query: Query<&XrHandJoints>,
+    parents: Query<&Parent>,
+    mut transforms: Query<&mut Transform, Without<XrHandSkin>>,
+) {
+    for (skin_entity, skin, skin_transform) in &skins {
+        let Ok(joints) = joints_query.get(skin.tracker) else {
+            continue;
+        };
+        let skin_inverse = skin_transform.compute_affine().inverse();
+        for (bone, entity) in &skin.bones {
+            let joint = joints.get(*bone);
+            if !joint.is_valid() {
+                continue;
+            }
+            // the joints are relative to the tracking root, like the skin itself
+            let mut target = joint.transform;
+            target.rotation *= skin.rotation_offset;
+            let target = skin_inverse * target.compute_affine();
+
+            // the transform of the parent relative to the skin, with the bones posed so far
+            let mut parent = Affine3A::IDENTITY;
+            let mut ancestor = parents.get(*entity).map(|p| p.get()).ok();
+            while let Some(current) = ancestor.filter(|e| *e != skin_entity) {
+                if let Ok(transform) = transforms.get(current) {
+                    parent = transform.compute_affine() * parent;
+                }
+                ancestor = parents.get(current).map(|p| p.get()).ok();
+            }
+
+            let Ok(mut transform) = transforms.get_mut(*entity) else {
+                continue;
+            };
+            let (_, rotation, translation) =
+                (parent.inverse() * target).to_scale_rotation_translation();
+            // keep the scale of the model's bones
+            transform.translation = translation;
+            transform.rotation = rotation;
+        }
+    }
+}
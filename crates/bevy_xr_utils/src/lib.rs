@@ -1,4 +1,10 @@
 pub mod hand_gizmos;
+pub mod hand_skinning;
+#[cfg(not(target_family = "wasm"))]
+pub mod pointer;
+pub mod raycast;
+pub mod space_gizmos;
+pub mod surface_snapping;
 #[cfg(not(target_family = "wasm"))]
 pub mod tracking_utils;
 #[cfg(not(target_family = "wasm"))]
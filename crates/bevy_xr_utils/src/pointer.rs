@@ -0,0 +1,262 @@
+//! A laser pointer for each controller, using the controllers' aim poses.
+//!
+//! Add [`OxrPointerPlugin`] to your app. Every frame the pointers cast a ray against all meshes,
+//! the nearest entity hit gets the [`XrPointerHovered`] component, and an [`XrPointerClick`] is sent
+//! when the trigger is pressed while hovering an entity.
+use bevy::picking::mesh_picking::ray_cast::{MeshRayCast, RayCastSettings, RayCastVisibility};
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use bevy::utils::HashSet;
+use bevy_mod_openxr::{
+    action_binding::{OxrSendActionBindings, OxrSuggestActionBinding},
+    action_set_attaching::OxrAttachActionSet,
+    action_set_syncing::OxrActionSets,
+    openxr_session_available, openxr_session_running,
+    resources::OxrInstance,
+    session::OxrSession,
+};
+use bevy_mod_xr::{
+    hands::HandSide,
+    session::{XrPreDestroySession, XrSessionCreated},
+};
+use openxr::Posef;
+
+pub struct OxrPointerPlugin {
+    /// Color of the laser while it isn't hovering anything.
+    pub laser_color: Color,
+    /// Color of the laser while it is hovering an entity.
+    pub hover_color: Color,
+    /// Maximum length of the laser in meters. Entities further away can't be hovered.
+    pub max_length: f32,
+}
+
+impl Default for OxrPointerPlugin {
+    fn default() -> Self {
+        Self {
+            laser_color: Color::WHITE,
+            hover_color: Color::srgb(0.2, 0.6, 1.0),
+            max_length: 10.0,
+        }
+    }
+}
+
+impl Plugin for OxrPointerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<XrPointerClick>()
+            .insert_resource(XrPointerSettings {
+                laser_color: self.laser_color,
+                hover_color: self.hover_color,
+                max_length: self.max_length,
+            })
+            .add_systems(Startup, create_actions.run_if(openxr_session_available))
+            .add_systems(OxrSendActionBindings, suggest_action_bindings)
+            .add_systems(XrSessionCreated, (attach_set, spawn_pointers))
+            .add_systems(XrPreDestroySession, despawn_pointers)
+            .add_systems(
+                PostUpdate,
+                update_pointers
+                    .after(TransformSystem::TransformPropagate)
+                    .run_if(openxr_session_running),
+            );
+    }
+}
+
+/// Appearance of the pointers. Can be changed at runtime.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct XrPointerSettings {
+    pub laser_color: Color,
+    pub hover_color: Color,
+    pub max_length: f32,
+}
+
+/// A laser pointer following the aim pose of a controller.
+#[derive(Component, Clone, Copy, Debug)]
+#[require(XrPointerState)]
+pub struct XrPointer(pub HandSide);
+
+/// The hover and click state of an [`XrPointer`].
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct XrPointerState {
+    /// The entity currently hovered by this pointer.
+    pub hovered: Option<Entity>,
+    /// The world space position where the pointer hits the hovered entity.
+    pub hit_position: Option<Vec3>,
+    /// Whether the trigger of this pointer's controller is pressed.
+    pub pressed: bool,
+}
+
+/// Marker component added to every entity hovered by at least one [`XrPointer`].
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct XrPointerHovered;
+
+/// Event sent when the trigger is pressed while an [`XrPointer`] is hovering an entity.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct XrPointerClick {
+    pub pointer: Entity,
+    pub hand: HandSide,
+    pub target: Entity,
+    pub position: Vec3,
+}
+
+#[derive(Resource)]
+struct PointerActions {
+    set: openxr::ActionSet,
+    left_aim: openxr::Action<Posef>,
+    right_aim: openxr::Action<Posef>,
+    left_select: openxr::Action<bool>,
+    right_select: openxr::Action<bool>,
+}
+
+fn create_actions(
+    instance: Res<OxrInstance>,
+    mut action_sets: ResMut<OxrActionSets>,
+    mut cmds: Commands,
+) {
+    let set = instance
+        .create_action_set("xr_pointer", "XR Pointer", 0)
+        .unwrap();
+    let left_aim = set
+        .create_action("left_aim", "Left Pointer Aim Pose", &[])
+        .unwrap();
+    let right_aim = set
+        .create_action("right_aim", "Right Pointer Aim Pose", &[])
+        .unwrap();
+    let left_select = set
+        .create_action("left_select", "Left Pointer Select", &[])
+        .unwrap();
+    let right_select = set
+        .create_action("right_select", "Right Pointer Select", &[])
+        .unwrap();
+
+    action_sets.insert("xr_pointer", set.clone());
+    cmds.insert_resource(PointerActions {
+        set,
+        left_aim,
+        right_aim,
+        left_select,
+        right_select,
+    });
+}
+
+fn suggest_action_bindings(
+    actions: Res<PointerActions>,
+    mut bindings: EventWriter<OxrSuggestActionBinding>,
+) {
+    for (profile, select) in [
+        (
+            "/interaction_profiles/oculus/touch_controller",
+            "trigger/value",
+        ),
+        (
+            "/interaction_profiles/valve/index_controller",
+            "trigger/click",
+        ),
+        (
+            "/interaction_profiles/khr/simple_controller",
+            "select/click",
+        ),
+    ] {
+        for (side, aim, select_action) in [
+            ("left", &actions.left_aim, &actions.left_select),
+            ("right", &actions.right_aim, &actions.right_select),
+        ] {
+            bindings.send(OxrSuggestActionBinding {
+                action: aim.as_raw(),
+                interaction_profile: profile.into(),
+                bindings: vec![format!("/user/hand/{side}/input/aim/pose").into()],
+            });
+            bindings.send(OxrSuggestActionBinding {
+                action: select_action.as_raw(),
+                interaction_profile: profile.into(),
+                bindings: vec![format!("/user/hand/{side}/input/{select}").into()],
+            });
+        }
+    }
+}
+
+fn attach_set(actions: Res<PointerActions>, mut attach: EventWriter<OxrAttachActionSet>) {
+    attach.send(OxrAttachActionSet(actions.set.clone()));
+}
+
+fn spawn_pointers(actions: Res<PointerActions>, session: Res<OxrSession>, mut cmds: Commands) {
+    for (side, action) in [
+        (HandSide::Left, &actions.left_aim),
+        (HandSide::Right, &actions.right_aim),
+    ] {
+        match session.create_action_space(action, openxr::Path::NULL, Isometry3d::IDENTITY) {
+            Ok(space) => {
+                cmds.spawn((space, XrPointer(side)));
+            }
+            Err(err) => error!("Unable to create pointer space: {}", err.to_string()),
+        }
+    }
+}
+
+fn despawn_pointers(query: Query<Entity, With<XrPointer>>, mut cmds: Commands) {
+    for e in &query {
+        cmds.entity(e).despawn_recursive();
+    }
+}
+
+fn update_pointers(
+    mut ray_cast: MeshRayCast,
+    settings: Res<XrPointerSettings>,
+    actions: Res<PointerActions>,
+    session: Res<OxrSession>,
+    mut pointers: Query<(Entity, &GlobalTransform, &XrPointer, &mut XrPointerState)>,
+    hovered: Query<Entity, With<XrPointerHovered>>,
+    mut clicks: EventWriter<XrPointerClick>,
+    mut gizmos: Gizmos,
+    mut cmds: Commands,
+) {
+    let mut currently_hovered = HashSet::new();
+    for (entity, transform, pointer, mut state) in &mut pointers {
+        let select = match pointer.0 {
+            HandSide::Left => &actions.left_select,
+            HandSide::Right => &actions.right_select,
+        };
+        let pressed = select
+            .state(&session, openxr::Path::NULL)
+            .is_ok_and(|s| s.is_active && s.current_state);
+        let just_pressed = pressed && !state.pressed;
+        state.pressed = pressed;
+
+        let ray = Ray3d::new(transform.translation(), transform.forward());
+        let ray_cast_settings =
+            RayCastSettings::default().with_visibility(RayCastVisibility::Visible);
+        let hit = ray_cast
+            .cast_ray(ray, &ray_cast_settings)
+            .first()
+            .filter(|(_, hit)| hit.distance <= settings.max_length)
+            .map(|(e, hit)| (*e, hit.point));
+
+        state.hovered = hit.map(|(e, _)| e);
+        state.hit_position = hit.map(|(_, p)| p);
+
+        let (end, color) = match hit {
+            Some((target, position)) => {
+                currently_hovered.insert(target);
+                if just_pressed {
+                    clicks.send(XrPointerClick {
+                        pointer: entity,
+                        hand: pointer.0,
+                        target,
+                        position,
+                    });
+                }
+                (position, settings.hover_color)
+            }
+            None => (ray.get_point(settings.max_length), settings.laser_color),
+        };
+        gizmos.line(ray.origin, end, color);
+    }
+
+    for e in &hovered {
+        if !currently_hovered.remove(&e) {
+            cmds.entity(e).remove::<XrPointerHovered>();
+        }
+    }
+    for e in currently_hovered {
+        cmds.entity(e).try_insert(XrPointerHovered);
+    }
+}
@@ -0,0 +1,74 @@
+//! Ray casts from tracked entities into the scene, e.g. for gaze or controller based selection.
+//!
+//! Add [`XrRaycastPlugin`] and an [`XrRaycaster`] to any entity with a transform, like an entity with an
+//! [`AimPose`](bevy_mod_xr::hands::AimPose) or an eye gaze space. Every frame a ray is cast along its forward
+//! direction against all visible meshes, and the nearest hit is stored in its [`XrRaycastHit`].
+use bevy::picking::mesh_picking::ray_cast::{MeshRayCast, RayCastSettings, RayCastVisibility};
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+pub struct XrRaycastPlugin;
+
+impl Plugin for XrRaycastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            update_raycast_hits.after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Casts a ray along the forward direction of this entity every frame.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+#[require(XrRaycastHit)]
+pub struct XrRaycaster {
+    /// Maximum length of the ray in meters. Entities further away aren't hit.
+    pub max_distance: f32,
+}
+
+impl Default for XrRaycaster {
+    fn default() -> Self {
+        Self { max_distance: 10.0 }
+    }
+}
+
+/// The nearest entity hit by the ray of an [`XrRaycaster`] this frame.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct XrRaycastHit(pub Option<XrRaycastTarget>);
+
+/// An entity hit by a ray.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct XrRaycastTarget {
+    pub entity: Entity,
+    /// The world space position where the ray hit the entity.
+    pub position: Vec3,
+    /// The world space normal of the surface at [`position`](Self::position).
+    pub normal: Vec3,
+    /// Distance from the ray origin to [`position`](Self::position).
+    pub distance: f32,
+}
+
+fn update_raycast_hits(
+    mut ray_cast: MeshRayCast,
+    mut raycasters: Query<(Entity, &GlobalTransform, &XrRaycaster, &mut XrRaycastHit)>,
+) {
+    for (entity, transform, raycaster, mut hit) in &mut raycasters {
+        let ray = Ray3d::new(transform.translation(), transform.forward());
+        // the raycaster itself might have a mesh, e.g. a controller model
+        let filter = |e: Entity| e != entity;
+        let settings = RayCastSettings::default()
+            .with_visibility(RayCastVisibility::Visible)
+            .with_filter(&filter);
+        let target = ray_cast
+            .cast_ray(ray, &settings)
+            .first()
+            .filter(|(_, hit)| hit.distance <= raycaster.max_distance)
+            .map(|(e, hit)| XrRaycastTarget {
+                entity: *e,
+                position: hit.point,
+                normal: hit.normal,
+                distance: hit.distance,
+            });
+        hit.set_if_neq(XrRaycastHit(target));
+    }
+}
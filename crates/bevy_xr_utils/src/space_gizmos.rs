@@ -0,0 +1,68 @@
+//! Debug gizmos for every located [`XrSpace`] and the origin of the tracking root.
+use bevy::color::palettes::css;
+use bevy::{prelude::*, transform::TransformSystem};
+use bevy_mod_xr::session::XrTrackingRoot;
+use bevy_mod_xr::spaces::{XrSpace, XrSpaceLocationFlags};
+
+/// Draws axes and a sphere at every entity with an [`XrSpace`], colored by its [`XrSpaceLocationFlags`]:
+/// green while tracked, yellow while valid but not tracked, e.g. extrapolated, and red while invalid.
+///
+/// The origin of the primary reference space, where the [`XrTrackingRoot`] is, gets larger axes with a white sphere.
+pub struct SpaceGizmosPlugin {
+    /// Length of the axes drawn at every space in meters.
+    pub axis_length: f32,
+}
+
+impl Default for SpaceGizmosPlugin {
+    fn default() -> Self {
+        Self { axis_length: 0.1 }
+    }
+}
+
+impl Plugin for SpaceGizmosPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SpaceGizmosSettings {
+            axis_length: self.axis_length,
+        })
+        .add_systems(
+            PostUpdate,
+            draw_space_gizmos.after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Settings of the [`SpaceGizmosPlugin`], can be changed at runtime.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct SpaceGizmosSettings {
+    pub axis_length: f32,
+}
+
+fn draw_space_gizmos(
+    mut gizmos: Gizmos,
+    settings: Res<SpaceGizmosSettings>,
+    spaces: Query<(&GlobalTransform, &XrSpaceLocationFlags), With<XrSpace>>,
+    root: Query<&GlobalTransform, With<XrTrackingRoot>>,
+) {
+    for (transform, flags) in &spaces {
+        let pose = transform.compute_transform();
+        let isometry = Isometry3d::new(pose.translation, pose.rotation);
+        gizmos.axes(isometry, settings.axis_length);
+        gizmos.sphere(isometry, settings.axis_length * 0.2, tracking_color(flags));
+    }
+    for transform in &root {
+        let pose = transform.compute_transform();
+        let isometry = Isometry3d::new(pose.translation, pose.rotation);
+        gizmos.axes(isometry, settings.axis_length * 3.0);
+        gizmos.sphere(isometry, settings.axis_length * 0.3, css::WHITE);
+    }
+}
+
+fn tracking_color(flags: &XrSpaceLocationFlags) -> Srgba {
+    if flags.fully_tracked() {
+        css::LIME
+    } else if flags.position_valid && flags.orientation_valid {
+        css::YELLOW
+    } else {
+        css::RED
+    }
+}
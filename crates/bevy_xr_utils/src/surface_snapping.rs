@@ -0,0 +1,143 @@
+//! Helpers for placing objects on real world surfaces, e.g. placing a virtual object on a real table in AR.
+//!
+//! Add [`XrPlacementSurface`] to any entity representing a flat surface, e.g. a detected scene plane,
+//! then use [`snap_to_nearest_surface`] with a ray from a controller's aim pose to find where to place an object.
+use bevy::prelude::*;
+
+/// A flat rectangular surface objects can be placed on.
+///
+/// The surface lies in the local XZ plane of the entity's [`GlobalTransform`] with its normal pointing along local +Y.
+/// Only the side the normal points out of can be hit.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+#[require(Transform)]
+pub struct XrPlacementSurface {
+    /// Half of the surface's extents along local X and Z. Use [`Vec2::INFINITY`] for an unbounded plane.
+    pub half_size: Vec2,
+}
+
+impl XrPlacementSurface {
+    pub const fn new(half_size: Vec2) -> Self {
+        Self { half_size }
+    }
+
+    pub const fn unbounded() -> Self {
+        Self {
+            half_size: Vec2::INFINITY,
+        }
+    }
+}
+
+/// Casts `ray` against all `surfaces` and returns a placement pose on the nearest surface that was hit within `max_distance`.
+///
+/// The returned [`Transform`] is positioned at the hit point with its up axis aligned to the surface normal,
+/// and its forward axis pointing back towards the ray origin so that placed objects face the user.
+/// Returns [`None`] when no surface is hit, so the app can show a "no surface" state.
+///
+/// To cast from a controller, use `Ray3d::new(aim.translation, aim.forward())` where `aim` is the aim pose in world space.
+pub fn snap_to_nearest_surface<'a>(
+    ray: Ray3d,
+    max_distance: f32,
+    surfaces: impl IntoIterator<Item = (&'a GlobalTransform, &'a XrPlacementSurface)>,
+) -> Option<Transform> {
+    let mut nearest: Option<(f32, Vec3, Dir3)> = None;
+    for (transform, surface) in surfaces {
+        let (_, rotation, origin) = transform.to_scale_rotation_translation();
+        let Ok(normal) = Dir3::new(rotation * Vec3::Y) else {
+            continue;
+        };
+        // only hit the front face of the surface
+        if normal.dot(*ray.direction) >= 0.0 {
+            continue;
+        }
+        let Some(distance) = ray.intersect_plane(origin, InfinitePlane3d::new(normal)) else {
+            continue;
+        };
+        if distance > max_distance || nearest.is_some_and(|(d, _, _)| d <= distance) {
+            continue;
+        }
+        let point = ray.get_point(distance);
+        let local = transform.affine().inverse().transform_point3(point);
+        if local.x.abs() > surface.half_size.x || local.z.abs() > surface.half_size.y {
+            continue;
+        }
+        nearest = Some((distance, point, normal));
+    }
+
+    let (_, point, normal) = nearest?;
+    let towards_origin = ray.origin - point;
+    let forward = towards_origin - normal * normal.dot(towards_origin);
+    let transform = Transform::from_translation(point);
+    Some(match Dir3::new(forward) {
+        Ok(forward) => transform.looking_to(forward, normal),
+        Err(_) => transform.with_rotation(Quat::from_rotation_arc(Vec3::Y, *normal)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn floor() -> (GlobalTransform, XrPlacementSurface) {
+        (
+            GlobalTransform::IDENTITY,
+            XrPlacementSurface::new(Vec2::splat(1.0)),
+        )
+    }
+
+    fn table() -> (GlobalTransform, XrPlacementSurface) {
+        (
+            GlobalTransform::from_translation(Vec3::new(0.0, 0.8, 0.0)),
+            XrPlacementSurface::new(Vec2::splat(0.5)),
+        )
+    }
+
+    fn snap(ray: Ray3d, surfaces: &[(GlobalTransform, XrPlacementSurface)]) -> Option<Transform> {
+        snap_to_nearest_surface(ray, 10.0, surfaces.iter().map(|(t, s)| (t, s)))
+    }
+
+    #[test]
+    fn snaps_to_the_nearest_surface() {
+        let ray = Ray3d::new(
+            Vec3::new(0.0, 2.0, 1.0),
+            Dir3::new(Vec3::new(0.0, -2.0, -1.0)).unwrap(),
+        );
+        let placed = snap(ray, &[floor(), table()]).unwrap();
+        assert!(placed
+            .translation
+            .abs_diff_eq(Vec3::new(0.0, 0.8, 0.4), 1e-5));
+        assert!(placed.up().abs_diff_eq(Vec3::Y, 1e-5));
+        // faces back towards the ray origin
+        assert!(placed.forward().abs_diff_eq(Vec3::Z, 1e-5));
+    }
+
+    #[test]
+    fn misses_outside_of_the_surface_bounds() {
+        let ray = Ray3d::new(Vec3::new(0.75, 2.0, 0.0), Dir3::NEG_Y);
+        let placed = snap(ray, &[floor(), table()]).unwrap();
+        assert!(placed
+            .translation
+            .abs_diff_eq(Vec3::new(0.75, 0.0, 0.0), 1e-5));
+        assert!(snap(ray, &[table()]).is_none());
+        assert!(snap(
+            ray,
+            &[(GlobalTransform::IDENTITY, XrPlacementSurface::unbounded())]
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn ignores_back_faces_and_far_surfaces() {
+        let from_below = Ray3d::new(Vec3::new(0.0, -1.0, 0.0), Dir3::Y);
+        assert!(snap(from_below, &[floor()]).is_none());
+        let ray = Ray3d::new(Vec3::new(0.0, 2.0, 0.0), Dir3::NEG_Y);
+        assert!(snap_to_nearest_surface(ray, 1.0, [(&floor().0, &floor().1)]).is_none());
+    }
+
+    #[test]
+    fn straight_down_ray_aligns_up_with_the_normal() {
+        let ray = Ray3d::new(Vec3::new(0.0, 2.0, 0.0), Dir3::NEG_Y);
+        let placed = snap(ray, &[floor()]).unwrap();
+        assert!(placed.translation.abs_diff_eq(Vec3::ZERO, 1e-5));
+        assert!(placed.up().abs_diff_eq(Vec3::Y, 1e-5));
+    }
+}